@@ -0,0 +1,49 @@
+-- 006_variation_conditions.sql
+-- Convert alert variation conditions to expressions.
+--
+-- Variations used to match on one of three fixed condition types:
+--   tier    eventData.tier equals condition_value
+--   amount  eventData.amount >= condition_value
+--   custom  eventData.custom_value equals condition_value
+--
+-- They now use condition_type 'expression' with condition_value holding an
+-- expression such as `amount between 100 and 499` (grammar documented in
+-- server/alerts/conditions.js). Each legacy row is rewritten to the
+-- equivalent expression; string values are double-quoted with `\` and `"`
+-- escaped.
+
+-- ---------------------------------------------------------------------------
+-- tier = 2  ->  tier == "2"
+-- ---------------------------------------------------------------------------
+
+UPDATE alert_variations SET
+  condition_type = 'expression',
+  condition_value = 'tier == "' || replace(replace(condition_value, '\', '\\'), '"', '\"') || '"'
+WHERE condition_type = 'tier';
+
+-- ---------------------------------------------------------------------------
+-- amount = 100  ->  amount >= 100
+-- Non-numeric thresholds never matched and are kept as strings, which
+-- still never match.
+-- ---------------------------------------------------------------------------
+
+UPDATE alert_variations SET
+  condition_type = 'expression',
+  condition_value = CASE
+    WHEN trim(condition_value) GLOB '[0-9]*'
+      AND trim(condition_value) NOT GLOB '*[^0-9.]*'
+      AND trim(condition_value) NOT GLOB '*.*.*'
+      AND trim(condition_value) NOT GLOB '*.'
+    THEN 'amount >= ' || trim(condition_value)
+    ELSE 'amount >= "' || replace(replace(condition_value, '\', '\\'), '"', '\"') || '"'
+  END
+WHERE condition_type = 'amount';
+
+-- ---------------------------------------------------------------------------
+-- custom = vip  ->  custom_value == "vip"
+-- ---------------------------------------------------------------------------
+
+UPDATE alert_variations SET
+  condition_type = 'expression',
+  condition_value = 'custom_value == "' || replace(replace(condition_value, '\', '\\'), '"', '\"') || '"'
+WHERE condition_type = 'custom';
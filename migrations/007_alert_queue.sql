@@ -0,0 +1,28 @@
+-- 007_alert_queue.sql
+-- Durable journal of the alert queue, owned by the Tauri shell.
+--
+-- The server keeps its queue in memory and reports every transition over
+-- the control protocol (see server/utils/control.js); the shell records
+-- them here:
+--   enqueued  waiting in the server's queue
+--   started   playing on the overlay
+--   done      finished (confirmed by the overlay or its fallback timer)
+--   skipped   skipped, cleared, or given up on after too many replays
+--
+-- Rows still enqueued or started when the sidecar exits are sent back to
+-- the next sidecar on startup. payload holds the alert object exactly as
+-- the server queued it (id, type, username, config, ...).
+
+CREATE TABLE IF NOT EXISTS alert_queue (
+  id          TEXT PRIMARY KEY,
+  alert_type  TEXT NOT NULL,
+  username    TEXT NOT NULL,
+  payload     TEXT NOT NULL,
+  status      TEXT NOT NULL DEFAULT 'enqueued'
+              CHECK (status IN ('enqueued', 'started', 'done', 'skipped')),
+  attempts    INTEGER NOT NULL DEFAULT 1,
+  enqueued_at TEXT NOT NULL,
+  updated_at  TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_queue_status ON alert_queue(status, enqueued_at);
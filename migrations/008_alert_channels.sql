@@ -0,0 +1,30 @@
+-- 008_alert_channels.sql
+-- Named alert channels, each with its own queue and pause state on the
+-- server, so different overlays (OBS scenes) can play different alerts at
+-- the same time.
+--
+-- An overlay picks its channel with the browser source URL, e.g.
+--   http://localhost:3000/overlays/alerts/?channel=big-events
+-- and one without ?channel uses 'default'.
+--
+-- Routing: an alert goes to every enabled channel whose alert_types (a
+-- JSON array such as '["cheer","raid"]') lists its type. Types no channel
+-- claims go to 'default', which always exists.
+--
+-- Channels are managed by the Tauri shell; the server reloads them when
+-- told to over the control protocol.
+
+CREATE TABLE IF NOT EXISTS alert_channels (
+  id          TEXT PRIMARY KEY,
+  name        TEXT NOT NULL,
+  alert_types TEXT NOT NULL DEFAULT '[]',
+  enabled     INTEGER NOT NULL DEFAULT 1,
+  created_at  TEXT NOT NULL,
+  updated_at  TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO alert_channels (id, name, alert_types, enabled, created_at, updated_at)
+VALUES ('default', 'Default', '[]', 1, datetime('now'), datetime('now'));
+
+-- The durable queue journal records which channel each alert was queued on
+ALTER TABLE alert_queue ADD COLUMN channel TEXT NOT NULL DEFAULT 'default';
@@ -5,6 +5,10 @@
  * renders styled alert elements with entry/exit animations, plays
  * sounds via Howler.js, and emits alert:done when complete.
  *
+ * The alert channel is picked with ?channel= on the browser source URL
+ * (e.g. /overlays/alerts/?channel=big-events); without it the overlay
+ * plays the 'default' channel.
+ *
  * Depends on:
  *   - StreamForge.connect() from ../shared/socket-client.js
  *   - Animations.playEntry() / Animations.playExit() from animations.js
@@ -14,6 +18,7 @@
  * Server payload shape (from server/alerts/queue.js):
  *   {
  *     id, type, username, displayName, amount, message, timestamp,
+ *     text,  (message_template rendered by server/alerts/messageTemplates.js)
  *     config: {
  *       message_template, duration_ms, animation_in, animation_out,
  *       sound_path, sound_volume, image_path, font_family, font_size,
@@ -39,26 +44,15 @@
   /** Counter for unique alert element IDs */
   var alertCounter = 0;
 
-  // -----------------------------------------------------------------------
-  // Template Processing
-  // -----------------------------------------------------------------------
+  /** Ends the display wait of the alert on screen early (set while waiting) */
+  var skipCurrent = null;
 
-  /**
-   * Replace {username}, {amount}, {message} placeholders in a template.
-   * Escapes HTML to prevent XSS from user-generated content.
-   *
-   * @param {string} template - Message template string
-   * @param {object} data - Replacement values
-   * @returns {string} Processed string (HTML-safe)
-   */
-  function processTemplate(template, data) {
-    if (!template) return '';
+  /** Server ID of the alert on screen */
+  var currentAlertId = null;
 
-    return template
-      .replace(/\{username\}/g, escapeHtml(data.username || ''))
-      .replace(/\{amount\}/g, escapeHtml(String(data.amount || '')))
-      .replace(/\{message\}/g, escapeHtml(data.message || ''));
-  }
+  // -----------------------------------------------------------------------
+  // HTML Escaping
+  // -----------------------------------------------------------------------
 
   /**
    * Escape HTML special characters to prevent XSS.
@@ -204,7 +198,6 @@
 
     var config = alertData.config || {};
     var username = alertData.displayName || alertData.username || 'Someone';
-    var amount = alertData.amount;
     var message = alertData.message || '';
     var type = alertData.type || 'follow';
 
@@ -233,12 +226,9 @@
       html += '<img src="' + escapeHtml(config.image_path) + '" class="alert-image" alt="Alert">';
     }
 
-    // Processed message text
-    var text = processTemplate(config.message_template || '{username}', {
-      username: username,
-      amount: amount,
-      message: message
-    });
+    // Message text, rendered from the template by the server. Payloads
+    // emitted without the queue have none and show the username.
+    var text = alertData.text != null ? String(alertData.text) : username;
 
     // Text element with inline styling from config
     var fontFamily = config.font_family || 'Poppins';
@@ -249,7 +239,7 @@
       + 'font-family: ' + escapeHtml(fontFamily) + ', sans-serif; '
       + 'font-size: ' + fontSize + 'px; '
       + 'color: ' + escapeHtml(textColor) + ';'
-      + '">' + text + '</div>';
+      + '">' + escapeHtml(text) + '</div>';
 
     alertEl.innerHTML = html;
 
@@ -283,11 +273,16 @@
     // messages while still keeping the alert visible during TTS.
     var duration = config.duration_ms || 5000;
     // Speak the rendered template text (what's visible on screen), not just
-    // the raw {message} variable. The `text` variable contains the processed
-    // template with HTML escaping — strip any HTML tags for clean TTS input.
-    var ttsText = text ? text.replace(/<[^>]*>/g, '') : message;
+    // the raw {message} variable.
+    var ttsText = text || message;
     var ttsPromise = speakMessage(ttsText, config);
-    await Promise.all([ttsPromise, sleep(duration)]);
+    var skipped = new Promise(function (resolve) {
+      skipCurrent = resolve;
+    });
+    currentAlertId = alertData.id || null;
+    await Promise.race([Promise.all([ttsPromise, sleep(duration)]), skipped]);
+    skipCurrent = null;
+    currentAlertId = null;
 
     // --- Exit Animation ---
     var animOut = config.animation_out || 'fadeOut';
@@ -338,7 +333,8 @@
       return;
     }
 
-    client = StreamForge.connect('/alerts');
+    var channel = new URLSearchParams(window.location.search).get('channel');
+    client = StreamForge.connect('/alerts', channel ? { query: { channel: channel } } : undefined);
 
     if (!client) {
       console.error('[Alerts] Failed to connect to /alerts namespace.');
@@ -347,7 +343,7 @@
 
     // Welcome message from server
     client.on('welcome', function (data) {
-      console.log('[Alerts] ' + data.message);
+      console.log('[Alerts] ' + data.message + ' (channel: ' + (channel || 'default') + ')');
     });
 
     // Listen for alert triggers
@@ -356,6 +352,22 @@
       displayAlert(alertData);
     });
 
+    // The server skipped the alert on screen -- cut it short
+    client.on('alert:skip', function (data) {
+      console.log('[Alerts] Received alert:skip:', data);
+      if (!skipCurrent || (data && data.alertId && data.alertId !== currentAlertId)) {
+        return;
+      }
+      if (currentSound) {
+        currentSound.stop();
+        currentSound = null;
+      }
+      if (typeof window.ttsManager !== 'undefined') {
+        window.ttsManager.stop();
+      }
+      skipCurrent();
+    });
+
     // Listen for pause/resume (future use)
     client.on('alert:paused', function (data) {
       console.log('[Alerts] Pause state:', data.paused);
@@ -0,0 +1,371 @@
+/**
+ * StreamForge — Variation Condition Expressions
+ *
+ * Parses and evaluates the expressions stored in alert_variations rows with
+ * condition_type 'expression', e.g.:
+ *
+ *   tier == 2
+ *   amount between 100 and 499
+ *   message contains "hype" or username in ["alice", "bob"]
+ *   platform == "twitch" and not (amount < 10)
+ *
+ * Grammar:
+ *
+ *   cond     := and ( "or" and )*
+ *   and      := not ( "and" not )*
+ *   not      := "not" not | "(" cond ")" | test
+ *   test     := operand
+ *             | operand ( "==" | "!=" | ">" | ">=" | "<" | "<=" ) operand
+ *             | operand "between" operand "and" operand
+ *             | operand "contains" operand
+ *             | operand ( "in" | "not in" ) "[" operand ( "," operand )* "]"
+ *   operand  := variable | number | string | "true" | "false"
+ *   string   := "..." or '...' with \" \' \\ escapes
+ *   variable := [A-Za-z_][A-Za-z0-9_]*
+ *
+ * Comparisons are numeric when both sides are numbers (numeric strings
+ * count); otherwise ==, !=, contains and in compare text case-insensitively
+ * and the ordering operators are false. `between` is inclusive. A bare
+ * operand is true unless it is missing, empty, 0 or false.
+ *
+ * The Tauri shell has the same language in src-tauri/src/condition.rs for
+ * validating expressions in the editor; keep the two in step.
+ *
+ * Usage:
+ *   const conditions = require('./conditions');
+ *   conditions.evaluate('amount >= 100', { amount: 250 }); // true
+ */
+
+const KEYWORDS = ['and', 'or', 'not', 'between', 'contains', 'in', 'true', 'false'];
+const OPERATORS = ['==', '!=', '>=', '<=', '>', '<'];
+const PUNCTUATION = { '(': 'open', ')': 'close', '[': 'openList', ']': 'closeList', ',': 'comma' };
+
+/**
+ * Numeric strings, as Rust's f64 parser reads them. Number() would also
+ * take hex, binary and "Infinity", which the shell treats as text.
+ */
+const DECIMAL = /^[+-]?([0-9]+\.?[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?$/;
+
+/** @type {Map<string, object>} Parsed expressions by source text */
+const cache = new Map();
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+/**
+ * Split an expression into tokens.
+ *
+ * @param {string} text - Expression source
+ * @returns {object[]} Tokens ({ kind, value })
+ */
+function tokenize(text) {
+  const tokens = [];
+  let i = 0;
+
+  while (i < text.length) {
+    const c = text[i];
+
+    if (/\s/.test(c)) {
+      i++;
+      continue;
+    }
+
+    if (PUNCTUATION[c]) {
+      tokens.push({ kind: PUNCTUATION[c] });
+      i++;
+      continue;
+    }
+
+    const op = OPERATORS.find((o) => text.startsWith(o, i));
+    if (op) {
+      tokens.push({ kind: 'op', value: op });
+      i += op.length;
+      continue;
+    }
+
+    if (c === '"' || c === "'") {
+      let value = '';
+      let closed = false;
+      i++;
+      while (i < text.length) {
+        const next = text[i++];
+        if (next === '\\') {
+          if (i < text.length) value += text[i++];
+        } else if (next === c) {
+          closed = true;
+          break;
+        } else {
+          value += next;
+        }
+      }
+      if (!closed) throw new Error('Unclosed string');
+      tokens.push({ kind: 'text', value });
+      continue;
+    }
+
+    const isNumber = /[0-9]/.test(c) || (c === '-' && /[0-9]/.test(text[i + 1] || ''));
+    if (isNumber || /[A-Za-z_]/.test(c)) {
+      const pattern = isNumber ? /-?[0-9.]*/y : /[A-Za-z_][A-Za-z0-9_]*/y;
+      pattern.lastIndex = i;
+      const word = pattern.exec(text)[0];
+      i += word.length;
+      if (isNumber) {
+        if (!/^-?[0-9]+(\.[0-9]*)?$/.test(word)) throw new Error(`Invalid number "${word}"`);
+        tokens.push({ kind: 'number', value: Number(word) });
+      } else {
+        tokens.push({ kind: 'word', value: word });
+      }
+      continue;
+    }
+
+    throw new Error(`Unexpected "${c}"`);
+  }
+
+  return tokens;
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/**
+ * Parse an expression into a tree of plain objects.
+ *
+ * @param {string} text - Expression source
+ * @returns {object} Expression tree
+ * @throws {Error} On syntax errors
+ */
+function parse(text) {
+  const tokens = tokenize(String(text));
+  if (tokens.length === 0) throw new Error('Empty condition');
+  let pos = 0;
+
+  const peek = (offset = 0) => tokens[pos + offset];
+  const isKeyword = (offset, keyword) => {
+    const token = peek(offset);
+    return !!token && token.kind === 'word' && token.value === keyword;
+  };
+  const keyword = (word) => {
+    if (!isKeyword(0, word)) return false;
+    pos++;
+    return true;
+  };
+  const expect = (kind, what) => {
+    if (!peek() || peek().kind !== kind) throw new Error(`Expected ${what}`);
+    pos++;
+  };
+
+  function or() {
+    let expr = and();
+    while (keyword('or')) expr = { op: 'or', a: expr, b: and() };
+    return expr;
+  }
+
+  function and() {
+    let expr = not();
+    while (keyword('and')) expr = { op: 'and', a: expr, b: not() };
+    return expr;
+  }
+
+  function not() {
+    if (keyword('not')) return { op: 'not', a: not() };
+    if (peek() && peek().kind === 'open') {
+      pos++;
+      const expr = or();
+      expect('close', '")"');
+      return expr;
+    }
+    return test();
+  }
+
+  function test() {
+    const left = operand();
+    const token = peek();
+
+    if (token && token.kind === 'op') {
+      pos++;
+      return { op: token.value, a: left, b: operand() };
+    }
+    if (keyword('between')) {
+      const low = operand();
+      if (!keyword('and')) throw new Error('Expected "and" in "between ... and ..."');
+      return { op: 'between', a: left, low, high: operand() };
+    }
+    if (keyword('contains')) return { op: 'contains', a: left, b: operand() };
+    if (isKeyword(0, 'not') && isKeyword(1, 'in')) {
+      pos += 2;
+      return { op: 'not', a: { op: 'in', a: left, items: list() } };
+    }
+    if (keyword('in')) return { op: 'in', a: left, items: list() };
+    return { op: 'truthy', a: left };
+  }
+
+  function list() {
+    expect('openList', '"[" to start a list');
+    const items = [];
+    if (peek() && peek().kind === 'closeList') {
+      pos++;
+      return items;
+    }
+    for (;;) {
+      items.push(operand());
+      const token = peek();
+      if (token && token.kind === 'comma') {
+        pos++;
+      } else if (token && token.kind === 'closeList') {
+        pos++;
+        return items;
+      } else {
+        throw new Error('Expected "," or "]"');
+      }
+    }
+  }
+
+  function operand() {
+    const token = peek();
+    if (!token) throw new Error('Incomplete condition');
+    pos++;
+    switch (token.kind) {
+      case 'word':
+        if (token.value === 'true' || token.value === 'false') {
+          return { literal: token.value === 'true' };
+        }
+        if (KEYWORDS.includes(token.value)) {
+          throw new Error(`Expected a value, found "${token.value}"`);
+        }
+        return { variable: token.value };
+      case 'number':
+      case 'text':
+        return { literal: token.value };
+      default:
+        throw new Error('Expected a variable, number or string');
+    }
+  }
+
+  const expr = or();
+  if (pos < tokens.length) throw new Error('Unexpected text after the condition');
+  return expr;
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+/** A value as a number, or null: numbers and numeric strings. */
+function asNumber(value) {
+  if (typeof value === 'number') return Number.isFinite(value) ? value : null;
+  if (typeof value === 'string' && DECIMAL.test(value.trim())) {
+    const n = Number(value.trim());
+    return Number.isFinite(n) ? n : null;
+  }
+  return null;
+}
+
+/** A value as lowercase text; missing and null are empty. */
+function asText(value) {
+  if (value === undefined || value === null) return '';
+  if (typeof value === 'object') return JSON.stringify(value).toLowerCase();
+  return String(value).toLowerCase();
+}
+
+function truthy(value) {
+  if (value === undefined || value === null) return false;
+  if (typeof value === 'string') return !(value === '' || value === '0' || value.toLowerCase() === 'false');
+  if (Array.isArray(value)) return value.length > 0;
+  if (typeof value === 'object') return Object.keys(value).length > 0;
+  return !!value;
+}
+
+function equals(a, b) {
+  const [x, y] = [asNumber(a), asNumber(b)];
+  if (x !== null && y !== null) return x === y;
+  return asText(a) === asText(b);
+}
+
+function evaluateNode(node, data) {
+  // Own properties only, so `constructor` or `__proto__` read as missing
+  const value = (operand) => {
+    if (!('variable' in operand)) return operand.literal;
+    return Object.prototype.hasOwnProperty.call(data, operand.variable) ? data[operand.variable] : undefined;
+  };
+  const ordered = (compare) => {
+    const [x, y] = [asNumber(value(node.a)), asNumber(value(node.b))];
+    return x !== null && y !== null && compare(x, y);
+  };
+
+  switch (node.op) {
+    case 'or': return evaluateNode(node.a, data) || evaluateNode(node.b, data);
+    case 'and': return evaluateNode(node.a, data) && evaluateNode(node.b, data);
+    case 'not': return !evaluateNode(node.a, data);
+    case 'truthy': return truthy(value(node.a));
+    case '==': return equals(value(node.a), value(node.b));
+    case '!=': return !equals(value(node.a), value(node.b));
+    case '>': return ordered((x, y) => x > y);
+    case '>=': return ordered((x, y) => x >= y);
+    case '<': return ordered((x, y) => x < y);
+    case '<=': return ordered((x, y) => x <= y);
+    case 'between': {
+      const [x, low, high] = [value(node.a), value(node.low), value(node.high)].map(asNumber);
+      return x !== null && low !== null && high !== null && low <= x && x <= high;
+    }
+    case 'contains': {
+      const haystack = value(node.a);
+      const needle = value(node.b);
+      if (Array.isArray(haystack)) return haystack.some((item) => equals(item, needle));
+      return asText(haystack).includes(asText(needle));
+    }
+    case 'in': {
+      const needle = value(node.a);
+      return node.items.some((item) => equals(needle, value(item)));
+    }
+    default:
+      return false;
+  }
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/**
+ * Evaluate an expression against event data. Invalid expressions never
+ * match (and are logged once).
+ *
+ * @param {string} expression - Condition expression
+ * @param {object} data - Event data (username, amount, message, tier, ...)
+ * @returns {boolean} True if the event matches
+ */
+function evaluate(expression, data = {}) {
+  let parsed = cache.get(expression);
+  if (parsed === undefined) {
+    try {
+      parsed = parse(expression);
+    } catch (err) {
+      console.warn(`[Alerts] Invalid variation condition "${expression}": ${err.message}`);
+      parsed = null;
+    }
+    cache.set(expression, parsed);
+  }
+  return parsed !== null && evaluateNode(parsed, data);
+}
+
+/**
+ * Check an expression for syntax errors.
+ *
+ * @param {string} expression - Condition expression
+ * @returns {string|null} The error message, or null if valid
+ */
+function validate(expression) {
+  try {
+    parse(expression);
+    return null;
+  } catch (err) {
+    return err.message;
+  }
+}
+
+module.exports = {
+  evaluate,
+  validate,
+};
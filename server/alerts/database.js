@@ -9,6 +9,7 @@
 
 const { v4: uuidv4 } = require('uuid');
 const { getDb } = require('../database');
+const conditions = require('./conditions');
 
 // ---------------------------------------------------------------------------
 // Alert Column Definitions
@@ -461,10 +462,14 @@ function findMatchingAlert(eventType, eventData = {}) {
  * Check if a variation's condition matches the event data.
  *
  * Condition types:
+ *   - 'expression': condition_value is an expression such as
+ *                   `amount between 100 and 499` (see ./conditions.js)
+ *
+ * Rows written before migration 006 (which converts them) or through the
+ * API by older clients may still use the legacy types:
  *   - 'tier':   matches eventData.tier (e.g. '1', '2', '3')
  *   - 'amount': matches if eventData.amount >= condition_value
- *   - 'custom': matches if eventData[condition_type_key] === condition_value
- *               (for extensibility)
+ *   - 'custom': matches if eventData.custom_value === condition_value
  *
  * @param {object} variation - The variation record
  * @param {object} eventData - The event data to match against
@@ -474,6 +479,9 @@ function doesVariationMatch(variation, eventData) {
   const { condition_type, condition_value } = variation;
 
   switch (condition_type) {
+    case 'expression':
+      return conditions.evaluate(condition_value, eventData);
+
     case 'tier':
       // Match exact tier value (e.g. '1', '2', '3')
       return String(eventData.tier) === String(condition_value);
@@ -523,6 +531,37 @@ function mergeVariation(alert, variation) {
   return merged;
 }
 
+// ---------------------------------------------------------------------------
+// Alert Channels
+// ---------------------------------------------------------------------------
+
+/**
+ * Get the enabled alert channels, with alert_types parsed into an array.
+ * Channels are managed by the Tauri shell; the server only reads them.
+ *
+ * @returns {{ id: string, name: string, alert_types: string[] }[]}
+ */
+function getAlertChannels() {
+  const db = getDb();
+  try {
+    return db
+      .prepare('SELECT id, name, alert_types FROM alert_channels WHERE enabled = 1 ORDER BY created_at ASC')
+      .all()
+      .map((row) => {
+        let types = [];
+        try {
+          types = JSON.parse(row.alert_types);
+        } catch (_) {
+          console.warn(`[Alerts DB] Ignoring invalid alert_types on channel ${row.id}`);
+        }
+        return { ...row, alert_types: Array.isArray(types) ? types : [] };
+      });
+  } catch (err) {
+    console.error('[Alerts DB] Error fetching alert channels:', err.message);
+    throw err;
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Exports
 // ---------------------------------------------------------------------------
@@ -546,4 +585,7 @@ module.exports = {
 
   // Alert Matching
   findMatchingAlert,
+
+  // Alert Channels
+  getAlertChannels,
 };
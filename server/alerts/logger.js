@@ -3,7 +3,8 @@
  *
  * Logs every alert event to the event_log table in SQLite and provides
  * query helpers for the History dashboard page. Also handles auto-pruning
- * of records older than 7 days.
+ * of records older than 7 days. Every logged event is forwarded to the
+ * Tauri shell over the control protocol.
  *
  * Usage:
  *   const logger = require('./alerts/logger');
@@ -13,6 +14,7 @@
 
 const { v4: uuidv4 } = require('uuid');
 const database = require('../database');
+const control = require('../utils/control');
 
 // ---------------------------------------------------------------------------
 // Logging
@@ -30,6 +32,7 @@ const database = require('../database');
  * @param {string}  [eventData.message=null]          - Associated message
  * @param {object}  [eventData.metadata={}]           - Platform-specific extra data
  * @param {number}  [eventData.alert_fired=0]         - 1 if an alert was triggered
+ * @param {string}  [eventData.timestamp]             - When the event happened (defaults to now)
  * @returns {object|null} The created log record, or null on failure
  */
 function logEvent(eventData) {
@@ -42,6 +45,7 @@ function logEvent(eventData) {
     message = null,
     metadata = {},
     alert_fired = 0,
+    timestamp = new Date().toISOString(),
   } = eventData;
 
   const log = {
@@ -54,7 +58,7 @@ function logEvent(eventData) {
     message,
     metadata: JSON.stringify(metadata),
     alert_fired,
-    timestamp: new Date().toISOString(),
+    timestamp,
   };
 
   try {
@@ -62,6 +66,8 @@ function logEvent(eventData) {
     console.log(
       `[Logger] Logged event: ${event_type} by ${username} (platform: ${platform}, alert: ${alert_fired ? 'yes' : 'no'})`
     );
+    // Feed the event to the desktop shell (native notifications)
+    control.sendStreamEvent(log);
     return log;
   } catch (error) {
     console.error('[Logger] Failed to log event:', error.message);
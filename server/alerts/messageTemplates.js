@@ -0,0 +1,590 @@
+/**
+ * StreamForge — Alert Message Templates
+ *
+ * Renders the message_template of an alert before it is sent to the
+ * overlay, e.g.:
+ *
+ *   {username|upper} cheered {amount|number} bit{amount|plural}!
+ *   {#if amount >= 1000}HYPE!{#elif amount >= 100}Nice!{/if}
+ *
+ * Grammar (whitespace inside tags is ignored):
+ *
+ *   template  := ( text | "{{" | "}}" | value | if )*
+ *   value     := "{" variable ( "|" filter )* "}"
+ *   filter    := name ( ":" arg ( "," arg )* )?
+ *   arg       := bare word | "double-quoted string"
+ *   if        := "{#if " cond "}" template
+ *                ( "{#elif " cond "}" template )*
+ *                ( "{#else}" template )?
+ *                "{/if}"
+ *   cond      := and ( "or" and )*
+ *   and       := not ( "and" not )*
+ *   not       := "not" not | "(" cond ")" | compare
+ *   compare   := operand ( ( "==" | "!=" | ">" | ">=" | "<" | "<=" ) operand )?
+ *   operand   := variable | number | "double-quoted string"
+ *   variable  := [A-Za-z_][A-Za-z0-9_]*
+ *
+ * `{{` and `}}` are literal braces. Missing variables render as empty text.
+ * Comparisons are numeric when both sides are numbers (numeric strings
+ * count); otherwise == and != compare text case-sensitively and the
+ * ordering operators are false. A bare operand is true unless it is
+ * missing, empty, 0 or false. Operands are read up to the next space,
+ * bracket or operator, so -5 and 1.5 are numbers and user.name is an
+ * invalid name.
+ *
+ * Filters: upper, lower, capitalize, number[:decimals], currency[:CODE],
+ * plural[:one[,other]], default:text, truncate:length.
+ *
+ * The Tauri shell has the same language in src-tauri/src/message_template.rs
+ * for checking templates in the editor; keep the two in step.
+ *
+ * Usage:
+ *   const messageTemplates = require('./messageTemplates');
+ *   messageTemplates.render('{username|upper}!', { username: 'alice' }); // 'ALICE!'
+ */
+
+const KEYWORDS = ['and', 'or', 'not'];
+const OPERATORS = ['==', '!=', '>=', '<=', '>', '<'];
+const FILTERS = ['upper', 'lower', 'capitalize', 'number', 'currency', 'plural', 'default', 'truncate'];
+
+/**
+ * Numeric strings, as Rust's f64 parser reads them. Number() would also
+ * take hex, binary and "Infinity", which the shell treats as text.
+ */
+const DECIMAL = /^[+-]?([0-9]+\.?[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?$/;
+
+/** Symbol and decimals of the currencies `currency` knows by name */
+const CURRENCIES = {
+  USD: ['$', 2],
+  EUR: ['€', 2],
+  GBP: ['£', 2],
+  JPY: ['¥', 0],
+  CAD: ['CA$', 2],
+  AUD: ['A$', 2],
+  BRL: ['R$', 2],
+  INR: ['₹', 2],
+};
+
+/** @type {Map<string, object[]|null>} Parsed templates by source text */
+const cache = new Map();
+
+function isVariable(name) {
+  return /^[A-Za-z_][A-Za-z0-9_]*$/.test(name);
+}
+
+// ---------------------------------------------------------------------------
+// Conditions
+// ---------------------------------------------------------------------------
+
+/**
+ * Split the condition of an {#if} tag into tokens.
+ *
+ * @param {string} text - Condition source
+ * @returns {object[]} Tokens ({ kind, value })
+ */
+function tokenize(text) {
+  const tokens = [];
+  let i = 0;
+
+  while (i < text.length) {
+    const c = text[i];
+
+    if (/\s/.test(c)) {
+      i++;
+      continue;
+    }
+
+    if (c === '(' || c === ')') {
+      tokens.push({ kind: c === '(' ? 'open' : 'close' });
+      i++;
+      continue;
+    }
+
+    const op = OPERATORS.find((o) => text.startsWith(o, i));
+    if (op) {
+      tokens.push({ kind: 'op', value: op });
+      i += op.length;
+      continue;
+    }
+
+    if (c === '"') {
+      const end = text.indexOf('"', i + 1);
+      if (end === -1) throw new Error('Unclosed string');
+      tokens.push({ kind: 'text', value: text.slice(i + 1, end) });
+      i = end + 1;
+      continue;
+    }
+
+    const pattern = /[A-Za-z0-9_.-]*/y;
+    pattern.lastIndex = i;
+    const word = pattern.exec(text)[0];
+    if (word === '') throw new Error(`Unexpected "${c}"`);
+    i += word.length;
+    if (DECIMAL.test(word) && Number.isFinite(Number(word))) {
+      tokens.push({ kind: 'number', value: Number(word) });
+    } else if (isVariable(word)) {
+      tokens.push({ kind: 'word', value: word });
+    } else {
+      throw new Error(`Invalid name "${word}"`);
+    }
+  }
+
+  return tokens;
+}
+
+/**
+ * Parse the condition of an {#if} or {#elif} tag.
+ *
+ * @param {string} text - Everything after "#if" or "#elif"
+ * @returns {object} Condition tree
+ * @throws {Error} On syntax errors
+ */
+function parseCondition(text) {
+  if (text.trim() === '') throw new Error('Missing condition');
+  if (!/^\s/.test(text)) throw new Error('Expected a space before the condition');
+
+  const tokens = tokenize(text);
+  let pos = 0;
+
+  const peek = () => tokens[pos];
+  const keyword = (word) => {
+    const token = peek();
+    if (!token || token.kind !== 'word' || token.value !== word) return false;
+    pos++;
+    return true;
+  };
+
+  function or() {
+    let cond = and();
+    while (keyword('or')) cond = { op: 'or', a: cond, b: and() };
+    return cond;
+  }
+
+  function and() {
+    let cond = not();
+    while (keyword('and')) cond = { op: 'and', a: cond, b: not() };
+    return cond;
+  }
+
+  function not() {
+    if (keyword('not')) return { op: 'not', a: not() };
+    if (peek() && peek().kind === 'open') {
+      pos++;
+      const cond = or();
+      if (!peek() || peek().kind !== 'close') throw new Error('Expected ")"');
+      pos++;
+      return cond;
+    }
+    const left = operand();
+    const token = peek();
+    if (token && token.kind === 'op') {
+      pos++;
+      return { op: token.value, a: left, b: operand() };
+    }
+    return { op: 'truthy', a: left };
+  }
+
+  function operand() {
+    const token = peek();
+    if (!token) throw new Error('Incomplete condition');
+    pos++;
+    switch (token.kind) {
+      case 'word':
+        if (KEYWORDS.includes(token.value)) {
+          throw new Error(`Expected a value, found "${token.value}"`);
+        }
+        return { variable: token.value };
+      case 'number':
+      case 'text':
+        return { literal: token.value };
+      default:
+        throw new Error('Expected a variable, number or string');
+    }
+  }
+
+  const cond = or();
+  if (pos < tokens.length) throw new Error('Unexpected text after condition');
+  return cond;
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/**
+ * Split `text` on `separator` outside double quotes.
+ *
+ * @param {string} text
+ * @param {string} separator
+ * @returns {string[]}
+ */
+function splitUnquoted(text, separator) {
+  const parts = [];
+  let start = 0;
+  let quoted = false;
+  for (let i = 0; i < text.length; i++) {
+    if (text[i] === '"') {
+      quoted = !quoted;
+    } else if (text[i] === separator && !quoted) {
+      parts.push(text.slice(start, i));
+      start = i + 1;
+    }
+  }
+  parts.push(text.slice(start));
+  return parts;
+}
+
+/** A filter argument: trimmed, with surrounding quotes removed. */
+function argument(text) {
+  const trimmed = text.trim();
+  if (trimmed.length >= 2 && trimmed.startsWith('"') && trimmed.endsWith('"')) {
+    return trimmed.slice(1, -1);
+  }
+  return trimmed;
+}
+
+/**
+ * Parse one `name:arg,...` filter of a value tag.
+ *
+ * @param {string} text - Trimmed filter source
+ * @returns {object} Filter ({ name, ... })
+ */
+function parseFilter(text) {
+  const colon = text.indexOf(':');
+  const name = colon === -1 ? text : text.slice(0, colon).trim();
+  const args = colon === -1 ? [] : splitUnquoted(text.slice(colon + 1), ',').map(argument);
+
+  const arity = (min, max) => {
+    if (args.length >= min && args.length <= max) return;
+    let expected;
+    if (max === 0) expected = 'no arguments';
+    else if (min === max) expected = `${min} argument(s)`;
+    else expected = `${min} to ${max} arguments`;
+    throw new Error(`"${name}" takes ${expected}`);
+  };
+  const count = (arg) => {
+    if (!/^\+?[0-9]+$/.test(arg)) {
+      throw new Error(`"${name}" expects a whole number, got "${arg}"`);
+    }
+    return Number(arg);
+  };
+
+  switch (name) {
+    case '':
+      throw new Error('Empty filter after "|"');
+    case 'upper':
+    case 'lower':
+    case 'capitalize':
+      arity(0, 0);
+      return { name };
+    case 'number': {
+      arity(0, 1);
+      const decimals = args.length ? count(args[0]) : null;
+      if (decimals !== null && decimals > 10) {
+        throw new Error('"number" supports up to 10 decimals');
+      }
+      return { name, decimals };
+    }
+    case 'currency': {
+      arity(0, 1);
+      const code = args.length ? args[0].toUpperCase() : 'USD';
+      if (!/^[A-Z]{3}$/.test(code)) {
+        throw new Error(`"${code}" is not a currency code like USD`);
+      }
+      return { name, code };
+    }
+    case 'plural': {
+      arity(0, 2);
+      const one = args.length ? args[0] : '';
+      const other = args.length > 1 ? args[1] : `${one}s`;
+      return { name, one, other };
+    }
+    case 'default':
+      arity(1, 1);
+      return { name, fallback: args[0] };
+    case 'truncate':
+      arity(1, 1);
+      return { name, length: count(args[0]) };
+    default:
+      throw new Error(`Unknown filter "${name}" (available: ${FILTERS.join(', ')})`);
+  }
+}
+
+/**
+ * Parse the contents of a `{variable|filter...}` tag.
+ *
+ * @param {string} content - Trimmed tag content
+ * @returns {object} Value node
+ */
+function parseValue(content) {
+  const [first, ...filters] = splitUnquoted(content, '|');
+  const variable = first.trim();
+  if (variable === '') throw new Error('Empty placeholder');
+  if (!isVariable(variable)) throw new Error(`Invalid variable name "${variable}"`);
+  return {
+    kind: 'value',
+    variable,
+    filters: filters.map((text) => parseFilter(text.trim())),
+  };
+}
+
+/**
+ * Parse a template into a list of nodes.
+ *
+ * @param {string} source - Template source
+ * @returns {object[]} Nodes ({ kind: 'text' | 'value' | 'if', ... })
+ * @throws {Error} On syntax errors
+ */
+function parse(source) {
+  let pos = 0;
+
+  /** Read the `{...}` tag at the cursor and return its trimmed content. */
+  function tag() {
+    const open = pos;
+    let quoted = false;
+    for (let i = open + 1; i < source.length; i++) {
+      const c = source[i];
+      if (c === '"') {
+        quoted = !quoted;
+      } else if (c === '{' && !quoted) {
+        break;
+      } else if (c === '}' && !quoted) {
+        pos = i + 1;
+        return source.slice(open + 1, i).trim();
+      }
+    }
+    throw new Error('Unclosed "{" (write "{{" for a literal brace)');
+  }
+
+  /**
+   * Parse nodes until the end of the source or an {#elif}, {#else} or
+   * {/if} tag, which is returned unconsumed.
+   */
+  function nodes() {
+    const list = [];
+    let text = '';
+    const flush = () => {
+      if (text !== '') list.push({ kind: 'text', text });
+      text = '';
+    };
+
+    while (pos < source.length) {
+      const rest = source.slice(pos, pos + 2);
+      if (rest === '{{' || rest === '}}') {
+        text += rest[0];
+        pos += 2;
+        continue;
+      }
+      if (rest[0] === '}') throw new Error('Unmatched "}" (write "}}" for a literal brace)');
+      if (rest[0] !== '{') {
+        text += rest[0];
+        pos++;
+        continue;
+      }
+
+      const content = tag();
+      if (content.startsWith('#elif') || content.startsWith('#else') || content === '/if') {
+        flush();
+        return { list, end: content };
+      }
+      flush();
+      if (content.startsWith('#if')) {
+        list.push(ifBlock(content.slice(3)));
+      } else if (content.startsWith('#') || content.startsWith('/')) {
+        throw new Error(`Unknown tag "{${content}}"`);
+      } else {
+        list.push(parseValue(content));
+      }
+    }
+    flush();
+    return { list, end: null };
+  }
+
+  function ifBlock(cond) {
+    const branches = [];
+    for (;;) {
+      const condition = parseCondition(cond);
+      const { list, end } = nodes();
+      branches.push({ condition, body: list });
+      if (end === null) throw new Error('"{#if}" is missing its "{/if}"');
+      if (end.startsWith('#elif')) {
+        cond = end.slice(5);
+        continue;
+      }
+      if (end.startsWith('#else')) {
+        if (end !== '#else') throw new Error('"{#else}" takes no condition (use "{#elif ...}")');
+        const otherwise = nodes();
+        if (otherwise.end === null) throw new Error('"{#if}" is missing its "{/if}"');
+        if (otherwise.end !== '/if') throw new Error('Expected "{/if}" after "{#else}"');
+        return { kind: 'if', branches, otherwise: otherwise.list };
+      }
+      return { kind: 'if', branches, otherwise: [] };
+    }
+  }
+
+  const { list, end } = nodes();
+  if (end !== null) throw new Error(`"{${end}}" without a matching "{#if}"`);
+  return list;
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+/** A value as a number, or null: numbers and numeric strings. */
+function asNumber(value) {
+  if (typeof value === 'number') return Number.isFinite(value) ? value : null;
+  if (typeof value === 'string' && DECIMAL.test(value.trim())) {
+    const n = Number(value.trim());
+    return Number.isFinite(n) ? n : null;
+  }
+  return null;
+}
+
+/** A value as display text; missing and null are empty. */
+function asText(value) {
+  if (value === undefined || value === null) return '';
+  if (typeof value === 'object') return JSON.stringify(value);
+  return String(value);
+}
+
+function truthy(value) {
+  if (value === undefined || value === null) return false;
+  if (typeof value === 'string') return !(value === '' || value === '0' || value === 'false');
+  if (Array.isArray(value)) return value.length > 0;
+  if (typeof value === 'object') return Object.keys(value).length > 0;
+  return !!value;
+}
+
+function equals(a, b) {
+  const [x, y] = [asNumber(a), asNumber(b)];
+  if (x !== null && y !== null) return x === y;
+  return asText(a) === asText(b);
+}
+
+/** `1234.5` with `decimals` → `1,234.50`; without, as many as needed. */
+function groupThousands(number, decimals) {
+  const formatted = decimals === null ? String(Math.abs(number)) : Math.abs(number).toFixed(decimals);
+  const [whole, fraction] = formatted.split('.');
+  let grouped = whole.replace(/\B(?=(\d{3})+$)/g, ',');
+  if (fraction !== undefined) grouped += `.${fraction}`;
+  if (number < 0 && /[1-9]/.test(grouped)) grouped = `-${grouped}`;
+  return grouped;
+}
+
+function currency(number, code) {
+  if (!CURRENCIES[code]) return `${groupThousands(number, 2)} ${code}`;
+  const [symbol, decimals] = CURRENCIES[code];
+  const amount = groupThousands(number, decimals);
+  return amount.startsWith('-') ? `-${symbol}${amount.slice(1)}` : `${symbol}${amount}`;
+}
+
+function applyFilter(filter, text, number) {
+  switch (filter.name) {
+    case 'upper': return text.toUpperCase();
+    case 'lower': return text.toLowerCase();
+    case 'capitalize': {
+      const [first = '', ...rest] = Array.from(text);
+      return first.toUpperCase() + rest.join('');
+    }
+    case 'number': return number === null ? text : groupThousands(number, filter.decimals);
+    case 'currency': return number === null ? text : currency(number, filter.code);
+    case 'plural': return number === 1 ? filter.one : filter.other;
+    case 'default': return text === '' ? filter.fallback : text;
+    case 'truncate': {
+      const chars = Array.from(text);
+      return chars.length > filter.length ? `${chars.slice(0, filter.length).join('')}…` : text;
+    }
+    default: return text;
+  }
+}
+
+function evaluateCondition(cond, variables) {
+  const value = (operand) => ('variable' in operand ? variables[operand.variable] : operand.literal);
+  const ordered = (compare) => {
+    const [x, y] = [asNumber(value(cond.a)), asNumber(value(cond.b))];
+    return x !== null && y !== null && compare(x, y);
+  };
+
+  switch (cond.op) {
+    case 'or': return evaluateCondition(cond.a, variables) || evaluateCondition(cond.b, variables);
+    case 'and': return evaluateCondition(cond.a, variables) && evaluateCondition(cond.b, variables);
+    case 'not': return !evaluateCondition(cond.a, variables);
+    case 'truthy': return truthy(value(cond.a));
+    case '==': return equals(value(cond.a), value(cond.b));
+    case '!=': return !equals(value(cond.a), value(cond.b));
+    case '>': return ordered((x, y) => x > y);
+    case '>=': return ordered((x, y) => x >= y);
+    case '<': return ordered((x, y) => x < y);
+    case '<=': return ordered((x, y) => x <= y);
+    default: return false;
+  }
+}
+
+function renderNodes(nodes, variables) {
+  let out = '';
+  for (const node of nodes) {
+    if (node.kind === 'text') {
+      out += node.text;
+    } else if (node.kind === 'value') {
+      const value = variables[node.variable];
+      const number = asNumber(value);
+      out += node.filters.reduce((text, filter) => applyFilter(filter, text, number), asText(value));
+    } else {
+      const branch = node.branches.find(({ condition }) => evaluateCondition(condition, variables));
+      out += renderNodes(branch ? branch.body : node.otherwise, variables);
+    }
+  }
+  return out;
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/**
+ * Render a template. Invalid templates are returned as written (and logged
+ * once), so a broken template still shows something on stream.
+ *
+ * @param {string} template - Message template
+ * @param {object} variables - Alert values (username, amount, message, type)
+ * @returns {string} Plain text; the overlay escapes it before display
+ */
+function render(template, variables = {}) {
+  const source = String(template || '');
+  let parsed = cache.get(source);
+  if (parsed === undefined) {
+    try {
+      parsed = parse(source);
+    } catch (err) {
+      console.warn(`[Alerts] Invalid message template "${source}": ${err.message}`);
+      parsed = null;
+    }
+    cache.set(source, parsed);
+  }
+  if (parsed === null) return source;
+
+  // Own properties only, so `constructor` or `__proto__` read as missing
+  const own = Object.assign(Object.create(null), variables);
+  return renderNodes(parsed, own);
+}
+
+/**
+ * Check a template for syntax errors.
+ *
+ * @param {string} template - Message template
+ * @returns {string|null} The error message, or null if valid
+ */
+function validate(template) {
+  try {
+    parse(String(template));
+    return null;
+  } catch (err) {
+    return err.message;
+  }
+}
+
+module.exports = {
+  render,
+  validate,
+};
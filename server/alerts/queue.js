@@ -1,38 +1,102 @@
 /**
  * StreamForge — Alert Queue System
  *
- * Manages an in-memory FIFO queue that ensures alerts fire sequentially
+ * Manages in-memory FIFO queues that ensure alerts fire sequentially
  * via WebSocket. Only one alert plays at a time — when it completes,
  * the next alert in the queue fires automatically.
  *
+ * Alerts are split across named channels, each with its own queue and
+ * pause state. An overlay picks its channel with ?channel= on the browser
+ * source URL and joins that channel's Socket.io room; one without it uses
+ * 'default'. An alert goes to every channel whose alert types list its
+ * type, or to 'default' if none does (see loadChannels).
+ *
+ * Every transition (enqueued, started, done, skipped) is reported to the
+ * Tauri shell, which keeps a durable copy of the queue and replays
+ * unfinished alerts after a restart (see replayAlerts). The shell also
+ * schedules the queue by priority (see applySchedule); without it alerts
+ * play in arrival order.
+ *
  * Usage:
  *   const alertQueue = require('./alerts/queue');
  *   alertQueue.init(io.of('/alerts'));
+ *   alertQueue.loadChannels(); // once the database is open
  *   alertQueue.enqueueAlert({ type: 'follow', username: 'viewer1', ... });
  */
 
 const { v4: uuidv4 } = require('uuid');
 const logger = require('./logger');
+const alertsDb = require('./database');
+const messageTemplates = require('./messageTemplates');
+const control = require('../utils/control');
 
 // ---------------------------------------------------------------------------
 // Internal State
 // ---------------------------------------------------------------------------
 
-/** @type {object[]} FIFO queue of pending alerts */
-const queue = [];
-
-/** @type {object|null} The alert currently being displayed on the overlay */
-let currentAlert = null;
+/** Channel used by overlays without ?channel, and for alerts no channel claims */
+const DEFAULT_CHANNEL = 'default';
 
-/** @type {boolean} Whether an alert is currently being processed */
-let isProcessing = false;
-
-/** @type {NodeJS.Timeout|null} Fallback timer in case overlay never sends alert:done */
-let fallbackTimer = null;
+/**
+ * Queue state per channel:
+ *   queue          — FIFO queue of pending alerts
+ *   currentAlert   — The alert currently being displayed on the overlay
+ *   isProcessing   — Whether an alert is currently being processed
+ *   isPaused       — While paused, queued alerts wait instead of firing
+ *   fallbackTimer  — Fallback timer in case overlay never sends alert:done
+ *   types          — Alert types routed to this channel
+ *
+ * @type {Map<string, object>}
+ */
+const channels = new Map();
 
 /** @type {import('socket.io').Namespace|null} Reference to the /alerts Socket.io namespace */
 let alertsNamespace = null;
 
+/**
+ * Create the state for a channel.
+ *
+ * @param {string} id
+ * @param {string[]} [types=[]]
+ * @returns {object}
+ */
+function createChannel(id, types = []) {
+  return {
+    id,
+    queue: [],
+    currentAlert: null,
+    isProcessing: false,
+    isPaused: false,
+    fallbackTimer: null,
+    types: new Set(types),
+  };
+}
+
+channels.set(DEFAULT_CHANNEL, createChannel(DEFAULT_CHANNEL));
+
+/**
+ * Socket.io room of a channel's overlays.
+ *
+ * @param {string} channelId
+ * @returns {string}
+ */
+function room(channelId) {
+  return `channel:${channelId}`;
+}
+
+/**
+ * Emit an event to the overlays of one channel.
+ *
+ * @param {string} channelId
+ * @param {string} event
+ * @param {object} data
+ */
+function emitTo(channelId, event, data) {
+  if (alertsNamespace) {
+    alertsNamespace.to(room(channelId)).emit(event, data);
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Initialization
 // ---------------------------------------------------------------------------
@@ -51,6 +115,120 @@ function init(namespace) {
   console.log('[AlertQueue] Initialized');
 }
 
+/**
+ * Put a newly connected overlay in the room of the channel it asked for
+ * with ?channel=, or 'default'. The requested channel is kept on
+ * socket.data.channel (null if none was given).
+ *
+ * @param {import('socket.io').Socket} socket
+ * @returns {string} The channel the socket joined
+ */
+function attachSocket(socket) {
+  const requested = socket.handshake.query?.channel;
+  socket.data.channel = typeof requested === 'string' && requested !== '' ? requested : null;
+
+  const channelId = socket.data.channel || DEFAULT_CHANNEL;
+  socket.join(room(channelId));
+  if (!channels.has(channelId)) {
+    console.warn(`[AlertQueue] Overlay ${socket.id} joined unknown channel '${channelId}'`);
+  }
+  return channelId;
+}
+
+/**
+ * (Re)load the alert channels from the database. Called on startup and
+ * whenever the shell changes them. Waiting alerts of a channel that was
+ * removed or disabled move to 'default'; its current alert is skipped.
+ */
+function loadChannels() {
+  let rows;
+  try {
+    rows = alertsDb.getAlertChannels();
+  } catch (err) {
+    console.error('[AlertQueue] Failed to load alert channels:', err.message);
+    return;
+  }
+
+  const wanted = new Map(rows.map((row) => [row.id, row.alert_types]));
+  if (!wanted.has(DEFAULT_CHANNEL)) {
+    wanted.set(DEFAULT_CHANNEL, []);
+  }
+
+  for (const [id, types] of wanted) {
+    const channel = channels.get(id);
+    if (channel) {
+      channel.types = new Set(types);
+    } else {
+      channels.set(id, createChannel(id, types));
+    }
+  }
+
+  const fallback = channels.get(DEFAULT_CHANNEL);
+  for (const channel of [...channels.values()]) {
+    if (wanted.has(channel.id)) {
+      continue;
+    }
+    // Pausing first keeps the skip from starting the channel's next alert
+    channel.isPaused = true;
+    skipCurrent(channel.id);
+    channels.delete(channel.id);
+    for (const alert of channel.queue) {
+      alert.channel = DEFAULT_CHANNEL;
+      fallback.queue.push(alert);
+    }
+    if (channel.queue.length > 0) {
+      console.log(
+        `[AlertQueue] Moved ${channel.queue.length} alert(s) from removed channel ` +
+        `'${channel.id}' to '${DEFAULT_CHANNEL}'`
+      );
+      processQueue(fallback);
+    }
+  }
+
+  console.log(`[AlertQueue] Loaded ${channels.size} alert channel(s): ${[...channels.keys()].join(', ')}`);
+}
+
+/**
+ * Channels an alert of this type is routed to: every channel listing the
+ * type, or 'default' if none does.
+ *
+ * @param {string} type
+ * @returns {object[]}
+ */
+function routeAlert(type) {
+  const routed = [...channels.values()].filter((channel) => channel.types.has(type));
+  return routed.length > 0 ? routed : [channels.get(DEFAULT_CHANNEL)];
+}
+
+/**
+ * Look up the channels an operation applies to.
+ *
+ * @param {string} [channelId] - A channel ID, or undefined for all channels
+ * @returns {object[]}
+ */
+function selectChannels(channelId) {
+  if (channelId === undefined || channelId === null) {
+    return [...channels.values()];
+  }
+  const channel = channels.get(channelId);
+  return channel ? [channel] : [];
+}
+
+/**
+ * Find a waiting or playing alert in any channel.
+ *
+ * @param {string} id
+ * @returns {boolean}
+ */
+function isQueued(id) {
+  for (const channel of channels.values()) {
+    if (channel.currentAlert?.id === id || channel.queue.some((queued) => queued.id === id)) {
+      return true;
+    }
+  }
+  return false;
+}
+
 // ---------------------------------------------------------------------------
 // Required Fields Validation
 // ---------------------------------------------------------------------------
@@ -113,13 +291,21 @@ const DEFAULT_MESSAGES = {
 // ---------------------------------------------------------------------------
 
 /**
- * Add an alert to the queue. If the queue was empty and nothing is
- * currently playing, the alert fires immediately.
+ * Add an alert to the queue of every channel it is routed to. If a
+ * channel's queue was empty and nothing is currently playing there, the
+ * alert fires immediately.
+ *
+ * The first channel keeps the alert's ID; copies for other channels get
+ * `<id>:<channel>`. Alerts that already name a channel (replayed or
+ * collapsed ones) go to that channel only, if it still exists.
  *
  * @param {object} alertData - Alert data object (must include type, username)
+ * @param {object} [options]
+ * @param {boolean} [options.log=true] - Record the alert in event_log (off for
+ *   alerts that were logged when they first arrived)
  * @returns {string|null} The alert instance ID, or null if validation failed
  */
-function enqueueAlert(alertData) {
+function enqueueAlert(alertData, { log = true } = {}) {
   // Validate required fields
   const { valid, missing } = validateAlertData(alertData);
   if (!valid) {
@@ -130,6 +316,11 @@ function enqueueAlert(alertData) {
   // Generate unique ID for this alert instance if not present
   const id = alertData.id || uuidv4();
 
+  if (isQueued(id)) {
+    console.warn(`[AlertQueue] Alert ${id} is already queued — ignoring duplicate`);
+    return id;
+  }
+
   // Build the full alert object with defaults
   const config = Object.assign({}, DEFAULT_CONFIG, alertData.config || {});
 
@@ -150,126 +341,322 @@ function enqueueAlert(alertData) {
     timestamp: alertData.timestamp || new Date().toISOString(),
   };
 
-  // Add to queue
-  queue.push(alert);
-  console.log(
-    `[AlertQueue] Enqueued alert ${id} (type: ${alert.type}, user: ${alert.username}) — ` +
-    `queue length: ${queue.length}, processing: ${isProcessing}`
-  );
-
-  // Log the triggered alert to the event_log table
-  logger.logEvent({
-    platform: alertData.platform || 'internal',
-    event_type: alert.type,
-    username: alert.username,
-    display_name: alert.displayName,
+  // Rendered once here so every overlay shows the same text
+  alert.text = messageTemplates.render(config.message_template, {
+    username: alert.displayName,
     amount: alert.amount,
     message: alert.message,
-    metadata: alertData.metadata || {},
-    alert_fired: 1,
+    type: alert.type,
   });
 
-  // If nothing is currently playing, start processing
-  if (!isProcessing) {
-    processQueue();
+  // Aggregate of an event burst, built by the shell's event pipeline
+  if (alertData.coalescedCount) {
+    alert.coalescedCount = alertData.coalescedCount;
+  }
+
+  // Summary of several alerts, built by the shell's scheduler
+  if (Array.isArray(alertData.collapsedUsernames)) {
+    alert.collapsedUsernames = alertData.collapsedUsernames;
+    alert.enqueuedAt = alertData.enqueuedAt;
   }
 
+  const targets = channels.has(alertData.channel)
+    ? [channels.get(alertData.channel)]
+    : routeAlert(alert.type);
+
+  // Add to the queue of each channel
+  targets.forEach((channel, index) => {
+    const copy = { ...alert, id: index === 0 ? id : `${id}:${channel.id}`, channel: channel.id };
+    channel.queue.push(copy);
+    console.log(
+      `[AlertQueue] Enqueued alert ${copy.id} on '${channel.id}' (type: ${copy.type}, ` +
+      `user: ${copy.username}) — queue length: ${channel.queue.length}, ` +
+      `processing: ${channel.isProcessing}`
+    );
+
+    control.sendQueueTransition('enqueued', copy);
+  });
+
+  // Log the triggered alert to the event_log table
+  if (log) {
+    logger.logEvent({
+      platform: alertData.platform || 'internal',
+      event_type: alert.type,
+      username: alert.username,
+      display_name: alert.displayName,
+      amount: alert.amount,
+      message: alert.message,
+      metadata: alertData.metadata || {},
+      alert_fired: 1,
+    });
+  }
+
+  // Start processing on every channel where nothing is currently playing
+  targets.forEach((channel) => processQueue(channel));
+
   return id;
 }
 
 /**
- * Get the alert currently being displayed, or null if none.
+ * Get the alert currently being displayed on a channel, or null if none.
  *
+ * @param {string} [channelId='default']
  * @returns {object|null}
  */
-function getCurrentAlert() {
-  return currentAlert;
+function getCurrentAlert(channelId = DEFAULT_CHANNEL) {
+  return channels.get(channelId)?.currentAlert || null;
 }
 
 /**
- * Get the number of alerts waiting in the queue (not including the current alert).
+ * Get the number of alerts waiting (not including the current alert).
  *
+ * @param {string} [channelId] - A channel ID, or undefined for all channels
  * @returns {number}
  */
-function getQueueLength() {
-  return queue.length;
+function getQueueLength(channelId) {
+  return selectChannels(channelId).reduce((sum, channel) => sum + channel.queue.length, 0);
 }
 
 /**
- * Clear all queued alerts. Does NOT stop the currently playing alert.
+ * Whether a channel's queue is paused.
  *
+ * @param {string} [channelId='default']
+ * @returns {boolean}
+ */
+function isQueuePaused(channelId = DEFAULT_CHANNEL) {
+  return channels.get(channelId)?.isPaused || false;
+}
+
+/**
+ * Summarize every channel's queue, e.g. for status endpoints.
+ *
+ * @returns {{ id: string, types: string[], currentAlert: string|null,
+ *   queueLength: number, paused: boolean }[]}
+ */
+function getChannelStates() {
+  return [...channels.values()].map((channel) => ({
+    id: channel.id,
+    types: [...channel.types],
+    currentAlert: channel.currentAlert?.id || null,
+    queueLength: channel.queue.length,
+    paused: channel.isPaused,
+  }));
+}
+
+/**
+ * Pause or resume a channel's queue. Pausing lets the current alert
+ * finish but holds back the rest; resuming fires the next alert right away.
+ *
+ * @param {boolean} paused
+ * @param {string} [channelId] - A channel ID, or undefined for all channels
+ */
+function setPaused(paused, channelId) {
+  for (const channel of selectChannels(channelId)) {
+    channel.isPaused = !!paused;
+    console.log(
+      `[AlertQueue] Channel '${channel.id}' ${channel.isPaused ? 'paused' : 'resumed'} — ` +
+      `${channel.queue.length} waiting`
+    );
+
+    emitTo(channel.id, 'alert:paused', { paused: channel.isPaused, channel: channel.id });
+    processQueue(channel);
+  }
+}
+
+/**
+ * Cut a channel's current alert short and move on to the next one.
+ * Overlays are told via alert:skip so they can hide it immediately.
+ *
+ * @param {string} [channelId='default']
+ * @returns {string|null} ID of the skipped alert, or null if none was playing
+ */
+function skipCurrent(channelId = DEFAULT_CHANNEL) {
+  const channel = channels.get(channelId);
+  if (!channel?.currentAlert) {
+    return null;
+  }
+
+  const skippedId = channel.currentAlert.id;
+  console.log(
+    `[AlertQueue] Skipping alert ${skippedId} on '${channel.id}' (type: ${channel.currentAlert.type})`
+  );
+  control.sendQueueTransition('skipped', channel.currentAlert);
+  emitTo(channel.id, 'alert:skip', { alertId: skippedId });
+  resetAndProcessNext(channel);
+  return skippedId;
+}
+
+/**
+ * Clear queued alerts. Does NOT stop the currently playing alerts.
+ *
+ * @param {string} [channelId] - A channel ID, or undefined for all channels
  * @returns {number} Number of alerts that were cleared
  */
-function clearQueue() {
-  const count = queue.length;
-  queue.length = 0;
+function clearQueue(channelId) {
+  let count = 0;
+  for (const channel of selectChannels(channelId)) {
+    count += channel.queue.length;
+    channel.queue.forEach((alert) => control.sendQueueTransition('skipped', alert));
+    channel.queue.length = 0;
+  }
   console.log(`[AlertQueue] Queue cleared (${count} alerts removed)`);
   return count;
 }
 
+/**
+ * Queue alerts again that a previous run never finished, as sent by the
+ * Tauri shell on startup. They keep their IDs and are not logged to
+ * event_log a second time.
+ *
+ * @param {object[]} alerts - Alert objects as originally queued, oldest first
+ * @returns {number} Number of alerts queued
+ */
+function replayAlerts(alerts) {
+  const replayed = alerts.filter((alert) => enqueueAlert(alert, { log: false }) !== null);
+  console.log(`[AlertQueue] Replayed ${replayed.length} unfinished alert(s)`);
+  return replayed.length;
+}
+
+/**
+ * Queue an alert from the shell's event pipeline. The events behind it
+ * (several, if a burst was coalesced into one alert) are logged to
+ * event_log individually.
+ *
+ * @param {object} alertData - Alert data object, as for enqueueAlert
+ * @param {object[]} events - event_log rows, as for logger.logEvent
+ * @returns {string|null} The alert instance ID, or null if validation failed
+ */
+function enqueueFromPipeline(alertData, events) {
+  const id = enqueueAlert(alertData, { log: false });
+  if (id !== null) {
+    events.forEach((event) => logger.logEvent(event));
+  }
+  return id;
+}
+
+/**
+ * Apply a plan from the shell's scheduler: drop or collapse expired
+ * alerts, then reorder the waiting alerts of every channel. IDs that are
+ * no longer queued are ignored, and a collapse only happens if every
+ * alert it replaces is still waiting. Alerts missing from the order keep
+ * their relative order after the ordered ones.
+ *
+ * @param {object} plan
+ * @param {string[]} plan.order - Alert IDs in play order
+ * @param {string[]} plan.drop - Alert IDs to remove
+ * @param {{ replaces: string[], alert: object }[]} plan.collapse - Summary
+ *   alerts and the alerts they replace
+ */
+function applySchedule({ order = [], drop = [], collapse = [] }) {
+  const waiting = (id) => [...channels.values()].some(
+    (channel) => channel.queue.some((queued) => queued.id === id)
+  );
+  const remove = (ids) => {
+    for (const id of ids) {
+      for (const { queue } of channels.values()) {
+        const index = queue.findIndex((alert) => alert.id === id);
+        if (index !== -1) {
+          control.sendQueueTransition('skipped', queue[index]);
+          queue.splice(index, 1);
+        }
+      }
+    }
+  };
+
+  if (drop.length > 0) {
+    console.log(`[AlertQueue] Dropping ${drop.length} expired alert(s)`);
+    remove(drop);
+  }
+
+  for (const { replaces, alert } of collapse) {
+    if (!replaces.every(waiting)) {
+      continue;
+    }
+    console.log(`[AlertQueue] Collapsing ${replaces.length} expired ${alert.type} alert(s) into one`);
+    remove(replaces);
+    enqueueAlert(alert, { log: false });
+  }
+
+  const rank = new Map(order.map((id, index) => [id, index]));
+  for (const { queue } of channels.values()) {
+    queue.sort((a, b) => (rank.get(a.id) ?? Infinity) - (rank.get(b.id) ?? Infinity));
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Queue Processing (Internal)
 // ---------------------------------------------------------------------------
 
 /**
- * Process the next alert in the queue. Called automatically when:
- *   - A new alert is enqueued and nothing is playing
+ * Process the next alert in a channel's queue. Called automatically when:
+ *   - A new alert is enqueued and nothing is playing on the channel
  *   - The current alert completes (via alert:done or fallback timeout)
+ *
+ * @param {object} channel - Channel state
  */
-function processQueue() {
-  // Guard: don't process if already processing or queue is empty
-  if (isProcessing || queue.length === 0) {
+function processQueue(channel) {
+  // Guard: don't process if already processing, paused or queue is empty
+  if (channel.isProcessing || channel.isPaused || channel.queue.length === 0) {
     return;
   }
 
-  isProcessing = true;
+  channel.isProcessing = true;
 
   // Take the next alert from the front of the queue (FIFO)
-  currentAlert = queue.shift();
+  const alert = channel.queue.shift();
+  channel.currentAlert = alert;
+  control.sendQueueTransition('started', alert);
 
   console.log(
-    `[AlertQueue] Firing alert ${currentAlert.id} (type: ${currentAlert.type}, ` +
-    `user: ${currentAlert.username}) — ${queue.length} remaining in queue`
+    `[AlertQueue] Firing alert ${alert.id} on '${channel.id}' (type: ${alert.type}, ` +
+    `user: ${alert.username}) — ${channel.queue.length} remaining in queue`
   );
 
-  // Emit to the /alerts namespace
+  // Emit to the channel's overlays in the /alerts namespace
   if (alertsNamespace) {
-    const connectedCount = alertsNamespace.sockets?.size || 0;
+    const connectedCount = alertsNamespace.adapter?.rooms?.get(room(channel.id))?.size || 0;
 
     if (connectedCount === 0) {
       console.warn(
-        '[AlertQueue] No overlay clients connected to /alerts — ' +
+        `[AlertQueue] No overlay clients connected to channel '${channel.id}' — ` +
         'alert will advance via fallback timeout'
       );
     }
 
-    alertsNamespace.emit('alert:trigger', currentAlert);
+    emitTo(channel.id, 'alert:trigger', alert);
   } else {
     console.error('[AlertQueue] Namespace not initialized — call init() first');
   }
 
   // Set a fallback timeout in case the overlay never sends alert:done.
   // This prevents the queue from getting permanently stuck.
-  const duration = currentAlert.config?.duration_ms || DEFAULT_CONFIG.duration_ms;
+  const duration = alert.config?.duration_ms || DEFAULT_CONFIG.duration_ms;
   const fallbackDelay = duration + 1000; // 1 second buffer
 
-  fallbackTimer = setTimeout(() => {
+  channel.fallbackTimer = setTimeout(() => {
     console.warn(
-      `[AlertQueue] Fallback timeout fired for alert ${currentAlert?.id} ` +
+      `[AlertQueue] Fallback timeout fired for alert ${alert.id} ` +
       `after ${fallbackDelay}ms — overlay did not send alert:done`
     );
-    resetAndProcessNext();
+    control.sendQueueTransition('done', alert);
+    resetAndProcessNext(channel);
   }, fallbackDelay);
 }
 
 /**
- * Handle the alert:done event from the overlay. Validates that the
- * completed alert matches the current one, then advances the queue.
+ * Handle the alert:done event from an overlay. Finds the channel playing
+ * the completed alert (or uses the overlay's channel if no ID was sent),
+ * then advances that channel's queue.
  *
  * @param {object} data - Payload from the overlay: { alertId: string }
+ * @param {string} [channelId='default'] - Channel of the overlay that sent it
  */
-function onAlertDone(data) {
+function onAlertDone(data, channelId = DEFAULT_CHANNEL) {
   const alertId = data?.alertId;
+  const channel = (alertId &&
+    [...channels.values()].find((candidate) => candidate.currentAlert?.id === alertId)) ||
+    channels.get(channelId);
+  const currentAlert = channel?.currentAlert;
 
   if (!currentAlert) {
     console.warn(`[AlertQueue] Received alert:done but no alert is currently playing (alertId: ${alertId})`);
@@ -285,31 +672,32 @@ function onAlertDone(data) {
   }
 
   console.log(
-    `[AlertQueue] Alert ${currentAlert.id} completed (type: ${currentAlert.type}, ` +
+    `[AlertQueue] Alert ${currentAlert.id} completed on '${channel.id}' (type: ${currentAlert.type}, ` +
     `user: ${currentAlert.username})`
   );
+  control.sendQueueTransition('done', currentAlert);
 
-  resetAndProcessNext();
+  resetAndProcessNext(channel);
 }
 
 /**
- * Reset current alert state, clear the fallback timer, and process
- * the next alert in the queue if any.
+ * Reset a channel's current alert state, clear the fallback timer, and
+ * process the next alert in its queue if any.
+ *
+ * @param {object} channel - Channel state
  */
-function resetAndProcessNext() {
+function resetAndProcessNext(channel) {
   // Clear the fallback timer
-  if (fallbackTimer) {
-    clearTimeout(fallbackTimer);
-    fallbackTimer = null;
+  if (channel.fallbackTimer) {
+    clearTimeout(channel.fallbackTimer);
+    channel.fallbackTimer = null;
   }
 
-  currentAlert = null;
-  isProcessing = false;
+  channel.currentAlert = null;
+  channel.isProcessing = false;
 
   // Process next alert if queue has more
-  if (queue.length > 0) {
-    processQueue();
-  }
+  processQueue(channel);
 }
 
 // ---------------------------------------------------------------------------
@@ -317,10 +705,20 @@ function resetAndProcessNext() {
 // ---------------------------------------------------------------------------
 
 module.exports = {
+  DEFAULT_CHANNEL,
   init,
+  attachSocket,
+  loadChannels,
   enqueueAlert,
   getCurrentAlert,
   getQueueLength,
   clearQueue,
+  isQueuePaused,
+  getChannelStates,
+  setPaused,
+  skipCurrent,
+  replayAlerts,
+  enqueueFromPipeline,
+  applySchedule,
   onAlertDone,
 };
@@ -3,17 +3,46 @@
  *
  * Central configuration for the Node.js sidecar server.
  * All configurable values live here for easy modification.
+ *
+ * Binding can be overridden through environment variables, which the Tauri
+ * shell sets from the user's launch settings:
+ *   STREAMFORGE_HOST, STREAMFORGE_PORT, STREAMFORGE_PORT_MIN, STREAMFORGE_PORT_MAX
+ */
+
+/**
+ * Read a port number from the environment, ignoring invalid values.
+ * @param {string} name - Environment variable name
+ * @param {number} fallback - Value used when the variable is unset or invalid
+ * @returns {number}
  */
+function envPort(name, fallback) {
+  const raw = process.env[name];
+  if (raw === undefined || raw === '') return fallback;
+
+  const port = Number(raw);
+  if (!Number.isInteger(port) || port < 1 || port > 65535) {
+    console.warn(`[Config] Ignoring invalid ${name}="${raw}"`);
+    return fallback;
+  }
+  return port;
+}
+
+const portMin = envPort('STREAMFORGE_PORT_MIN', 39283);
+const portMax = envPort('STREAMFORGE_PORT_MAX', 39383);
+const validRange = portMin <= portMax;
+if (!validRange) {
+  console.warn(`[Config] Ignoring invalid port range ${portMin}-${portMax}`);
+}
 
 const config = {
   // Server binding
-  host: '127.0.0.1', // Localhost only — not accessible from other machines
-  defaultPort: 39283, // Default port; auto-detection will try this first
+  host: process.env.STREAMFORGE_HOST || '127.0.0.1', // Localhost only by default
+  defaultPort: envPort('STREAMFORGE_PORT', 39283), // Auto-detection will try this first
 
   // Port auto-detection range: if defaultPort is busy, scan this range
   portRange: {
-    min: 39283,
-    max: 39383, // 100 ports to try before giving up
+    min: validRange ? portMin : 39283,
+    max: validRange ? portMax : 39383, // 100 ports to try before giving up
   },
 
   // CORS — restricted to localhost only
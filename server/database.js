@@ -24,6 +24,11 @@ const Database = require('better-sqlite3');
  * @returns {string} Absolute path to the streamforge data directory
  */
 function getAppDataDir() {
+  // Set by the Tauri shell when the user picks a custom data directory
+  if (process.env.STREAMFORGE_DATA_DIR) {
+    return process.env.STREAMFORGE_DATA_DIR;
+  }
+
   const platform = process.platform;
 
   let baseDir;
@@ -142,6 +147,11 @@ function initDatabase() {
  * startup it scans the migrations directory, compares against already-applied
  * migrations, and executes any new ones in alphabetical order inside
  * individual transactions.
+ *
+ * Under the Tauri shell the same files are embedded in the app and applied
+ * (with the same bookkeeping) before the sidecar starts, so this usually
+ * finds nothing to do -- and it doesn't matter if a packaged build can't
+ * locate the migrations directory.
  */
 function runMigrations() {
   // Create the migrations tracking table if it doesn't exist
@@ -219,6 +229,21 @@ function runMigrations() {
   }
 }
 
+/**
+ * Get the schema version, i.e. the numeric prefix of the newest applied
+ * migration (e.g. 5 for 005_alert_templates.sql).
+ *
+ * @returns {number|null} The schema version, or null if the database is not open
+ */
+function getSchemaVersion() {
+  if (!db) return null;
+  const rows = db.prepare('SELECT filename FROM _migrations').all();
+  return rows.reduce((max, row) => {
+    const num = parseInt(row.filename, 10);
+    return Number.isNaN(num) ? max : Math.max(max, num);
+  }, 0);
+}
+
 // ---------------------------------------------------------------------------
 // Settings Helper Functions
 // ---------------------------------------------------------------------------
@@ -393,6 +418,7 @@ module.exports = {
   getDb,
   getDbPath,
   getAppDataDir,
+  getSchemaVersion,
 
   // Settings
   getSetting,
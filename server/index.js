@@ -11,7 +11,8 @@
  * The server will:
  *   1. Try to bind to the default port (39283)
  *   2. If busy, scan ports 39283–39383 for a free one
- *   3. Print "SERVER_PORT=<port>" to stdout so Tauri can read it
+ *   3. Print a JSON "ready" control message to stdout so Tauri can read it
+ *      (see utils/control.js), then send heartbeats every few seconds
  *   4. Handle graceful shutdown on SIGINT / SIGTERM
  */
 
@@ -33,6 +34,7 @@ const eventsRouter = require('./routes/events');
 const templatesRouter = require('./routes/templates');
 const cron = require('node-cron');
 const { pruneOldEvents } = require('./alerts/logger');
+const control = require('./utils/control');
 
 const fs = require('fs');
 
@@ -131,22 +133,24 @@ const namespaceEvents = {
       event: 'alert:done',
       handler: (socket, data) => {
         console.log(`[Alerts] alert:done from ${socket.id}:`, data);
-        // Advance the alert queue to the next alert
-        alertQueue.onAlertDone(data);
+        // Advance the queue of the channel playing the alert
+        alertQueue.onAlertDone(data, socket.data.channel || alertQueue.DEFAULT_CHANNEL);
       },
     },
     {
       event: 'alert:skip',
       handler: (socket, data) => {
         console.log(`[Alerts] alert:skip from ${socket.id}:`, data);
+        alertQueue.skipCurrent(data?.channel || socket.data.channel || alertQueue.DEFAULT_CHANNEL);
       },
     },
     {
       event: 'alert:pause',
       handler: (socket, data) => {
         console.log(`[Alerts] alert:pause from ${socket.id}:`, data);
-        // Broadcast pause state to all alert clients
-        namespaces['/alerts'].emit('alert:paused', { paused: !!data?.paused });
+        // Pauses the given channel, the sender's own channel, or every
+        // channel for clients that didn't pick one
+        alertQueue.setPaused(!!data?.paused, data?.channel || socket.data.channel || undefined);
       },
     },
   ],
@@ -197,6 +201,9 @@ const namespaceEvents = {
               username: data.username,
               tier: data.tier || null,
               amount: data.amount || null,
+              message: data.message || null,
+              platform: data.platform || null,
+              type: data.type,
               custom_value: data.custom_value || null,
             };
             const matchedAlert = alertsDb.findMatchingAlert(data.type, eventData);
@@ -269,6 +276,7 @@ function setupNamespace(nspath) {
     console.log(
       `[${label}] Client connected: ${socket.id} (${clientCounts[nspath]} connected)`
     );
+    control.sendClients({ ...clientCounts });
 
     // Send welcome message to the newly connected client
     socket.emit('welcome', {
@@ -279,6 +287,11 @@ function setupNamespace(nspath) {
       message: `Connected to StreamForge ${label} namespace`,
     });
 
+    // Overlays of the /alerts namespace join the room of their channel
+    if (nspath === '/alerts') {
+      alertQueue.attachSocket(socket);
+    }
+
     // Attach per-namespace event listeners
     const events = namespaceEvents[nspath] || [];
     for (const { event, handler } of events) {
@@ -291,6 +304,7 @@ function setupNamespace(nspath) {
       console.log(
         `[${label}] Client disconnected: ${socket.id} (${reason}) (${clientCounts[nspath]} connected)`
       );
+      control.sendClients({ ...clientCounts });
     });
   });
 
@@ -414,7 +428,7 @@ app.get('/api/server/info', (req, res) => {
     port: serverPort,
     host: config.host,
     uptime: process.uptime(),
-    version: '0.1.0',
+    version: require('./package.json').version,
     dbPath: database.getDbPath(),
     overlays: {
       alerts: '/overlays/alerts/',
@@ -546,6 +560,7 @@ app.post('/api/test/alert-overlay', (req, res) => {
     namespace: '/alerts',
     alertId,
     queueLength: alertQueue.getQueueLength(),
+    channels: alertQueue.getChannelStates(),
     connectedClients: clientCounts['/alerts'],
   });
 });
@@ -651,6 +666,9 @@ async function startServer() {
       console.error('[Server] The server will start without database support.');
     }
 
+    // Load the alert channels and their routing rules
+    alertQueue.loadChannels();
+
     // Prune old event logs on startup (clears any backlog from downtime)
     try {
       pruneOldEvents();
@@ -670,9 +688,14 @@ async function startServer() {
       // If port was 0, the OS assigned one — read the actual port
       serverPort = httpServer.address().port;
 
-      // Print port to stdout in the format Tauri expects.
+      // Hand the port and server details to Tauri over the control protocol.
       // This MUST be a clean line so Tauri can parse it reliably.
-      console.log(`SERVER_PORT=${serverPort}`);
+      control.sendReady({
+        port: serverPort,
+        schemaVersion: database.getSchemaVersion(),
+        dataDir: database.getAppDataDir(),
+      });
+      control.startHeartbeat();
 
       console.log(
         `[Server] StreamForge server running at http://${config.host}:${serverPort}`
@@ -695,6 +718,7 @@ async function startServer() {
     });
   } catch (err) {
     console.error('[Server] Failed to start:', err.message);
+    control.sendFatal(`Failed to start: ${err.message}`);
     process.exit(1);
   }
 }
@@ -703,14 +727,22 @@ async function startServer() {
 // Graceful Shutdown
 // ---------------------------------------------------------------------------
 
+/** Set once shutdown starts so repeated signals/requests are ignored */
+let shuttingDown = false;
+
 /**
  * Gracefully shut down the server:
  *   1. Close all Socket.io connections
  *   2. Close the HTTP server (stop accepting new connections)
- *   3. Exit the process
+ *   3. Tell the Tauri shell we're done (control "stopped" message)
+ *   4. Exit the process
  */
 function shutdown(signal) {
+  if (shuttingDown) return;
+  shuttingDown = true;
+
   console.log(`\n[Server] Received ${signal}, shutting down gracefully...`);
+  control.stopHeartbeat();
 
   // Close the database connection
   database.closeDatabase();
@@ -722,6 +754,7 @@ function shutdown(signal) {
     // Close HTTP server
     httpServer.close(() => {
       console.log('[Server] HTTP server closed');
+      control.sendStopped();
       process.exit(0);
     });
   });
@@ -736,9 +769,22 @@ function shutdown(signal) {
 process.on('SIGINT', () => shutdown('SIGINT'));
 process.on('SIGTERM', () => shutdown('SIGTERM'));
 
+// The Tauri shell requests shutdown over stdin (works on Windows, where
+// there is no SIGTERM), hands back alerts a previous run never finished,
+// schedules the queue by priority, feeds in alerts from its own event
+// sources and tells the server when alert channels change
+control.listen({
+  onShutdown: () => shutdown('shutdown request'),
+  onReplay: (alerts) => alertQueue.replayAlerts(alerts),
+  onSchedule: (plan) => alertQueue.applySchedule(plan),
+  onEnqueue: (alert, events) => alertQueue.enqueueFromPipeline(alert, events),
+  onChannels: () => alertQueue.loadChannels(),
+});
+
 // Handle uncaught errors to prevent silent crashes
 process.on('uncaughtException', (err) => {
   console.error('[Server] Uncaught exception:', err);
+  control.sendFatal(`Uncaught exception: ${err.message}`);
   process.exit(1);
 });
 
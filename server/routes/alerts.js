@@ -26,6 +26,7 @@
 const express = require('express');
 const router = express.Router();
 const alertsDb = require('../alerts/database');
+const conditions = require('../alerts/conditions');
 
 // ---------------------------------------------------------------------------
 // Alert Fields
@@ -69,6 +70,18 @@ function pickFields(body, fields) {
   return result;
 }
 
+/**
+ * Syntax error in an expression condition, if there is one.
+ * @param {string|undefined} conditionType - The variation's condition type
+ * @param {string|undefined} conditionValue - The new condition value
+ * @returns {string|null} Error message, or null
+ */
+function conditionError(conditionType, conditionValue) {
+  if (conditionType !== 'expression' || conditionValue === undefined) return null;
+  const error = conditions.validate(conditionValue);
+  return error ? `Invalid condition: ${error}` : null;
+}
+
 // ---------------------------------------------------------------------------
 // Alert Routes
 // ---------------------------------------------------------------------------
@@ -251,6 +264,9 @@ router.get('/:id/variations', (req, res) => {
  * Required body fields: name, condition_type, condition_value
  * Optional: message_template, sound_path, sound_volume, image_path,
  *           animation_in, animation_out, custom_css, enabled, priority
+ *
+ * condition_type is 'expression' with condition_value an expression such as
+ * `amount between 100 and 499`; invalid expressions are rejected with 400.
  */
 router.post('/:id/variations', (req, res) => {
   try {
@@ -263,6 +279,11 @@ router.post('/:id/variations', (req, res) => {
       });
     }
 
+    const invalid = conditionError(data.condition_type, data.condition_value);
+    if (invalid) {
+      return res.status(400).json({ error: invalid });
+    }
+
     const variation = alertsDb.createAlertVariation(data);
     res.status(201).json(variation);
   } catch (err) {
@@ -299,6 +320,14 @@ router.get('/variations/:id', (req, res) => {
 router.put('/variations/:id', (req, res) => {
   try {
     const data = pickFields(req.body, VARIATION_FIELDS);
+
+    const conditionType = data.condition_type
+      ?? alertsDb.getVariationById(req.params.id)?.condition_type;
+    const invalid = conditionError(conditionType, data.condition_value);
+    if (invalid) {
+      return res.status(400).json({ error: invalid });
+    }
+
     const variation = alertsDb.updateAlertVariation(req.params.id, data);
 
     if (!variation) {
@@ -9,6 +9,8 @@
  *   POST /api/test-alert           - Enqueue a test alert
  *   GET  /api/test-alert/status    - Get current queue status
  *   POST /api/test-alert/clear     - Clear the alert queue
+ *   POST /api/test-alert/pause     - Pause or resume the alert queue
+ *   POST /api/test-alert/skip      - Skip the currently playing alert
  */
 
 const express = require('express');
@@ -84,6 +86,9 @@ router.post('/', (req, res) => {
     username,
     tier: body.tier || null,
     amount: body.amount || (type === 'cheer' ? 100 : type === 'raid' ? 50 : type === 'donation' ? 5 : null),
+    message: body.message || null,
+    platform: body.platform || null,
+    type,
     custom_value: body.custom_value || null,
   };
 
@@ -184,7 +189,9 @@ router.post('/', (req, res) => {
 /**
  * GET /api/test-alert/status
  *
- * Returns the current state of the alert queue.
+ * Returns the current state of the alert queue. The top-level fields
+ * describe the 'default' channel (queueLength counts every channel);
+ * `channels` lists each channel.
  */
 router.get('/status', (req, res) => {
   const current = alertQueue.getCurrentAlert();
@@ -193,17 +200,19 @@ router.get('/status', (req, res) => {
       ? { id: current.id, type: current.type, username: current.username }
       : null,
     queueLength: alertQueue.getQueueLength(),
+    paused: alertQueue.isQueuePaused(),
+    channels: alertQueue.getChannelStates(),
   });
 });
 
 /**
  * POST /api/test-alert/clear
  *
- * Clears all pending alerts from the queue.
- * Does not stop the currently playing alert.
+ * Clears all pending alerts from the queue, or from one channel with
+ * { "channel": "..." }. Does not stop the currently playing alert.
  */
 router.post('/clear', (req, res) => {
-  const cleared = alertQueue.clearQueue();
+  const cleared = alertQueue.clearQueue(req.body?.channel);
   res.json({
     status: 'ok',
     cleared,
@@ -211,4 +220,38 @@ router.post('/clear', (req, res) => {
   });
 });
 
+/**
+ * POST /api/test-alert/pause
+ *
+ * Pauses the queue, or resumes it with { "paused": false }. Applies to
+ * every channel unless { "channel": "..." } is given.
+ * The currently playing alert is allowed to finish.
+ */
+router.post('/pause', (req, res) => {
+  const paused = req.body?.paused !== false;
+  alertQueue.setPaused(paused, req.body?.channel);
+  res.json({
+    status: 'ok',
+    paused,
+    queueLength: alertQueue.getQueueLength(req.body?.channel),
+  });
+});
+
+/**
+ * POST /api/test-alert/skip
+ *
+ * Ends the currently playing alert early and fires the next one, on the
+ * 'default' channel or the one given as { "channel": "..." }.
+ */
+router.post('/skip', (req, res) => {
+  const channel = req.body?.channel || alertQueue.DEFAULT_CHANNEL;
+  const skipped = alertQueue.skipCurrent(channel);
+  res.json({
+    status: 'ok',
+    skipped,
+    currentAlert: alertQueue.getCurrentAlert(channel)?.id || null,
+    queueLength: alertQueue.getQueueLength(channel),
+  });
+});
+
 module.exports = router;
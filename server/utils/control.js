@@ -0,0 +1,216 @@
+/**
+ * StreamForge — Sidecar Control Protocol
+ *
+ * Line-delimited JSON messages written to stdout for the Tauri shell.
+ * Every message is a single JSON object on its own line with a
+ * `streamforge` key (the protocol version) and a `type` discriminator.
+ * All other stdout output is treated as plain log lines.
+ *
+ * The Rust parser lives in src-tauri/src/sidecar/protocol.rs and must be
+ * kept in sync with this file.
+ *
+ * Messages (server -> shell, on stdout):
+ *   ready     — { port, serverVersion, schemaVersion, pid, dataDir }
+ *   heartbeat — { uptime }
+ *   fatal     — { message }
+ *   clients   — { clients: { [namespace]: count } } (sent on every change)
+ *   event     — { event: { id, platform, eventType, username, displayName,
+ *                          amount, message, alertFired, timestamp } }
+ *   queue     — { state, alertId, alert? } (an alert was enqueued, started,
+ *               done or skipped; the full alert object is sent with
+ *               'enqueued' only)
+ *   stopped   — {} (shutdown finished, process is exiting)
+ *
+ * Messages (shell -> server, on stdin):
+ *   shutdown  — {} (close the database and sockets, then exit)
+ *   replay    — { alerts: [...] } (alerts a previous run never finished,
+ *               oldest first, to be queued again)
+ *   schedule  — { order: [id], drop: [id], collapse: [{ replaces: [id],
+ *               alert }] } (reorder the waiting alerts; drop or collapse
+ *               expired ones)
+ *   enqueue   — { alert, events: [...] } (queue an alert from the shell's
+ *               event pipeline and log each event behind it)
+ *   channels  — {} (alert channels changed in the database; reload them)
+ */
+
+const readline = require('readline');
+const { version: SERVER_VERSION } = require('../package.json');
+
+/**
+ * Protocol version understood by the Tauri shell (v2 added queue/replay,
+ * v3 schedule, v4 enqueue, v5 channels)
+ */
+const PROTOCOL_VERSION = 5;
+
+/** Interval between heartbeat messages in ms */
+const HEARTBEAT_INTERVAL_MS = 5000;
+
+/** @type {NodeJS.Timeout|null} */
+let heartbeatTimer = null;
+
+/**
+ * Write a single control message to stdout.
+ * Uses process.stdout.write directly so the line is never interleaved
+ * with other console output.
+ *
+ * @param {string} type - Message type
+ * @param {object} [fields] - Message payload
+ */
+function send(type, fields = {}) {
+  const message = { streamforge: PROTOCOL_VERSION, type, ...fields };
+  process.stdout.write(`${JSON.stringify(message)}\n`);
+}
+
+/**
+ * Announce that the HTTP server is listening.
+ *
+ * @param {object} info
+ * @param {number} info.port - The port the server bound to
+ * @param {number|null} info.schemaVersion - Highest applied migration
+ * @param {string} info.dataDir - App data directory in use
+ */
+function sendReady({ port, schemaVersion, dataDir }) {
+  send('ready', {
+    port,
+    serverVersion: SERVER_VERSION,
+    schemaVersion,
+    pid: process.pid,
+    dataDir,
+  });
+}
+
+/**
+ * Report an unrecoverable error. Call right before exiting.
+ * @param {string} message - Human-readable error description
+ */
+function sendFatal(message) {
+  send('fatal', { message });
+}
+
+/**
+ * Report the connected Socket.io clients per namespace.
+ * @param {Object<string, number>} clients - Count keyed by namespace path
+ */
+function sendClients(clients) {
+  send('clients', { clients });
+}
+
+/**
+ * Forward a stream event (follow, sub, cheer...) that was just logged.
+ * @param {object} log - Row written to event_log
+ */
+function sendStreamEvent(log) {
+  send('event', {
+    event: {
+      id: log.id,
+      platform: log.platform,
+      eventType: log.event_type,
+      username: log.username,
+      displayName: log.display_name,
+      amount: log.amount,
+      message: log.message,
+      alertFired: !!log.alert_fired,
+      timestamp: log.timestamp,
+    },
+  });
+}
+
+/**
+ * Report an alert queue transition so the shell can keep a durable copy
+ * of the queue.
+ *
+ * @param {'enqueued'|'started'|'done'|'skipped'} state
+ * @param {object} alert - The queued alert object
+ */
+function sendQueueTransition(state, alert) {
+  send('queue', {
+    state,
+    alertId: alert.id,
+    alert: state === 'enqueued' ? alert : undefined,
+  });
+}
+
+/**
+ * Confirm that a shutdown has completed. Call right before process.exit().
+ */
+function sendStopped() {
+  send('stopped');
+}
+
+/**
+ * Listen for control messages from the shell on stdin.
+ * Lines that aren't control messages are ignored, so this is harmless
+ * when the server is run from a terminal.
+ *
+ * @param {object} handlers
+ * @param {Function} handlers.onShutdown - Called when the shell requests a shutdown
+ * @param {Function} [handlers.onReplay] - Called with the alerts to queue again
+ * @param {Function} [handlers.onSchedule] - Called with a scheduling plan
+ * @param {Function} [handlers.onEnqueue] - Called with an alert and its events
+ * @param {Function} [handlers.onChannels] - Called when alert channels changed
+ */
+function listen({ onShutdown, onReplay, onSchedule, onEnqueue, onChannels }) {
+  const rl = readline.createInterface({ input: process.stdin, terminal: false });
+
+  rl.on('line', (line) => {
+    let message;
+    try {
+      message = JSON.parse(line);
+    } catch (_) {
+      return;
+    }
+    if (!message || typeof message !== 'object' || message.streamforge === undefined) {
+      return;
+    }
+
+    if (message.type === 'shutdown') {
+      onShutdown();
+    } else if (message.type === 'replay' && onReplay) {
+      onReplay(Array.isArray(message.alerts) ? message.alerts : []);
+    } else if (message.type === 'schedule' && onSchedule) {
+      onSchedule(message);
+    } else if (message.type === 'enqueue' && onEnqueue) {
+      onEnqueue(message.alert, Array.isArray(message.events) ? message.events : []);
+    } else if (message.type === 'channels' && onChannels) {
+      onChannels();
+    } else {
+      console.warn(`[Control] Ignoring unknown control message: ${message.type}`);
+    }
+  });
+}
+
+/**
+ * Start sending periodic heartbeats so the shell can detect a hung
+ * event loop. Safe to call more than once.
+ */
+function startHeartbeat() {
+  if (heartbeatTimer) return;
+  heartbeatTimer = setInterval(() => {
+    send('heartbeat', { uptime: process.uptime() });
+  }, HEARTBEAT_INTERVAL_MS);
+  // Don't keep the process alive just for heartbeats
+  heartbeatTimer.unref();
+}
+
+/**
+ * Stop sending heartbeats (e.g. during shutdown).
+ */
+function stopHeartbeat() {
+  if (heartbeatTimer) {
+    clearInterval(heartbeatTimer);
+    heartbeatTimer = null;
+  }
+}
+
+module.exports = {
+  PROTOCOL_VERSION,
+  sendReady,
+  sendFatal,
+  sendClients,
+  sendStreamEvent,
+  sendQueueTransition,
+  sendStopped,
+  listen,
+  startHeartbeat,
+  stopHeartbeat,
+};
@@ -63,4 +63,39 @@ fn main() {
 
     // Re-run if the source binary changes
     println!("cargo:rerun-if-changed=binaries/");
+
+    embed_migrations();
+}
+
+// ---------------------------------------------------------------------------
+// Embed migrations/*.sql so the shell can migrate the database without
+// locating the project root at runtime. Generates a `MIGRATIONS` table of
+// (filename, sql) pairs in filename order, included by src/db/migrations.rs.
+// ---------------------------------------------------------------------------
+
+fn embed_migrations() {
+    let dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("../migrations");
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+        .collect();
+    files.sort();
+
+    let mut code = String::from("pub(crate) const MIGRATIONS: &[(&str, &str)] = &[\n");
+    for path in &files {
+        let filename = path.file_name().unwrap().to_string_lossy();
+        let absolute = fs::canonicalize(path).unwrap();
+        code.push_str(&format!(
+            "    ({:?}, include_str!({:?})),\n",
+            filename,
+            absolute.display().to_string()
+        ));
+    }
+    code.push_str("];\n");
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("migrations.rs");
+    fs::write(&out, code).unwrap_or_else(|e| panic!("Failed to write {}: {}", out.display(), e));
 }
@@ -0,0 +1,268 @@
+//! Durable alert queue journal.
+//!
+//! The server's queue (`server/alerts/queue.js`) lives in memory, so a
+//! crash or restart used to drop every pending alert. The server now
+//! reports each transition over the control protocol and the shell records
+//! it in the `alert_queue` table. When a new sidecar reports ready, alerts
+//! that were still enqueued or playing are sent back to it with a `replay`
+//! message, oldest first, and it queues them again without logging them to
+//! `event_log` a second time.
+//!
+//! An alert that has been replayed `MAX_ATTEMPTS` times without finishing
+//! is marked skipped instead, so one that crashes the server can't take it
+//! down on every start.
+//!
+//! `schedule` decides the order the waiting alerts play in.
+
+pub(crate) mod schedule;
+
+use chrono::{SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::channels::DEFAULT_CHANNEL;
+use crate::db;
+use crate::sidecar::protocol::{QueueMessage, QueueState};
+
+/// Times an alert is queued (the original plus replays) before it is given
+/// up on.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Finished (done or skipped) rows kept for inspection.
+const HISTORY_LIMIT: u32 = 200;
+
+const PENDING: &str = "status IN ('enqueued', 'started')";
+const FINISHED: &str = "status IN ('done', 'skipped')";
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BacklogEntry {
+    pub(crate) id: String,
+    pub(crate) alert_type: String,
+    pub(crate) username: String,
+    /// Alert channel the alert was queued on.
+    pub(crate) channel: String,
+    pub(crate) status: QueueState,
+    /// 1 for an alert that was never replayed.
+    pub(crate) attempts: u32,
+    pub(crate) enqueued_at: String,
+    pub(crate) updated_at: String,
+    /// The alert object as the server queued it.
+    pub(crate) alert: Value,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlertBacklog {
+    /// Alerts that will be replayed to the next sidecar, oldest first.
+    pub(crate) pending: Vec<BacklogEntry>,
+    /// Recently finished alerts, newest first; empty unless requested.
+    pub(crate) finished: Vec<BacklogEntry>,
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn status_from_str(status: &str) -> QueueState {
+    match status {
+        "started" => QueueState::Started,
+        "done" => QueueState::Done,
+        "skipped" => QueueState::Skipped,
+        _ => QueueState::Enqueued,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+/// Applies a transition reported by the server.
+///
+/// `enqueued` inserts the alert with its channel, or counts another attempt
+/// if it is a replay. An `enqueuedAt` on the alert (set on collapsed
+/// alerts) backdates it. The other states only update rows that exist, so
+/// transitions for alerts purged from the backlog are ignored.
+pub(crate) fn record(conn: &Connection, message: &QueueMessage) -> Result<(), String> {
+    let now = now();
+    match (message.state, &message.alert) {
+        (QueueState::Enqueued, Some(alert)) => {
+            let text = |field: &str| {
+                alert
+                    .get(field)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let enqueued_at = alert
+                .get("enqueuedAt")
+                .and_then(Value::as_str)
+                .unwrap_or(&now);
+            let channel = alert
+                .get("channel")
+                .and_then(Value::as_str)
+                .unwrap_or(DEFAULT_CHANNEL);
+            conn.execute(
+                "INSERT INTO alert_queue (id, alert_type, username, channel, payload, status, attempts, enqueued_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'enqueued', 1, ?6, ?7)
+                 ON CONFLICT(id) DO UPDATE SET
+                   channel = excluded.channel,
+                   payload = excluded.payload,
+                   status = 'enqueued',
+                   attempts = attempts + 1,
+                   updated_at = excluded.updated_at",
+                params![
+                    message.alert_id,
+                    text("type"),
+                    text("username"),
+                    channel,
+                    alert.to_string(),
+                    enqueued_at,
+                    now
+                ],
+            )
+            .map_err(|e| format!("Failed to record queued alert: {}", e))?;
+        }
+        (QueueState::Enqueued, None) => {
+            return Err(format!(
+                "Queue message for alert {} is missing the alert",
+                message.alert_id
+            ));
+        }
+        (state, _) => {
+            conn.execute(
+                "UPDATE alert_queue SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                params![state.as_str(), now, message.alert_id],
+            )
+            .map_err(|e| format!("Failed to record alert {}: {}", state.as_str(), e))?;
+            if matches!(state, QueueState::Done | QueueState::Skipped) {
+                prune_history(conn)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Drops all but the newest `HISTORY_LIMIT` finished rows.
+fn prune_history(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        &format!(
+            "DELETE FROM alert_queue WHERE {finished} AND id NOT IN (
+               SELECT id FROM alert_queue WHERE {finished}
+               ORDER BY updated_at DESC LIMIT {limit})",
+            finished = FINISHED,
+            limit = HISTORY_LIMIT
+        ),
+        [],
+    )
+    .map_err(|e| format!("Failed to prune the alert queue history: {}", e))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Replay
+// ---------------------------------------------------------------------------
+
+/// Returns the payloads of unfinished alerts to hand to a new sidecar,
+/// oldest first, after marking the ones out of attempts as skipped.
+pub(crate) fn take_replay(conn: &Connection) -> Result<(Vec<Value>, usize), String> {
+    let given_up = conn
+        .execute(
+            &format!(
+                "UPDATE alert_queue SET status = 'skipped', updated_at = ?1
+                 WHERE {} AND attempts >= ?2",
+                PENDING
+            ),
+            params![now(), MAX_ATTEMPTS],
+        )
+        .map_err(|e| format!("Failed to update the alert queue: {}", e))?;
+
+    let alerts = pending(conn)?
+        .into_iter()
+        .map(|entry| entry.alert)
+        .collect();
+    Ok((alerts, given_up))
+}
+
+// ---------------------------------------------------------------------------
+// Inspection
+// ---------------------------------------------------------------------------
+
+fn entries(conn: &Connection, sql: &str) -> Result<Vec<BacklogEntry>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Failed to read the alert queue: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            let status: String = row.get("status")?;
+            let payload: String = row.get("payload")?;
+            Ok(BacklogEntry {
+                id: row.get("id")?,
+                alert_type: row.get("alert_type")?,
+                username: row.get("username")?,
+                channel: row.get("channel")?,
+                status: status_from_str(&status),
+                attempts: row.get("attempts")?,
+                enqueued_at: row.get("enqueued_at")?,
+                updated_at: row.get("updated_at")?,
+                alert: serde_json::from_str(&payload).unwrap_or(Value::Null),
+            })
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| format!("Failed to read the alert queue: {}", e))?;
+    Ok(rows)
+}
+
+fn pending(conn: &Connection) -> Result<Vec<BacklogEntry>, String> {
+    entries(
+        conn,
+        &format!(
+            "SELECT * FROM alert_queue WHERE {} ORDER BY enqueued_at ASC, rowid ASC",
+            PENDING
+        ),
+    )
+}
+
+/// Lists the pending alerts, plus the finished history if asked.
+pub(crate) fn backlog(conn: &Connection, include_finished: bool) -> Result<AlertBacklog, String> {
+    let finished = if include_finished {
+        entries(
+            conn,
+            &format!(
+                "SELECT * FROM alert_queue WHERE {} ORDER BY updated_at DESC, rowid DESC",
+                FINISHED
+            ),
+        )?
+    } else {
+        Vec::new()
+    };
+    Ok(AlertBacklog {
+        pending: pending(conn)?,
+        finished,
+    })
+}
+
+/// Deletes the pending alerts so they won't be replayed, and the finished
+/// history too if asked. Returns how many rows were removed.
+///
+/// Alerts the running server already holds stay in its queue; clear that
+/// from the dashboard.
+pub(crate) fn purge(conn: &Connection, include_finished: bool) -> Result<usize, String> {
+    let filter = if include_finished { "1" } else { PENDING };
+    conn.execute(&format!("DELETE FROM alert_queue WHERE {}", filter), [])
+        .map_err(|e| format!("Failed to purge the alert queue: {}", e))
+}
+
+/// Opens the database and runs `backlog`.
+pub(crate) fn list(app: &AppHandle, include_finished: bool) -> Result<AlertBacklog, String> {
+    let conn = db::open(app)?;
+    backlog(&conn, include_finished)
+}
+
+/// Opens the database and runs `purge`.
+pub(crate) fn clear(app: &AppHandle, include_finished: bool) -> Result<usize, String> {
+    let conn = db::open(app)?;
+    purge(&conn, include_finished)
+}
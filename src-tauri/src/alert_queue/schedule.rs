@@ -0,0 +1,783 @@
+//! Alert scheduling: the order the server plays queued alerts in.
+//!
+//! Every waiting alert gets a priority class from its type, raised or
+//! lowered by amount thresholds (a 10,000-bit cheer can be `urgent` while
+//! small cheers stay `normal`). Alerts play highest class first and in
+//! arrival order within a class. To keep low classes from starving during
+//! a raid, an alert moves up one class for every `promote_after_secs` it
+//! has waited. Alerts at or below `expire_class` that wait longer than
+//! `max_age_secs` are dropped, or collapsed into one summary alert per type
+//! and alert channel ("alice, bob and 7 others"). Each channel plays its
+//! own alerts in the planned order.
+//!
+//! `plan` is a pure function of the settings and the waiting alerts. The
+//! shell runs it whenever an alert is enqueued and every few seconds (so
+//! waiting alerts age), and sends the result to the server as a `schedule`
+//! message; the server reorders its in-memory queue to match. Without the
+//! shell, or with scheduling disabled, the server stays FIFO.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::{pending, BacklogEntry};
+use crate::db;
+use crate::sidecar::protocol::{ShellMessage, SCHEDULE_PROTOCOL_VERSION};
+use crate::sidecar::{send_to_sidecar, SidecarInfo};
+
+/// File in the app config dir holding the persisted `SchedulingSettings`.
+const CONFIG_FILE: &str = "alert_scheduling.json";
+
+/// How often waiting alerts are rescheduled so they age.
+const RESCHEDULE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Usernames spelled out in a collapsed alert before "and N others".
+const COLLAPSED_NAMES_SHOWN: usize = 3;
+
+// ---------------------------------------------------------------------------
+// Settings
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum PriorityClass {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl PriorityClass {
+    const ALL: [PriorityClass; 4] = [
+        PriorityClass::Low,
+        PriorityClass::Normal,
+        PriorityClass::High,
+        PriorityClass::Urgent,
+    ];
+
+    /// The class `steps` above this one, capped at `Urgent`.
+    fn promoted(self, steps: u64) -> Self {
+        let index = (self as usize).saturating_add(steps.min(3) as usize);
+        Self::ALL[index.min(Self::ALL.len() - 1)]
+    }
+}
+
+/// Puts alerts with at least `min_amount` (bits, viewers, money...) in
+/// `class`. Of the rules that match an alert, the one with the highest
+/// threshold wins over the type's class.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AmountRule {
+    /// Alert type the rule applies to; `None` for every type.
+    #[serde(default)]
+    pub(crate) alert_type: Option<String>,
+    pub(crate) min_amount: f64,
+    pub(crate) class: PriorityClass,
+}
+
+/// What happens to alerts that waited longer than `max_age_secs`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ExpiredAction {
+    Drop,
+    /// Merge all expired alerts of a type into one. A type with a single
+    /// expired alert is left alone.
+    #[default]
+    Collapse,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct SchedulingSettings {
+    /// Off: the server plays alerts in arrival order.
+    pub(crate) enabled: bool,
+    /// Class per alert type; types without one are `normal`.
+    pub(crate) type_classes: BTreeMap<String, PriorityClass>,
+    pub(crate) amount_rules: Vec<AmountRule>,
+    /// Waiting time after which an alert moves up one class; `None`
+    /// disables promotion.
+    pub(crate) promote_after_secs: Option<u64>,
+    /// Waiting time after which alerts at or below `expire_class` expire;
+    /// `None` keeps them forever.
+    pub(crate) max_age_secs: Option<u64>,
+    pub(crate) expire_class: PriorityClass,
+    pub(crate) expired_action: ExpiredAction,
+}
+
+impl Default for SchedulingSettings {
+    fn default() -> Self {
+        let type_classes = [
+            ("follow", PriorityClass::Low),
+            ("subscribe", PriorityClass::Normal),
+            ("cheer", PriorityClass::Normal),
+            ("donation", PriorityClass::Normal),
+            ("raid", PriorityClass::High),
+        ]
+        .into_iter()
+        .map(|(alert_type, class)| (alert_type.to_string(), class))
+        .collect();
+
+        Self {
+            enabled: true,
+            type_classes,
+            amount_rules: vec![
+                AmountRule {
+                    alert_type: Some("cheer".to_string()),
+                    min_amount: 1000.0,
+                    class: PriorityClass::Urgent,
+                },
+                AmountRule {
+                    alert_type: Some("donation".to_string()),
+                    min_amount: 50.0,
+                    class: PriorityClass::Urgent,
+                },
+            ],
+            promote_after_secs: Some(120),
+            max_age_secs: Some(300),
+            expire_class: PriorityClass::Low,
+            expired_action: ExpiredAction::Collapse,
+        }
+    }
+}
+
+impl SchedulingSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.type_classes.keys().any(|t| t.trim().is_empty()) {
+            return Err("Alert type must not be empty".to_string());
+        }
+        for rule in &self.amount_rules {
+            if !rule.min_amount.is_finite() || rule.min_amount < 0.0 {
+                return Err("Amount thresholds must be positive numbers".to_string());
+            }
+            if rule
+                .alert_type
+                .as_ref()
+                .is_some_and(|t| t.trim().is_empty())
+            {
+                return Err("Alert type must not be empty".to_string());
+            }
+        }
+        if self.promote_after_secs == Some(0) {
+            return Err("Promotion time must be at least 1 second".to_string());
+        }
+        if self.max_age_secs == Some(0) {
+            return Err("Maximum queue age must be at least 1 second".to_string());
+        }
+        Ok(())
+    }
+
+    /// The class of an alert before it has waited.
+    pub(crate) fn class_of(&self, alert_type: &str, amount: Option<f64>) -> PriorityClass {
+        let rule = amount.and_then(|amount| {
+            self.amount_rules
+                .iter()
+                .filter(|rule| {
+                    rule.alert_type.as_deref().is_none_or(|t| t == alert_type)
+                        && amount >= rule.min_amount
+                })
+                .max_by(|a, b| a.min_amount.total_cmp(&b.min_amount))
+        });
+        match rule {
+            Some(rule) => rule.class,
+            None => self
+                .type_classes
+                .get(alert_type)
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Planning
+// ---------------------------------------------------------------------------
+
+/// A waiting alert as the scheduler sees it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScheduledAlert {
+    pub(crate) id: String,
+    pub(crate) alert_type: String,
+    pub(crate) username: String,
+    pub(crate) channel: String,
+    pub(crate) amount: Option<f64>,
+    pub(crate) waited_secs: u64,
+    pub(crate) class: PriorityClass,
+    /// `class` after promotion for waiting time.
+    pub(crate) effective_class: PriorityClass,
+    pub(crate) expired: bool,
+    #[serde(skip)]
+    enqueued_at: String,
+    #[serde(skip)]
+    alert: Value,
+}
+
+/// Replaces several expired alerts of one type on one channel with a
+/// single alert.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CollapsedAlert {
+    /// IDs of the alerts it replaces, oldest first.
+    pub(crate) replaces: Vec<String>,
+    /// The summary alert, in the server's queued alert shape.
+    pub(crate) alert: Value,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SchedulePlan {
+    /// IDs of the alerts to keep, in the order they should play.
+    pub(crate) order: Vec<String>,
+    /// Expired alerts to remove.
+    pub(crate) drop: Vec<String>,
+    pub(crate) collapse: Vec<CollapsedAlert>,
+}
+
+impl SchedulePlan {
+    /// Whether the plan removes or replaces anything.
+    fn changes_queue(&self) -> bool {
+        !self.drop.is_empty() || !self.collapse.is_empty()
+    }
+}
+
+/// Reads an alert's amount, accepting numeric strings like the server.
+fn amount_of(alert: &Value) -> Option<f64> {
+    match alert.get("amount")? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Classifies a waiting alert at `now`.
+pub(crate) fn classify(
+    settings: &SchedulingSettings,
+    entry: &BacklogEntry,
+    now: DateTime<Utc>,
+) -> ScheduledAlert {
+    let waited_secs = DateTime::parse_from_rfc3339(&entry.enqueued_at)
+        .map(|at| (now - at.with_timezone(&Utc)).num_seconds().max(0) as u64)
+        .unwrap_or(0);
+    let amount = amount_of(&entry.alert);
+    let class = settings.class_of(&entry.alert_type, amount);
+    let effective_class = match settings.promote_after_secs {
+        Some(step) => class.promoted(waited_secs / step),
+        None => class,
+    };
+    let expired = settings
+        .max_age_secs
+        .is_some_and(|max| waited_secs >= max && class <= settings.expire_class);
+
+    ScheduledAlert {
+        id: entry.id.clone(),
+        alert_type: entry.alert_type.clone(),
+        username: entry.username.clone(),
+        channel: entry.channel.clone(),
+        amount,
+        waited_secs,
+        class,
+        effective_class,
+        expired,
+        enqueued_at: entry.enqueued_at.clone(),
+        alert: entry.alert.clone(),
+    }
+}
+
+/// "alice", "alice and bob", "alice, bob and carol", "alice, bob, carol
+/// and 9 others".
+fn summarize_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [one] => one.clone(),
+        _ if names.len() <= COLLAPSED_NAMES_SHOWN => format!(
+            "{} and {}",
+            names[..names.len() - 1].join(", "),
+            names[names.len() - 1]
+        ),
+        _ => format!(
+            "{} and {} others",
+            names[..COLLAPSED_NAMES_SHOWN].join(", "),
+            names.len() - COLLAPSED_NAMES_SHOWN
+        ),
+    }
+}
+
+/// Builds the summary alert for a group of expired alerts, oldest first.
+/// It plays on the first alert's channel with its config, where a
+/// template's `{username}` shows the summarized names and `{amount}` the
+/// total, and keeps the first alert's place in the queue through
+/// `enqueuedAt`.
+fn collapse(group: &[&ScheduledAlert]) -> CollapsedAlert {
+    let mut names = Vec::new();
+    for alert in group {
+        match alert
+            .alert
+            .get("collapsedUsernames")
+            .and_then(Value::as_array)
+        {
+            Some(collapsed) => {
+                names.extend(collapsed.iter().filter_map(Value::as_str).map(String::from))
+            }
+            None => names.push(
+                alert
+                    .alert
+                    .get("displayName")
+                    .and_then(Value::as_str)
+                    .unwrap_or(&alert.username)
+                    .to_string(),
+            ),
+        }
+    }
+    let amounts: Vec<f64> = group.iter().filter_map(|alert| alert.amount).collect();
+
+    let mut summary = group[0].alert.clone();
+    if let Some(object) = summary.as_object_mut() {
+        object.insert(
+            "id".to_string(),
+            Value::from(uuid::Uuid::new_v4().to_string()),
+        );
+        object.insert(
+            "displayName".to_string(),
+            Value::from(summarize_names(&names)),
+        );
+        object.insert(
+            "amount".to_string(),
+            if amounts.is_empty() {
+                Value::Null
+            } else {
+                Value::from(amounts.iter().sum::<f64>())
+            },
+        );
+        object.insert("message".to_string(), Value::Null);
+        object.insert("collapsedUsernames".to_string(), Value::from(names));
+        object.insert(
+            "enqueuedAt".to_string(),
+            Value::from(group[0].enqueued_at.clone()),
+        );
+    }
+
+    CollapsedAlert {
+        replaces: group.iter().map(|alert| alert.id.clone()).collect(),
+        alert: summary,
+    }
+}
+
+/// Works out the play order for `waiting` (oldest first), and which
+/// expired alerts to drop or collapse.
+pub(crate) fn plan(settings: &SchedulingSettings, waiting: &[ScheduledAlert]) -> SchedulePlan {
+    let mut plan = SchedulePlan::default();
+    let mut kept: Vec<&ScheduledAlert> = Vec::new();
+    let mut expired: BTreeMap<(&str, &str), Vec<&ScheduledAlert>> = BTreeMap::new();
+
+    for alert in waiting {
+        if alert.expired {
+            expired
+                .entry((&alert.channel, &alert.alert_type))
+                .or_default()
+                .push(alert);
+        } else {
+            kept.push(alert);
+        }
+    }
+
+    // Collapsed alerts take the place of their oldest member
+    let mut summaries: Vec<(usize, PriorityClass, String)> = Vec::new();
+    for group in expired.into_values() {
+        match settings.expired_action {
+            ExpiredAction::Drop => plan.drop.extend(group.iter().map(|a| a.id.clone())),
+            ExpiredAction::Collapse if group.len() == 1 => kept.push(group[0]),
+            ExpiredAction::Collapse => {
+                let collapsed = collapse(&group);
+                let position = waiting.iter().position(|a| a.id == group[0].id);
+                summaries.push((
+                    position.unwrap_or(0),
+                    group[0].effective_class,
+                    value_id(&collapsed.alert),
+                ));
+                plan.collapse.push(collapsed);
+            }
+        }
+    }
+
+    let mut ranked: Vec<(PriorityClass, usize, String)> = kept
+        .iter()
+        .map(|alert| {
+            let position = waiting.iter().position(|a| a.id == alert.id).unwrap_or(0);
+            (alert.effective_class, position, alert.id.clone())
+        })
+        .chain(
+            summaries
+                .into_iter()
+                .map(|(position, class, id)| (class, position, id)),
+        )
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    plan.order = ranked.into_iter().map(|(_, _, id)| id).collect();
+    plan
+}
+
+fn value_id(alert: &Value) -> String {
+    alert
+        .get("id")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The waiting (not yet playing) alerts in the durable queue, classified
+/// at `now`.
+fn waiting_alerts(
+    conn: &Connection,
+    settings: &SchedulingSettings,
+    now: DateTime<Utc>,
+) -> Result<Vec<ScheduledAlert>, String> {
+    Ok(pending(conn)?
+        .iter()
+        .filter(|entry| entry.status == super::QueueState::Enqueued)
+        .map(|entry| classify(settings, entry, now))
+        .collect())
+}
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+pub(crate) struct AlertScheduler(pub(crate) Mutex<SchedulerState>);
+
+pub(crate) struct SchedulerState {
+    pub(crate) settings: SchedulingSettings,
+    /// Order last sent to the server, to avoid resending an unchanged plan.
+    last_order: Vec<String>,
+}
+
+impl SchedulerState {
+    pub(crate) fn new(settings: SchedulingSettings) -> Self {
+        Self {
+            settings,
+            last_order: Vec::new(),
+        }
+    }
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Loads the persisted settings, falling back to defaults if there are none
+/// or they can't be used.
+pub(crate) fn load(app: &AppHandle) -> SchedulingSettings {
+    let Ok(path) = config_path(app) else {
+        return SchedulingSettings::default();
+    };
+    let Ok(text) = fs::read_to_string(&path) else {
+        return SchedulingSettings::default();
+    };
+
+    match serde_json::from_str::<SchedulingSettings>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(|settings| settings.validate().map(|_| settings))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!(
+                "[Tauri] Ignoring invalid alert scheduling settings {}: {}",
+                path.display(),
+                e
+            );
+            SchedulingSettings::default()
+        }
+    }
+}
+
+/// Writes the settings to the app config dir.
+fn save(app: &AppHandle, settings: &SchedulingSettings) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize alert scheduling settings: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Validates, persists and applies new settings, rescheduling the queue
+/// right away.
+pub(crate) fn update(
+    app: &AppHandle,
+    settings: SchedulingSettings,
+) -> Result<SchedulingSettings, String> {
+    settings.validate()?;
+    save(app, &settings)?;
+    {
+        let state = app.state::<AlertScheduler>();
+        let mut state = state
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to update alert scheduling settings: {}", e))?;
+        state.settings = settings.clone();
+        state.last_order.clear();
+    }
+    reschedule(app)?;
+    Ok(settings)
+}
+
+// ---------------------------------------------------------------------------
+// Scheduling
+// ---------------------------------------------------------------------------
+
+/// Whether the running sidecar understands `schedule` messages.
+fn sidecar_accepts_schedules(app: &AppHandle) -> bool {
+    app.state::<SidecarInfo>()
+        .0
+        .lock()
+        .is_ok_and(|info| info.protocol >= Some(SCHEDULE_PROTOCOL_VERSION))
+}
+
+/// Plans the waiting alerts and sends the plan to the server if it
+/// changes anything.
+pub(crate) fn reschedule(app: &AppHandle) -> Result<(), String> {
+    if !sidecar_accepts_schedules(app) {
+        return Ok(());
+    }
+    let state = app.state::<AlertScheduler>();
+    let mut state = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read alert scheduling settings: {}", e))?;
+    if !state.settings.enabled {
+        return Ok(());
+    }
+
+    let conn = db::open(app)?;
+    let waiting = waiting_alerts(&conn, &state.settings, Utc::now())?;
+    let plan = plan(&state.settings, &waiting);
+    if !plan.changes_queue() && plan.order == state.last_order {
+        return Ok(());
+    }
+
+    send_to_sidecar(app, &ShellMessage::Schedule(plan.clone()))?;
+    state.last_order = plan.order;
+    Ok(())
+}
+
+/// Starts the background thread that reschedules the queue as alerts wait.
+pub(crate) fn spawn_scheduler(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(RESCHEDULE_INTERVAL);
+        if let Err(e) = reschedule(&app) {
+            eprintln!("[Tauri] Alert scheduling failed: {}", e);
+        }
+    });
+}
+
+/// The waiting alerts in play order with their classes, and the plan the
+/// scheduler would send now.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SchedulePreview {
+    pub(crate) enabled: bool,
+    /// Oldest first.
+    pub(crate) alerts: Vec<ScheduledAlert>,
+    pub(crate) plan: SchedulePlan,
+}
+
+/// Computes the current plan without sending it.
+pub(crate) fn preview(app: &AppHandle) -> Result<SchedulePreview, String> {
+    let settings = app
+        .state::<AlertScheduler>()
+        .0
+        .lock()
+        .map(|state| state.settings.clone())
+        .map_err(|e| format!("Failed to read alert scheduling settings: {}", e))?;
+    let conn = db::open(app)?;
+    let alerts = waiting_alerts(&conn, &settings, Utc::now())?;
+    let plan = plan(&settings, &alerts);
+    Ok(SchedulePreview {
+        enabled: settings.enabled,
+        alerts,
+        plan,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{SecondsFormat, TimeZone};
+    use serde_json::json;
+
+    use super::*;
+    use crate::sidecar::protocol::QueueState;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 17, 20, 0, 0).unwrap()
+    }
+
+    fn entry(id: &str, alert_type: &str, amount: Option<f64>, waited_secs: i64) -> BacklogEntry {
+        let enqueued_at = (now() - chrono::Duration::seconds(waited_secs))
+            .to_rfc3339_opts(SecondsFormat::Millis, true);
+        BacklogEntry {
+            id: id.to_string(),
+            alert_type: alert_type.to_string(),
+            username: format!("user-{}", id),
+            channel: "default".to_string(),
+            status: QueueState::Enqueued,
+            attempts: 1,
+            enqueued_at: enqueued_at.clone(),
+            updated_at: enqueued_at,
+            alert: json!({ "id": id, "type": alert_type, "amount": amount }),
+        }
+    }
+
+    /// Classifies `entries` (oldest first) and plans them.
+    fn plan_for(settings: &SchedulingSettings, entries: &[BacklogEntry]) -> SchedulePlan {
+        let waiting: Vec<ScheduledAlert> = entries
+            .iter()
+            .map(|entry| classify(settings, entry, now()))
+            .collect();
+        plan(settings, &waiting)
+    }
+
+    #[test]
+    fn plays_higher_classes_first() {
+        let settings = SchedulingSettings::default();
+        let plan = plan_for(
+            &settings,
+            &[
+                entry("follow", "follow", None, 3),
+                entry("cheer", "cheer", Some(100.0), 2),
+                entry("raid", "raid", Some(20.0), 1),
+                entry("big-cheer", "cheer", Some(5000.0), 0),
+            ],
+        );
+        assert_eq!(plan.order, ["big-cheer", "raid", "cheer", "follow"]);
+        assert!(plan.drop.is_empty() && plan.collapse.is_empty());
+    }
+
+    #[test]
+    fn keeps_arrival_order_within_a_class() {
+        let settings = SchedulingSettings::default();
+        let plan = plan_for(
+            &settings,
+            &[
+                entry("a", "subscribe", None, 30),
+                entry("b", "cheer", Some(10.0), 20),
+                entry("c", "donation", Some(5.0), 10),
+                entry("d", "subscribe", None, 0),
+            ],
+        );
+        assert_eq!(plan.order, ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn promotes_alerts_after_waiting() {
+        let settings = SchedulingSettings {
+            promote_after_secs: Some(60),
+            max_age_secs: None,
+            ..SchedulingSettings::default()
+        };
+        let waited = classify(&settings, &entry("old", "follow", None, 61), now());
+        assert_eq!(waited.class, PriorityClass::Low);
+        assert_eq!(waited.effective_class, PriorityClass::Normal);
+        let capped = classify(&settings, &entry("ancient", "follow", None, 3600), now());
+        assert_eq!(capped.effective_class, PriorityClass::Urgent);
+
+        // A promoted follow plays before a newer cheer, a fresh one after
+        let plan = plan_for(
+            &settings,
+            &[
+                entry("old", "follow", None, 61),
+                entry("new", "follow", None, 59),
+                entry("cheer", "cheer", Some(100.0), 0),
+            ],
+        );
+        assert_eq!(plan.order, ["old", "cheer", "new"]);
+
+        let disabled = SchedulingSettings {
+            promote_after_secs: None,
+            ..settings
+        };
+        let waited = classify(&disabled, &entry("old", "follow", None, 3600), now());
+        assert_eq!(waited.effective_class, PriorityClass::Low);
+    }
+
+    #[test]
+    fn drops_alerts_past_max_age() {
+        let settings = SchedulingSettings {
+            max_age_secs: Some(300),
+            expire_class: PriorityClass::Low,
+            expired_action: ExpiredAction::Drop,
+            ..SchedulingSettings::default()
+        };
+        let plan = plan_for(
+            &settings,
+            &[
+                entry("stale", "follow", None, 300),
+                // Above the expiry class, so it waits as long as it takes
+                entry("stale-cheer", "cheer", Some(100.0), 400),
+                entry("fresh", "follow", None, 299),
+            ],
+        );
+        assert_eq!(plan.drop, ["stale"]);
+        assert_eq!(plan.order, ["stale-cheer", "fresh"]);
+        assert!(plan.changes_queue());
+
+        let forever = SchedulingSettings {
+            max_age_secs: None,
+            ..settings
+        };
+        let plan = plan_for(&forever, &[entry("stale", "follow", None, 3600)]);
+        assert!(plan.drop.is_empty());
+        assert_eq!(plan.order, ["stale"]);
+    }
+
+    #[test]
+    fn collapses_expired_alerts_per_type() {
+        let settings = SchedulingSettings {
+            promote_after_secs: None,
+            ..SchedulingSettings::default()
+        };
+        let plan = plan_for(
+            &settings,
+            &[
+                entry("a", "follow", None, 500),
+                entry("b", "follow", None, 400),
+                entry("cheer", "cheer", Some(100.0), 350),
+                entry("c", "follow", None, 301),
+                entry("fresh", "follow", None, 0),
+            ],
+        );
+        assert_eq!(plan.collapse.len(), 1);
+        let collapsed = &plan.collapse[0];
+        assert_eq!(collapsed.replaces, ["a", "b", "c"]);
+        assert_eq!(
+            collapsed.alert["displayName"],
+            json!("user-a, user-b and user-c")
+        );
+        let summary = value_id(&collapsed.alert);
+        assert_eq!(
+            plan.order,
+            ["cheer".to_string(), summary, "fresh".to_string()]
+        );
+    }
+
+    #[test]
+    fn summarizes_names() {
+        let names = |n: usize| (1..=n).map(|i| format!("u{}", i)).collect::<Vec<_>>();
+        assert_eq!(summarize_names(&names(1)), "u1");
+        assert_eq!(summarize_names(&names(2)), "u1 and u2");
+        assert_eq!(summarize_names(&names(3)), "u1, u2 and u3");
+        assert_eq!(summarize_names(&names(5)), "u1, u2, u3 and 2 others");
+    }
+}
@@ -0,0 +1,214 @@
+//! Named alert channels.
+//!
+//! Each channel has its own queue and pause state on the server, so
+//! different overlays (OBS scenes) can play different alerts side by side.
+//! An overlay picks its channel with `?channel=<id>` on the browser source
+//! URL; without it, it plays `default`.
+//!
+//! Routing is by alert type: an alert goes to every enabled channel that
+//! lists its type, and to `default` if none does. `default` always exists
+//! and can't be disabled or deleted.
+//!
+//! Channels live in the `alert_channels` table. The shell is the only
+//! writer; after a change it tells the server to reload them with a
+//! `channels` control message.
+
+use chrono::{SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::db;
+use crate::sidecar::protocol::{ShellMessage, CHANNELS_PROTOCOL_VERSION};
+use crate::sidecar::{send_to_sidecar, SidecarInfo};
+
+/// Channel for overlays without `?channel` and for alerts no channel claims.
+pub(crate) const DEFAULT_CHANNEL: &str = "default";
+
+/// Longest channel ID accepted, to keep browser source URLs readable.
+const MAX_ID_LEN: usize = 32;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlertChannel {
+    /// Slug used in `?channel=`: lowercase letters, digits, `-` and `_`.
+    pub(crate) id: String,
+    pub(crate) name: String,
+    /// Alert types routed to this channel.
+    #[serde(default)]
+    pub(crate) alert_types: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub(crate) enabled: bool,
+    /// Set by `save`; ignored on input.
+    #[serde(default)]
+    pub(crate) created_at: String,
+    #[serde(default)]
+    pub(crate) updated_at: String,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl AlertChannel {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty()
+            || self.id.len() > MAX_ID_LEN
+            || !self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Channel ID must be 1-{} lowercase letters, digits, '-' or '_'",
+                MAX_ID_LEN
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err("Channel name must not be empty".to_string());
+        }
+        if self.alert_types.iter().any(|t| t.trim().is_empty()) {
+            return Err("Alert types must not be empty".to_string());
+        }
+        if self.id == DEFAULT_CHANNEL && !self.enabled {
+            return Err("The default channel can't be disabled".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn from_row(row: &rusqlite::Row) -> rusqlite::Result<AlertChannel> {
+    let alert_types: String = row.get("alert_types")?;
+    Ok(AlertChannel {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        alert_types: serde_json::from_str(&alert_types).unwrap_or_default(),
+        enabled: row.get("enabled")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Storage
+// ---------------------------------------------------------------------------
+
+/// Lists every channel, `default` first, then oldest first.
+pub(crate) fn all(conn: &Connection) -> Result<Vec<AlertChannel>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM alert_channels
+             ORDER BY id = ?1 DESC, created_at ASC, id ASC",
+        )
+        .map_err(|e| format!("Failed to read alert channels: {}", e))?;
+    let rows = stmt
+        .query_map([DEFAULT_CHANNEL], from_row)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| format!("Failed to read alert channels: {}", e))?;
+    Ok(rows)
+}
+
+/// Creates or updates a channel, keeping its creation time. Duplicate
+/// alert types are dropped. Returns the stored channel.
+pub(crate) fn upsert(conn: &Connection, mut channel: AlertChannel) -> Result<AlertChannel, String> {
+    channel.validate()?;
+    channel.name = channel.name.trim().to_string();
+    let mut alert_types: Vec<String> = Vec::new();
+    for alert_type in &channel.alert_types {
+        let alert_type = alert_type.trim().to_string();
+        if !alert_types.contains(&alert_type) {
+            alert_types.push(alert_type);
+        }
+    }
+    channel.alert_types = alert_types;
+
+    let types = serde_json::to_string(&channel.alert_types)
+        .map_err(|e| format!("Failed to serialize alert types: {}", e))?;
+    let now = now();
+    conn.execute(
+        "INSERT INTO alert_channels (id, name, alert_types, enabled, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+         ON CONFLICT(id) DO UPDATE SET
+           name = excluded.name,
+           alert_types = excluded.alert_types,
+           enabled = excluded.enabled,
+           updated_at = excluded.updated_at",
+        params![channel.id, channel.name, types, channel.enabled, now],
+    )
+    .map_err(|e| format!("Failed to save alert channel {}: {}", channel.id, e))?;
+
+    conn.query_row(
+        "SELECT * FROM alert_channels WHERE id = ?1",
+        [&channel.id],
+        from_row,
+    )
+    .map_err(|e| format!("Failed to read alert channel {}: {}", channel.id, e))
+}
+
+/// Deletes a channel. Returns `false` if it didn't exist.
+pub(crate) fn remove(conn: &Connection, id: &str) -> Result<bool, String> {
+    if id == DEFAULT_CHANNEL {
+        return Err("The default channel can't be deleted".to_string());
+    }
+    let exists = conn
+        .query_row("SELECT 1 FROM alert_channels WHERE id = ?1", [id], |_| {
+            Ok(())
+        })
+        .optional()
+        .map_err(|e| format!("Failed to read alert channel {}: {}", id, e))?
+        .is_some();
+    if exists {
+        conn.execute("DELETE FROM alert_channels WHERE id = ?1", [id])
+            .map_err(|e| format!("Failed to delete alert channel {}: {}", id, e))?;
+    }
+    Ok(exists)
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Tells the running server to reload its channels. A server too old to
+/// know about channels keeps playing everything on one queue.
+fn reload(app: &AppHandle) {
+    let accepts = app
+        .state::<SidecarInfo>()
+        .0
+        .lock()
+        .is_ok_and(|info| info.protocol >= Some(CHANNELS_PROTOCOL_VERSION));
+    if !accepts {
+        return;
+    }
+    if let Err(e) = send_to_sidecar(app, &ShellMessage::ReloadChannels) {
+        eprintln!("[Tauri] Failed to reload alert channels: {}", e);
+    }
+}
+
+/// Opens the database and runs `all`.
+pub(crate) fn list(app: &AppHandle) -> Result<Vec<AlertChannel>, String> {
+    let conn = db::open(app)?;
+    all(&conn)
+}
+
+/// Opens the database, runs `upsert` and has the server reload.
+pub(crate) fn save(app: &AppHandle, channel: AlertChannel) -> Result<AlertChannel, String> {
+    let conn = db::open(app)?;
+    let channel = upsert(&conn, channel)?;
+    reload(app);
+    Ok(channel)
+}
+
+/// Opens the database, runs `remove` and has the server reload. Alerts
+/// waiting on the deleted channel move to `default`.
+pub(crate) fn delete(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let conn = db::open(app)?;
+    let removed = remove(&conn, id)?;
+    if removed {
+        reload(app);
+    }
+    Ok(removed)
+}
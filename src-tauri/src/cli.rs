@@ -0,0 +1,291 @@
+//! Command-line subcommands for scripting a running StreamForge instance,
+//! e.g. from stream-deck buttons or shell hotkeys:
+//!
+//! ```text
+//! streamforge status
+//! streamforge alert fire [--type follow] [--user name] [--amount 100] [--message text]
+//! streamforge queue pause|resume|skip|clear
+//! ```
+//!
+//! The server port is discovered through the port file the running
+//! instance publishes (see `port_file`), so scripts never need to know it.
+//! Every command prints a single JSON object to stdout and exits non-zero on
+//! failure.
+//!
+//! Release builds are GUI apps on Windows and start without a console, so
+//! subcommands attach to the console of the shell that ran them. `cmd.exe`
+//! doesn't wait for GUI apps, so its prompt may come back before the
+//! output; scripts that need the exit code should use
+//! `start /wait streamforge ...` or PowerShell's `Start-Process -Wait`.
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use ureq::SendBody;
+
+use crate::port_file;
+use crate::sidecar::launch;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const USAGE: &str = "\
+Usage:
+  streamforge [--headless]
+  streamforge status
+  streamforge alert fire [--type TYPE] [--user NAME] [--amount N] [--message TEXT]
+  streamforge queue pause|resume|skip|clear";
+
+/// A parsed subcommand.
+#[derive(Debug)]
+enum Command {
+    Status,
+    FireAlert(Map<String, Value>),
+    Queue(QueueAction),
+    Help,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum QueueAction {
+    Pause,
+    Resume,
+    Skip,
+    Clear,
+}
+
+/// Runs the subcommand given in `args` (without the program name).
+///
+/// Returns `None` when `args` don't name a subcommand, in which case the app
+/// should start normally; otherwise the process exit code.
+pub(crate) fn run(identifier: &str, args: &[String]) -> Option<i32> {
+    let parsed = parse(args)?;
+    attach_console();
+    let command = match parsed {
+        Ok(command) => command,
+        Err(e) => {
+            print_error(&e);
+            eprintln!("{}", USAGE);
+            return Some(2);
+        }
+    };
+
+    let result = match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            return Some(0);
+        }
+        Command::Status => status(identifier),
+        Command::FireAlert(body) => call(identifier, "POST", "/api/test-alert", Some(body)),
+        Command::Queue(action) => {
+            let (path, body) = match action {
+                QueueAction::Pause => ("/api/test-alert/pause", Some(pause_body(true))),
+                QueueAction::Resume => ("/api/test-alert/pause", Some(pause_body(false))),
+                QueueAction::Skip => ("/api/test-alert/skip", None),
+                QueueAction::Clear => ("/api/test-alert/clear", None),
+            };
+            call(identifier, "POST", path, body)
+        }
+    };
+
+    match result {
+        Ok(output) => {
+            println!("{}", output);
+            Some(0)
+        }
+        Err(e) => {
+            print_error(&e);
+            Some(1)
+        }
+    }
+}
+
+/// Routes stdout and stderr to the parent's console, unless they were
+/// redirected already (`streamforge status > status.json`).
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // SAFETY: `AttachConsole` has no memory-safety preconditions; without a
+    // parent console it just fails and output stays detached.
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+/// Console programs everywhere else; nothing to do.
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn pause_body(paused: bool) -> Map<String, Value> {
+    let mut body = Map::new();
+    body.insert("paused".to_string(), Value::Bool(paused));
+    body
+}
+
+fn print_error(message: &str) {
+    println!("{}", json!({ "ok": false, "error": message }));
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+/// `None` if the first argument isn't a subcommand.
+fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let (first, rest) = args.split_first()?;
+    let rest: Vec<&str> = rest.iter().map(String::as_str).collect();
+
+    let command = match first.as_str() {
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        "status" => expect_no_args(&rest).map(|_| Command::Status),
+        "alert" => match rest.split_first() {
+            Some((&"fire", options)) => parse_alert(options).map(Command::FireAlert),
+            Some((other, _)) => Err(format!("Unknown alert command {:?}", other)),
+            None => Err("Missing alert command (expected `fire`)".to_string()),
+        },
+        "queue" => match rest.split_first() {
+            Some((action, options)) => expect_no_args(options).and_then(|_| {
+                match *action {
+                    "pause" => Ok(QueueAction::Pause),
+                    "resume" => Ok(QueueAction::Resume),
+                    "skip" => Ok(QueueAction::Skip),
+                    "clear" => Ok(QueueAction::Clear),
+                    other => Err(format!("Unknown queue command {:?}", other)),
+                }
+                .map(Command::Queue)
+            }),
+            None => Err("Missing queue command (pause, resume, skip or clear)".to_string()),
+        },
+        _ => return None,
+    };
+    Some(command)
+}
+
+fn expect_no_args(args: &[&str]) -> Result<(), String> {
+    match args.first() {
+        Some(arg) => Err(format!("Unexpected argument {:?}", arg)),
+        None => Ok(()),
+    }
+}
+
+/// Turns `--type follow --user x ...` into the `/api/test-alert` body.
+fn parse_alert(options: &[&str]) -> Result<Map<String, Value>, String> {
+    let mut body = Map::new();
+    let mut iter = options.iter();
+
+    while let Some(option) = iter.next() {
+        let (name, inline) = match option.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (*option, None),
+        };
+        let key = match name {
+            "--type" => "type",
+            "--user" => "username",
+            "--display-name" => "displayName",
+            "--amount" => "amount",
+            "--message" => "message",
+            "--tier" => "tier",
+            other => return Err(format!("Unknown option {:?}", other)),
+        };
+        let value = match inline {
+            Some(value) => value,
+            None => iter
+                .next()
+                .copied()
+                .ok_or_else(|| format!("Missing value for {}", name))?,
+        };
+
+        let value = if key == "amount" {
+            let amount: f64 = value
+                .parse()
+                .map_err(|_| format!("--amount must be a number, got {:?}", value))?;
+            json!(amount)
+        } else {
+            Value::String(value.to_string())
+        };
+        body.insert(key.to_string(), value);
+    }
+
+    Ok(body)
+}
+
+// ---------------------------------------------------------------------------
+// Requests
+// ---------------------------------------------------------------------------
+
+/// Where the running server can be reached.
+fn locate(identifier: &str) -> Result<(IpAddr, u16), String> {
+    let port = port_file::read(identifier)
+        .ok_or("StreamForge is not running (no server port published)")?;
+    let ip = launch::load_for_identifier(identifier).probe_address();
+    Ok((ip, port))
+}
+
+/// Sends a request and returns the JSON response body.
+fn request_json(
+    ip: IpAddr,
+    port: u16,
+    method: &str,
+    path: &str,
+    body: Option<Map<String, Value>>,
+) -> Result<Value, String> {
+    let mut request = ureq::http::Request::builder()
+        .method(method)
+        .uri(launch::server_url(ip, port, path));
+    let body = match body {
+        Some(body) => {
+            request = request.header("Content-Type", "application/json");
+            SendBody::from_json(&Value::Object(body))
+                .map_err(|e| format!("{} {}: {}", method, path, e))?
+        }
+        None => SendBody::none(),
+    };
+    let request = request
+        .body(body)
+        .map_err(|e| format!("{} {}: {}", method, path, e))?;
+    let mut response = launch::server_agent(REQUEST_TIMEOUT)
+        .run(request)
+        .map_err(|e| format!("{} {}: {}", method, path, e))?;
+
+    let status = response.status().as_u16();
+    let value: Value = response
+        .body_mut()
+        .read_json()
+        .map_err(|e| format!("{} {} returned invalid JSON: {}", method, path, e))?;
+    if !(200..300).contains(&status) {
+        let detail = value
+            .get("error")
+            .or_else(|| value.get("message"))
+            .and_then(Value::as_str)
+            .unwrap_or("request failed");
+        return Err(format!(
+            "{} {} returned HTTP {}: {}",
+            method, path, status, detail
+        ));
+    }
+    Ok(value)
+}
+
+fn call(
+    identifier: &str,
+    method: &str,
+    path: &str,
+    body: Option<Map<String, Value>>,
+) -> Result<Value, String> {
+    let (ip, port) = locate(identifier)?;
+    request_json(ip, port, method, path, body)
+}
+
+fn status(identifier: &str) -> Result<Value, String> {
+    let (ip, port) = locate(identifier)?;
+    let health = request_json(ip, port, "GET", "/api/health", None)?;
+    let queue = request_json(ip, port, "GET", "/api/test-alert/status", None)?;
+    let sockets = request_json(ip, port, "GET", "/api/ws/status", None)?;
+
+    Ok(json!({
+        "ok": true,
+        "running": true,
+        "port": port,
+        "health": health,
+        "queue": queue,
+        "clients": sockets.get("clients").cloned().unwrap_or(Value::Null),
+    }))
+}
@@ -0,0 +1,299 @@
+//! Alert variation conditions.
+//!
+//! A small expression language evaluated against an event's data
+//! (`username`, `amount`, `message`, `tier`, `platform`, ...):
+//!
+//! ```text
+//! cond     := and ( "or" and )*
+//! and      := not ( "and" not )*
+//! not      := "not" not | "(" cond ")" | test
+//! test     := operand
+//!           | operand ( "==" | "!=" | ">" | ">=" | "<" | "<=" ) operand
+//!           | operand "between" operand "and" operand
+//!           | operand "contains" operand
+//!           | operand ( "in" | "not in" ) "[" operand ( "," operand )* "]"
+//! operand  := variable | number | string | "true" | "false"
+//! string   := '"' ... '"' | "'" ... "'"      (\" \' \\ escapes)
+//! variable := [A-Za-z_][A-Za-z0-9_]*
+//! ```
+//!
+//! Examples: `tier == 2`, `amount between 100 and 499`,
+//! `message contains "hype" or username in ["alice", "bob"]`,
+//! `platform == "twitch" and not (amount < 10)`.
+//!
+//! Comparisons are numeric when both sides are numbers (numeric strings
+//! count); otherwise `==`, `!=`, `contains` and `in` compare text
+//! case-insensitively and the ordering operators are false. `between` is
+//! inclusive. A bare operand is true unless it is missing, empty, `0` or
+//! `false`.
+//!
+//! The lexer, parser and evaluator live in `expression.rs`, shared with
+//! template conditions. `server/alerts/conditions.js` implements the same
+//! language for the server; keep the two in step.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::expression::{error, Dialect, Expression, ParseError, SourceIssue, Span};
+
+/// Event fields alerts are matched on; other names only warn when used.
+pub(crate) const EVENT_VARIABLES: [&str; 7] = [
+    "username",
+    "amount",
+    "message",
+    "tier",
+    "platform",
+    "type",
+    "custom_value",
+];
+
+/// A parsed condition.
+#[derive(Debug)]
+pub(crate) struct Condition {
+    expression: Expression,
+}
+
+impl Condition {
+    pub(crate) fn parse(text: &str) -> Result<Self, ParseError> {
+        if text.trim().is_empty() {
+            return error("Empty condition", (0, text.len()));
+        }
+        let expression = Expression::parse(text, 0, Dialect::Condition)?;
+        Ok(Self { expression })
+    }
+
+    pub(crate) fn evaluate(&self, variables: &Map<String, Value>) -> bool {
+        self.expression.evaluate(variables)
+    }
+
+    /// Every variable reference, in order.
+    pub(crate) fn variables(&self) -> Vec<(&str, Span)> {
+        self.expression.variables()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConditionCheck {
+    pub(crate) valid: bool,
+    pub(crate) error: Option<SourceIssue>,
+    /// Variables events don't carry, which are always missing.
+    pub(crate) warnings: Vec<SourceIssue>,
+    /// Variables the condition uses, in order of first use.
+    pub(crate) variables: Vec<String>,
+    /// Whether `event` matches; `None` without an event or if invalid.
+    pub(crate) matches: Option<bool>,
+}
+
+/// Parses `text` and, given sample event data, evaluates it.
+pub(crate) fn check(text: &str, event: Option<&Map<String, Value>>) -> ConditionCheck {
+    let condition = match Condition::parse(text) {
+        Ok(condition) => condition,
+        Err(e) => {
+            return ConditionCheck {
+                valid: false,
+                error: Some(SourceIssue::new(text, e.message, e.span)),
+                warnings: Vec::new(),
+                variables: Vec::new(),
+                matches: None,
+            }
+        }
+    };
+
+    let mut variables: Vec<String> = Vec::new();
+    let mut warnings = Vec::new();
+    for (name, span) in condition.variables() {
+        if !EVENT_VARIABLES.contains(&name) {
+            warnings.push(SourceIssue::new(
+                text,
+                format!(
+                    "Events have no \"{}\" (available: {})",
+                    name,
+                    EVENT_VARIABLES.join(", ")
+                ),
+                span,
+            ));
+        }
+        if !variables.iter().any(|v| v == name) {
+            variables.push(name.to_string());
+        }
+    }
+    ConditionCheck {
+        valid: true,
+        error: None,
+        warnings,
+        variables,
+        matches: event.map(|event| condition.evaluate(event)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn event() -> Map<String, Value> {
+        json!({
+            "username": "Alice",
+            "amount": 250,
+            "message": "Big HYPE train",
+            "tier": "2",
+            "platform": "twitch",
+            "custom_value": "",
+            "tags": ["vip", "First"],
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    }
+
+    /// Evaluates every `(expression, expected)` against `event()`.
+    fn assert_table(cases: &[(&str, bool)]) {
+        let event = event();
+        for (text, expected) in cases {
+            let condition = Condition::parse(text)
+                .unwrap_or_else(|e| panic!("{:?} failed to parse: {}", text, e.message));
+            assert_eq!(condition.evaluate(&event), *expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        assert_table(&[
+            // and binds tighter than or
+            ("true or true and false", true),
+            ("false and true or true", true),
+            ("(true or true) and false", false),
+            // not binds tighter than and
+            ("not false and false", false),
+            ("not (false and false)", true),
+            ("not not tier", true),
+            ("not amount > 500 and tier == 2", true),
+            ("not (amount < 10) and platform == \"twitch\"", true),
+        ]);
+    }
+
+    #[test]
+    fn between() {
+        assert_table(&[
+            ("amount between 100 and 499", true),
+            ("amount between 250 and 250", true),
+            ("amount between 251 and 499", false),
+            ("tier between 1 and 2", true),
+            ("username between 1 and 2", false),
+            ("missing between 0 and 1", false),
+            ("amount between 0 and 1000 and tier == 2", true),
+        ]);
+    }
+
+    #[test]
+    fn in_and_not_in() {
+        assert_table(&[
+            ("username in [\"bob\", \"alice\"]", true),
+            ("username in [\"bob\"]", false),
+            ("username not in [\"bob\"]", true),
+            ("username not in [\"ALICE\"]", false),
+            ("tier in [1, 2]", true),
+            ("amount in [\"250.0\"]", true),
+            ("amount in []", false),
+            ("not tier in [3]", true),
+            ("tags contains \"first\"", true),
+            ("tags contains \"mod\"", false),
+            ("message contains \"hype\"", true),
+        ]);
+    }
+
+    #[test]
+    fn escaped_strings() {
+        let condition = Condition::parse(r#"message == "say \"hi\" \\ 'now'""#).unwrap();
+        let data = json!({ "message": r#"say "hi" \ 'now'"# });
+        assert!(condition.evaluate(data.as_object().unwrap()));
+
+        let condition = Condition::parse(r#"message == 'it\'s "fine"'"#).unwrap();
+        let data = json!({ "message": r#"it's "fine""# });
+        assert!(condition.evaluate(data.as_object().unwrap()));
+    }
+
+    #[test]
+    fn numeric_and_text_comparison() {
+        assert_table(&[
+            // Numeric when both sides are numbers, numeric strings included
+            ("tier == 2", true),
+            ("tier == 2.0", true),
+            ("tier > 1", true),
+            ("amount >= \"250\"", true),
+            ("amount < 1000", true),
+            ("amount != 250", false),
+            // Text otherwise, ignoring case
+            ("username == \"alice\"", true),
+            ("username != \"ALICE\"", false),
+            ("platform == \"youtube\"", false),
+            // Ordering needs numbers
+            ("username > \"a\"", false),
+            ("username <= \"z\"", false),
+            ("missing > 0", false),
+            // Truthiness of bare operands
+            ("custom_value", false),
+            ("missing", false),
+            ("0", false),
+            ("\"false\"", false),
+            ("tags", true),
+        ]);
+    }
+
+    #[test]
+    fn error_spans() {
+        let cases: [(&str, &str, Span); 10] = [
+            ("", "Empty condition", (0, 0)),
+            ("amount >", "Incomplete condition", (8, 8)),
+            (
+                "amount > > 1",
+                "Expected a variable, number or string",
+                (9, 10),
+            ),
+            ("(tier == 2", "Expected \")\"", (10, 10)),
+            (
+                "tier == 2 tier",
+                "Unexpected text after the condition",
+                (10, 14),
+            ),
+            ("message == \"hi", "Unclosed string", (11, 14)),
+            (
+                "amount between 1 or 2",
+                "Expected \"and\" in \"between ... and ...\"",
+                (17, 19),
+            ),
+            ("tier in [1 2]", "Expected \",\" or \"]\"", (11, 12)),
+            ("tier in 1", "Expected \"[\" to start a list", (8, 9)),
+            ("amount == 1.2.3", "Invalid number \"1.2.3\"", (10, 15)),
+        ];
+        for (text, message, span) in cases {
+            let error = Condition::parse(text).expect_err(text);
+            assert_eq!(
+                (error.message.as_str(), error.span),
+                (message, span),
+                "{}",
+                text
+            );
+        }
+        let error = Condition::parse("tier == and").unwrap_err();
+        assert_eq!(error.message, "Expected a value, found \"and\"");
+    }
+
+    #[test]
+    fn check_reports_utf16_offsets_and_unknown_variables() {
+        let result = check("username == \"é\" and colour", Some(&event()));
+        assert!(result.valid);
+        assert_eq!(result.variables, ["username", "colour"]);
+        assert_eq!(result.warnings.len(), 1);
+        let warning = &result.warnings[0];
+        assert_eq!((warning.start, warning.end), (20, 26));
+        assert_eq!(result.matches, Some(false));
+
+        let result = check("\"é\" ==", None);
+        assert!(!result.valid);
+        let error = result.error.unwrap();
+        assert_eq!((error.start, error.end), (6, 6));
+    }
+}
@@ -0,0 +1,479 @@
+//! Backups of `streamforge.db`.
+//!
+//! Backups are taken with SQLite's online backup API, so they are
+//! consistent even while the sidecar is writing. They live in a `backups`
+//! folder next to the database and are named `<kind>-<timestamp>.db`; only
+//! `auto` backups (taken on app start) are rotated.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use rusqlite::{Connection, OpenFlags, MAIN_DB};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use super::{data_dir, db_path, migrations, open};
+use crate::sidecar;
+use crate::sidecar::status::{current_status, SidecarStatus};
+
+/// File in the app config dir holding the persisted `BackupSettings`.
+const CONFIG_FILE: &str = "backups.json";
+
+const BACKUP_DIR: &str = "backups";
+
+/// Upper bound for `BackupSettings::keep`.
+const MAX_KEEP: u32 = 100;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum BackupKind {
+    /// Taken on app start; only the newest `keep` are retained.
+    Auto,
+    /// Requested by the user.
+    Manual,
+    /// Safety copy of the database a restore replaced.
+    PreRestore,
+}
+
+impl BackupKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Manual => "manual",
+            Self::PreRestore => "pre-restore",
+        }
+    }
+
+    fn from_file_name(name: &str) -> Option<Self> {
+        [Self::PreRestore, Self::Auto, Self::Manual]
+            .into_iter()
+            .find(|kind| name.starts_with(&format!("{}-", kind.prefix())))
+    }
+}
+
+/// Orders backup names written by `create`: the timestamp as
+/// `YYYYMMDDHHMMSS`, the milliseconds (absent before they were added) and
+/// the collision suffix, which is 1 for the unsuffixed name.
+fn name_order(file_name: &str) -> Option<(u64, u32, u32)> {
+    let kind = BackupKind::from_file_name(file_name)?;
+    let stamp = file_name
+        .strip_prefix(kind.prefix())?
+        .strip_prefix('-')?
+        .strip_suffix(".db")?;
+    let mut parts = stamp.split('-');
+    let (date, time) = (parts.next()?, parts.next()?);
+    if date.len() != 8 || time.len() != 6 {
+        return None;
+    }
+    let seconds = format!("{}{}", date, time).parse().ok()?;
+    let millis = parts.next().map_or(Some(0), |ms| ms.parse().ok())?;
+    let suffix = parts.next().map_or(Some(1), |n| n.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((seconds, millis, suffix))
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupInfo {
+    pub(crate) path: String,
+    pub(crate) file_name: String,
+    pub(crate) kind: BackupKind,
+    pub(crate) size_bytes: u64,
+    /// ISO timestamp of when the backup was written.
+    pub(crate) created_at: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IntegrityReport {
+    pub(crate) ok: bool,
+    /// Problems reported by `PRAGMA integrity_check` (empty when `ok`).
+    pub(crate) errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RestoreReport {
+    pub(crate) restored_from: String,
+    /// Copy of the database as it was before the restore.
+    pub(crate) safety_backup: BackupInfo,
+    /// Whether the sidecar was running and has been started again.
+    pub(crate) sidecar_restarted: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Settings
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct BackupSettings {
+    /// Back up the database every time the app starts.
+    pub(crate) auto_backup: bool,
+    /// Number of automatic backups to keep.
+    pub(crate) keep: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            auto_backup: true,
+            keep: 5,
+        }
+    }
+}
+
+impl BackupSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.keep == 0 || self.keep > MAX_KEEP {
+            return Err(format!(
+                "Number of backups to keep must be between 1 and {}",
+                MAX_KEEP
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Backups(pub(crate) Mutex<BackupSettings>);
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Loads the persisted settings, falling back to defaults if there are none
+/// or they can't be used.
+pub(crate) fn load(app: &AppHandle) -> BackupSettings {
+    let Ok(path) = config_path(app) else {
+        return BackupSettings::default();
+    };
+    let Ok(text) = fs::read_to_string(&path) else {
+        return BackupSettings::default();
+    };
+
+    match serde_json::from_str::<BackupSettings>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(|settings| settings.validate().map(|_| settings))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!(
+                "[Tauri] Ignoring invalid backup settings {}: {}",
+                path.display(),
+                e
+            );
+            BackupSettings::default()
+        }
+    }
+}
+
+/// Writes the settings to the app config dir.
+pub(crate) fn save(app: &AppHandle, settings: &BackupSettings) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize backup settings: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn current_settings(app: &AppHandle) -> BackupSettings {
+    app.state::<Backups>()
+        .0
+        .lock()
+        .map(|settings| settings.clone())
+        .unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Backup
+// ---------------------------------------------------------------------------
+
+fn backup_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    data_dir(app).map(|dir| dir.join(BACKUP_DIR))
+}
+
+fn describe(path: &Path) -> Option<BackupInfo> {
+    let file_name = path.file_name()?.to_string_lossy().into_owned();
+    let kind = BackupKind::from_file_name(&file_name)?;
+    let metadata = fs::metadata(path).ok()?;
+    let created_at = metadata
+        .modified()
+        .map(|time| DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default();
+
+    Some(BackupInfo {
+        path: path.display().to_string(),
+        file_name,
+        kind,
+        size_bytes: metadata.len(),
+        created_at,
+    })
+}
+
+/// Copies the live database into the backup folder.
+pub(crate) fn create(app: &AppHandle, kind: BackupKind) -> Result<BackupInfo, String> {
+    let dir = backup_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    // Milliseconds keep names sorting chronologically; the suffix covers two
+    // backups in the same millisecond (or a clock that went back)
+    let stem = format!(
+        "{}-{}",
+        kind.prefix(),
+        Local::now().format("%Y%m%d-%H%M%S-%3f")
+    );
+    let mut dest = dir.join(format!("{}.db", stem));
+    let mut n = 1;
+    while dest.exists() {
+        n += 1;
+        dest = dir.join(format!("{}-{}.db", stem, n));
+    }
+
+    let conn = open(app)?;
+    conn.backup(MAIN_DB, &dest, None)
+        .map_err(|e| format!("Failed to back up the database: {}", e))?;
+
+    println!("[Tauri] Database backed up to {}", dest.display());
+    describe(&dest).ok_or_else(|| format!("Backup {} is unreadable", dest.display()))
+}
+
+/// Backups in the backup folder, newest first.
+pub(crate) fn list(app: &AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let dir = backup_dir(app)?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "db"))
+        .filter_map(|path| describe(&path))
+        .collect();
+    // By the time in the name, which survives copying the folder; files
+    // renamed by hand go last, by modification time
+    backups.sort_by(|a, b| {
+        name_order(&b.file_name)
+            .cmp(&name_order(&a.file_name))
+            .then_with(|| b.created_at.cmp(&a.created_at))
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    Ok(backups)
+}
+
+/// Deletes automatic backups beyond the newest `keep`.
+fn rotate(app: &AppHandle, keep: u32) -> Result<(), String> {
+    let stale = list(app)?
+        .into_iter()
+        .filter(|backup| backup.kind == BackupKind::Auto)
+        .skip(keep as usize);
+    for backup in stale {
+        fs::remove_file(&backup.path)
+            .map_err(|e| format!("Failed to delete old backup {}: {}", backup.path, e))?;
+    }
+    Ok(())
+}
+
+/// Takes the automatic start-up backup if enabled. Call before the sidecar
+/// starts so the backup predates any migrations.
+pub(crate) fn backup_on_start(app: &AppHandle) {
+    let settings = current_settings(app);
+    if !settings.auto_backup {
+        return;
+    }
+    // Nothing to back up on first launch
+    if !db_path(app).is_ok_and(|path| path.exists()) {
+        return;
+    }
+
+    if let Err(e) = create(app, BackupKind::Auto).and_then(|_| rotate(app, settings.keep)) {
+        eprintln!("[Tauri] Automatic database backup failed: {}", e);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Integrity
+// ---------------------------------------------------------------------------
+
+fn integrity_check(conn: &Connection) -> Result<IntegrityReport, String> {
+    let run = || -> rusqlite::Result<Vec<String>> {
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect()
+    };
+    let messages = run().map_err(|e| format!("Integrity check failed to run: {}", e))?;
+
+    let ok = messages.len() == 1 && messages[0] == "ok";
+    Ok(IntegrityReport {
+        ok,
+        errors: if ok { Vec::new() } else { messages },
+    })
+}
+
+/// Runs `PRAGMA integrity_check` on the live database.
+pub(crate) fn check(app: &AppHandle) -> Result<IntegrityReport, String> {
+    integrity_check(&open(app)?)
+}
+
+// ---------------------------------------------------------------------------
+// Restore
+// ---------------------------------------------------------------------------
+
+/// Makes sure `path` is an intact StreamForge database this build can run.
+fn validate_source(path: &Path) -> Result<(), String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    let report = integrity_check(&conn)
+        .map_err(|_| format!("{} is not a SQLite database", path.display()))?;
+    if !report.ok {
+        return Err(format!(
+            "{} is damaged: {}",
+            path.display(),
+            report.errors.join("; ")
+        ));
+    }
+
+    let has_migrations = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_migrations'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if has_migrations == 0 {
+        return Err(format!("{} is not a StreamForge database", path.display()));
+    }
+
+    let unknown = migrations::unknown_applied(&conn)?;
+    if !unknown.is_empty() {
+        return Err(format!(
+            "{} was created by a newer StreamForge (unknown migrations: {})",
+            path.display(),
+            unknown.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Asks the user for a database file to restore. `None` if cancelled.
+pub(crate) fn pick_source(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    let mut dialog = app
+        .dialog()
+        .file()
+        .set_title("Restore StreamForge database")
+        .add_filter("SQLite database", &["db", "sqlite", "sqlite3"]);
+    if let Ok(dir) = backup_dir(app) {
+        dialog = dialog.set_directory(dir);
+    }
+
+    dialog
+        .blocking_pick_file()
+        .map(|file| {
+            file.into_path()
+                .map_err(|e| format!("Unsupported file selection: {}", e))
+        })
+        .transpose()
+}
+
+/// Replaces the live database with `source`. The sidecar is stopped for
+/// the duration and started again (running any migrations the restored
+/// database lacks) unless the user had stopped it, whether or not the
+/// restore succeeded. Blocks, so call off the main thread.
+pub(crate) fn restore(app: &AppHandle, source: &Path) -> Result<RestoreReport, String> {
+    validate_source(source)?;
+    if db_path(app).is_ok_and(|live| fs::canonicalize(&live).ok() == fs::canonicalize(source).ok())
+    {
+        return Err("Cannot restore the live database onto itself".to_string());
+    }
+
+    // Also true while the server is starting, unhealthy or waiting for a
+    // crash restart, which the stop below cancels
+    let should_run = current_status(app) != SidecarStatus::Stopped;
+    sidecar::stop_sidecar_on_request(app);
+
+    let result = create(app, BackupKind::PreRestore).and_then(|safety_backup| {
+        let mut conn = open(app)?;
+        conn.restore(MAIN_DB, source, None::<fn(rusqlite::backup::Progress)>)
+            .map_err(|e| format!("Failed to restore {}: {}", source.display(), e))?;
+        Ok(safety_backup)
+    });
+
+    // Bring the server back even if the restore failed, so the app stays usable
+    let sidecar_restarted = should_run && {
+        match sidecar::start_stopped_sidecar(app) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("[Tauri] Sidecar restart after restore failed: {}", e);
+                false
+            }
+        }
+    };
+
+    let safety_backup = result?;
+    println!("[Tauri] Database restored from {}", source.display());
+    Ok(RestoreReport {
+        restored_from: source.display().to_string(),
+        safety_backup,
+        sidecar_restarted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_names_by_timestamp_and_suffix() {
+        let mut names = vec![
+            "auto-20261017-101010-500.db",
+            "auto-20261017-101010-500-2.db",
+            "auto-20261017-101010-500-10.db",
+            "manual-20261017-101011-001.db",
+            "auto-20261017-101010.db",
+            "pre-restore-20261016-235959-999.db",
+        ];
+        names.sort_by_key(|name| std::cmp::Reverse(name_order(name)));
+        assert_eq!(
+            names,
+            [
+                "manual-20261017-101011-001.db",
+                "auto-20261017-101010-500-10.db",
+                "auto-20261017-101010-500-2.db",
+                "auto-20261017-101010-500.db",
+                "auto-20261017-101010.db",
+                "pre-restore-20261016-235959-999.db",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_names_not_written_by_create() {
+        for name in [
+            "auto-latest.db",
+            "auto-20261017.db",
+            "auto-20261017-101010-500-2-copy.db",
+            "manual-20261017-1010-500.db",
+            "other-20261017-101010.db",
+        ] {
+            assert_eq!(name_order(name), None, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Schema migrations, embedded from `migrations/*.sql` at build time.
+//!
+//! Uses the same `_migrations` bookkeeping as `runMigrations()` in
+//! `server/database.js`: a migration is identified by its filename and
+//! applied at most once, in filename order, each inside its own
+//! transaction. Running this before the sidecar starts leaves the server's
+//! runner with nothing to do, even in packaged builds where it can't find
+//! the migrations folder.
+
+use std::collections::BTreeMap;
+
+use chrono::{SecondsFormat, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::open;
+
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+/// A known or applied migration, as reported by `list_migrations`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MigrationInfo {
+    pub(crate) filename: String,
+    /// Numeric filename prefix (`5` for `005_alert_templates.sql`).
+    pub(crate) version: Option<u32>,
+    /// ISO timestamp, or `None` if still pending.
+    pub(crate) applied_at: Option<String>,
+    /// `false` for migrations recorded in the database that this build
+    /// doesn't ship (the database was migrated by a newer version).
+    pub(crate) embedded: bool,
+}
+
+fn version_of(filename: &str) -> Option<u32> {
+    let digits: String = filename.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+fn ensure_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _migrations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            filename TEXT UNIQUE NOT NULL,
+            applied_at TEXT NOT NULL
+        );",
+    )
+    .map_err(|e| format!("Failed to create the _migrations table: {}", e))
+}
+
+/// Applied migrations keyed by filename, with their `applied_at`.
+fn applied(conn: &Connection) -> Result<BTreeMap<String, String>, String> {
+    let read = || -> rusqlite::Result<BTreeMap<String, String>> {
+        let mut stmt = conn.prepare("SELECT filename, applied_at FROM _migrations")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    };
+    read().map_err(|e| format!("Failed to read applied migrations: {}", e))
+}
+
+// ---------------------------------------------------------------------------
+// Runner
+// ---------------------------------------------------------------------------
+
+/// Applies pending migrations to `conn`, returning the filenames applied.
+/// Stops at the first failure, leaving that migration unapplied.
+pub(crate) fn migrate(conn: &mut Connection) -> Result<Vec<String>, String> {
+    ensure_table(conn)?;
+    let done = applied(conn)?;
+
+    let mut newly_applied = Vec::new();
+    for (filename, sql) in MIGRATIONS {
+        if done.contains_key(*filename) {
+            continue;
+        }
+        println!("[Tauri] Applying migration: {}", filename);
+
+        // Foreign keys can't be toggled inside a transaction, and ALTER TABLE
+        // rebuilds would otherwise trip over them
+        conn.pragma_update(None, "foreign_keys", "OFF")
+            .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+        let result = apply(conn, filename, sql);
+        conn.pragma_update(None, "foreign_keys", "ON")
+            .map_err(|e| format!("Failed to re-enable foreign keys: {}", e))?;
+
+        result.map_err(|e| format!("Migration {} failed: {}", filename, e))?;
+        newly_applied.push(filename.to_string());
+    }
+    Ok(newly_applied)
+}
+
+fn apply(conn: &mut Connection, filename: &str, sql: &str) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(sql)?;
+    tx.execute(
+        "INSERT INTO _migrations (filename, applied_at) VALUES (?1, ?2)",
+        (
+            filename,
+            Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        ),
+    )?;
+    tx.commit()
+}
+
+/// Migrates the app's database. Called before every sidecar start, since a
+/// changed data dir may point at a fresh database.
+pub(crate) fn run(app: &AppHandle) -> Result<Vec<String>, String> {
+    let mut conn = open(app)?;
+    let newly_applied = migrate(&mut conn)?;
+    if newly_applied.is_empty() {
+        println!("[Tauri] All migrations already applied");
+    } else {
+        println!("[Tauri] Applied {} migration(s)", newly_applied.len());
+    }
+    Ok(newly_applied)
+}
+
+// ---------------------------------------------------------------------------
+// Inspection
+// ---------------------------------------------------------------------------
+
+/// Numeric prefix of the newest applied migration, 0 for a fresh database.
+/// Mirrors `getSchemaVersion()` in `server/database.js`.
+pub(crate) fn schema_version(app: &AppHandle) -> Result<u32, String> {
+    version(&open(app)?)
+}
+
+/// `schema_version` for an open connection.
+pub(crate) fn version(conn: &Connection) -> Result<u32, String> {
+    ensure_table(conn)?;
+    Ok(applied(conn)?
+        .keys()
+        .filter_map(|filename| version_of(filename))
+        .max()
+        .unwrap_or(0))
+}
+
+/// Schema version this build migrates databases to.
+pub(crate) fn latest_version() -> u32 {
+    MIGRATIONS
+        .iter()
+        .filter_map(|(filename, _)| version_of(filename))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Applied migrations in `conn` that this build doesn't ship, i.e. the
+/// database comes from a newer StreamForge. `conn` may be read-only, so the
+/// caller must check that `_migrations` exists.
+pub(crate) fn unknown_applied(conn: &Connection) -> Result<Vec<String>, String> {
+    Ok(applied(conn)?
+        .into_keys()
+        .filter(|filename| !MIGRATIONS.iter().any(|(known, _)| known == filename))
+        .collect())
+}
+
+/// Every embedded migration with its state, followed by any applied ones
+/// this build doesn't know about.
+pub(crate) fn list(app: &AppHandle) -> Result<Vec<MigrationInfo>, String> {
+    let conn = open(app)?;
+    ensure_table(&conn)?;
+    let mut done = applied(&conn)?;
+
+    let mut migrations: Vec<MigrationInfo> = MIGRATIONS
+        .iter()
+        .map(|(filename, _)| MigrationInfo {
+            filename: filename.to_string(),
+            version: version_of(filename),
+            applied_at: done.remove(*filename),
+            embedded: true,
+        })
+        .collect();
+    migrations.extend(
+        done.into_iter()
+            .map(|(filename, applied_at)| MigrationInfo {
+                version: version_of(&filename),
+                filename,
+                applied_at: Some(applied_at),
+                embedded: false,
+            }),
+    );
+    Ok(migrations)
+}
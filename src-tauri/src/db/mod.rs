@@ -0,0 +1,162 @@
+//! Direct access to the sidecar's SQLite database (`streamforge.db`).
+//!
+//! The shell only touches the database while the sidecar is stopped, or for
+//! short reads the WAL journal lets run alongside it.
+
+pub(crate) mod backup;
+pub(crate) mod migrations;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::Connection;
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::sidecar::launch::{self, ENV_DATA_DIR};
+
+const DB_FILE: &str = "streamforge.db";
+
+/// Folder under the platform config dir holding the database by default.
+const DEFAULT_DIR_NAME: &str = "streamforge";
+
+/// How long a write waits for the sidecar to release its lock.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The directory the sidecar keeps its data in, resolved the same way as
+/// `getAppDataDir()` in `server/database.js`.
+pub(crate) fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    if let Some(dir) = launch::current(app).to_env().get(ENV_DATA_DIR) {
+        return Ok(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os(ENV_DATA_DIR) {
+        return Ok(PathBuf::from(dir));
+    }
+    dirs::config_dir()
+        .map(|dir| dir.join(DEFAULT_DIR_NAME))
+        .ok_or_else(|| "Failed to resolve the data directory".to_string())
+}
+
+pub(crate) fn db_path(app: &AppHandle) -> Result<PathBuf, String> {
+    data_dir(app).map(|dir| dir.join(DB_FILE))
+}
+
+/// Opens (creating if needed) the database with the same pragmas the
+/// sidecar uses.
+pub(crate) fn open(app: &AppHandle) -> Result<Connection, String> {
+    let dir = data_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let path = dir.join(DB_FILE);
+    let conn =
+        Connection::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))
+        .and_then(|_| conn.pragma_update(None, "foreign_keys", "ON"))
+        .and_then(|_| conn.busy_timeout(BUSY_TIMEOUT))
+        .map_err(|e| format!("Failed to configure {}: {}", path.display(), e))?;
+    Ok(conn)
+}
+
+// ---------------------------------------------------------------------------
+// Rows as JSON
+// ---------------------------------------------------------------------------
+
+/// A table row keyed by column name, as the server's API returns them.
+pub(crate) type Row = Map<String, Value>;
+
+/// Runs `sql` and returns every row with all its columns.
+pub(crate) fn select_rows(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<Row>, String> {
+    let read = || -> rusqlite::Result<Vec<Row>> {
+        let mut stmt = conn.prepare(sql)?;
+        let names: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let rows = stmt.query_map(params, |row| {
+            let mut map = Row::new();
+            for (i, name) in names.iter().enumerate() {
+                map.insert(name.clone(), to_json(row.get_ref(i)?));
+            }
+            Ok(map)
+        })?;
+        rows.collect()
+    };
+    read().map_err(|e| format!("Failed to query the database: {}", e))
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(text) | ValueRef::Blob(text) => {
+            Value::String(String::from_utf8_lossy(text).into_owned())
+        }
+    }
+}
+
+/// Converts a JSON value for binding; nested values are stored as JSON text
+/// like the server does.
+pub(crate) fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(_) | Value::Object(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+/// Column names of `table`.
+pub(crate) fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let read = || -> rusqlite::Result<Vec<String>> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+        names.collect()
+    };
+    read().map_err(|e| format!("Failed to read the columns of {}: {}", table, e))
+}
+
+/// Inserts `row` into `table`, overwriting the row with the same `key`
+/// column if there is one. Only `columns` are written.
+pub(crate) fn upsert(
+    conn: &Connection,
+    table: &str,
+    key: &str,
+    columns: &[&str],
+    row: &Row,
+) -> Result<(), String> {
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|column| **column != key)
+        .map(|column| format!("{0} = excluded.{0}", column))
+        .collect();
+    let conflict = if updates.is_empty() {
+        "DO NOTHING".to_string()
+    } else {
+        format!("DO UPDATE SET {}", updates.join(", "))
+    };
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT({}) {}",
+        table,
+        columns.join(", "),
+        placeholders.join(", "),
+        key,
+        conflict
+    );
+
+    let values: Vec<SqlValue> = columns
+        .iter()
+        .map(|column| row.get(*column).map(to_sql).unwrap_or(SqlValue::Null))
+        .collect();
+    conn.execute(&sql, rusqlite::params_from_iter(values))
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write to {}: {}", table, e))
+}
@@ -0,0 +1,136 @@
+//! EventSub subscription management over the Helix API.
+//!
+//! WebSocket subscriptions are tied to a session: they are created with
+//! `POST /eventsub/subscriptions` naming the session ID once the welcome
+//! message arrives, carry over a `session_reconnect`, and are deleted by
+//! Twitch when the connection closes. There is nothing to clean up here.
+//!
+//! The base URL is configurable so the Twitch CLI's mock server
+//! (`twitch event websocket start-server`, which serves the same endpoint
+//! at `http://127.0.0.1:8080/eventsub/subscriptions`) can stand in for
+//! `https://api.twitch.tv/helix`.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::EventSubSettings;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A subscription type and the version the mapping in `messages` expects.
+pub(crate) const SUBSCRIPTION_TYPES: [(&str, &str); 4] = [
+    ("channel.follow", "2"),
+    ("channel.subscribe", "1"),
+    ("channel.cheer", "1"),
+    ("channel.raid", "1"),
+];
+
+/// Outcome of subscribing to one type, as shown in the status.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SubscriptionStatus {
+    pub(crate) subscription_type: String,
+    pub(crate) version: String,
+    /// Twitch's subscription ID, once created.
+    pub(crate) id: Option<String>,
+    /// `enabled`, `failed`, or the revocation reason.
+    pub(crate) status: String,
+    pub(crate) error: Option<String>,
+}
+
+fn condition(subscription_type: &str, broadcaster_id: &str) -> Value {
+    match subscription_type {
+        "channel.follow" => json!({
+            "broadcaster_user_id": broadcaster_id,
+            "moderator_user_id": broadcaster_id,
+        }),
+        "channel.raid" => json!({ "to_broadcaster_user_id": broadcaster_id }),
+        _ => json!({ "broadcaster_user_id": broadcaster_id }),
+    }
+}
+
+/// Creates one subscription for `session_id`.
+fn subscribe(
+    agent: &ureq::Agent,
+    settings: &EventSubSettings,
+    session_id: &str,
+    subscription_type: &str,
+    version: &str,
+) -> Result<String, String> {
+    let url = format!(
+        "{}/eventsub/subscriptions",
+        settings.api_url.trim_end_matches('/')
+    );
+    let body = json!({
+        "type": subscription_type,
+        "version": version,
+        "condition": condition(subscription_type, &settings.broadcaster_id),
+        "transport": { "method": "websocket", "session_id": session_id },
+    });
+
+    let mut response = agent
+        .post(&url)
+        .header("Client-Id", &settings.client_id)
+        .header(
+            "Authorization",
+            &format!("Bearer {}", settings.access_token),
+        )
+        .send_json(&body)
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+    let status = response.status().as_u16();
+    let reply: Value = response.body_mut().read_json().unwrap_or(Value::Null);
+
+    if !(200..300).contains(&status) {
+        let message = reply
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("no error message");
+        return Err(format!("Twitch answered {}: {}", status, message));
+    }
+    reply
+        .pointer("/data/0/id")
+        .and_then(Value::as_str)
+        .map(String::from)
+        .ok_or_else(|| "Twitch did not return a subscription ID".to_string())
+}
+
+/// Subscribes the session to every enabled type. Failures are reported per
+/// type so one missing scope doesn't keep the others from working.
+pub(crate) fn subscribe_all(
+    settings: &EventSubSettings,
+    session_id: &str,
+) -> Vec<SubscriptionStatus> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+
+    SUBSCRIPTION_TYPES
+        .iter()
+        .filter(|(subscription_type, _)| {
+            settings
+                .subscription_types
+                .iter()
+                .any(|t| t == subscription_type)
+        })
+        .map(|(subscription_type, version)| {
+            let result = subscribe(&agent, settings, session_id, subscription_type, version);
+            if let Err(e) = &result {
+                eprintln!(
+                    "[Tauri] Failed to subscribe to {}: {}",
+                    subscription_type, e
+                );
+            }
+            SubscriptionStatus {
+                subscription_type: subscription_type.to_string(),
+                version: version.to_string(),
+                status: if result.is_ok() { "enabled" } else { "failed" }.to_string(),
+                id: result.as_ref().ok().cloned(),
+                error: result.err(),
+            }
+        })
+        .collect()
+}
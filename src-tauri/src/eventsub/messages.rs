@@ -0,0 +1,203 @@
+//! EventSub WebSocket messages and their mapping onto pipeline events.
+//!
+//! Every message is a JSON object with `metadata` (ID, type, timestamp)
+//! and a `payload` whose shape depends on `metadata.message_type`:
+//!
+//! ```text
+//! session_welcome    payload.session { id, keepalive_timeout_seconds, ... }
+//! session_keepalive  {}
+//! notification       payload.subscription, payload.event
+//! session_reconnect  payload.session { reconnect_url, ... }
+//! revocation         payload.subscription (with the reason as status)
+//! ```
+//!
+//! See <https://dev.twitch.tv/docs/eventsub/websocket-reference/>.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::pipeline::IncomingEvent;
+
+#[derive(Deserialize)]
+struct Envelope {
+    metadata: Metadata,
+    #[serde(default)]
+    payload: Value,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    message_id: String,
+    message_type: String,
+    message_timestamp: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Session {
+    pub(crate) id: String,
+    /// Null on reconnect messages.
+    #[serde(default)]
+    pub(crate) keepalive_timeout_seconds: Option<u64>,
+    /// Only set on reconnect messages.
+    #[serde(default)]
+    pub(crate) reconnect_url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Subscription {
+    pub(crate) id: String,
+    /// `enabled`, or the revocation reason (`authorization_revoked`...).
+    pub(crate) status: String,
+    #[serde(rename = "type")]
+    pub(crate) subscription_type: String,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Notification {
+    /// Twitch may deliver a message more than once; dedupe on this.
+    pub(crate) message_id: String,
+    pub(crate) timestamp: String,
+    pub(crate) subscription: Subscription,
+    pub(crate) event: Map<String, Value>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Message {
+    Welcome(Session),
+    Keepalive,
+    Notification(Notification),
+    Reconnect(Session),
+    Revocation(Subscription),
+}
+
+fn field<T: serde::de::DeserializeOwned>(payload: &Value, name: &str) -> Result<T, String> {
+    let value = payload
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("missing payload.{}", name))?;
+    serde_json::from_value(value).map_err(|e| format!("invalid payload.{}: {}", name, e))
+}
+
+/// Parses a text frame from the EventSub server.
+pub(crate) fn parse(text: &str) -> Result<Message, String> {
+    let envelope: Envelope = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let payload = &envelope.payload;
+
+    match envelope.metadata.message_type.as_str() {
+        "session_welcome" => field(payload, "session").map(Message::Welcome),
+        "session_keepalive" => Ok(Message::Keepalive),
+        "notification" => Ok(Message::Notification(Notification {
+            message_id: envelope.metadata.message_id,
+            timestamp: envelope.metadata.message_timestamp,
+            subscription: field(payload, "subscription")?,
+            event: field(payload, "event")?,
+        })),
+        "session_reconnect" => field(payload, "session").map(Message::Reconnect),
+        "revocation" => field(payload, "subscription").map(Message::Revocation),
+        other => Err(format!("unknown message type {}", other)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Mapping
+// ---------------------------------------------------------------------------
+
+fn text(event: &Map<String, Value>, name: &str) -> Option<String> {
+    event
+        .get(name)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Twitch sends tiers as "1000"/"2000"/"3000"; alert variations match
+/// "1"/"2"/"3".
+fn tier(event: &Map<String, Value>) -> Option<String> {
+    text(event, "tier").map(|tier| match tier.as_str() {
+        "1000" => "1".to_string(),
+        "2000" => "2".to_string(),
+        "3000" => "3".to_string(),
+        _ => tier,
+    })
+}
+
+/// Turns a notification into a pipeline event, or `None` for subscription
+/// types the alerts don't cover.
+pub(crate) fn to_event(notification: &Notification) -> Option<IncomingEvent> {
+    let event = &notification.event;
+    let mut metadata = Map::new();
+    metadata.insert(
+        "eventsubMessageId".to_string(),
+        json!(notification.message_id),
+    );
+
+    let (event_type, login, name, amount, message, tier) =
+        match notification.subscription.subscription_type.as_str() {
+            "channel.follow" => (
+                "follow",
+                text(event, "user_login")?,
+                text(event, "user_name"),
+                None,
+                None,
+                None,
+            ),
+            "channel.subscribe" => {
+                metadata.insert("isGift".to_string(), json!(event.get("is_gift")));
+                (
+                    "subscribe",
+                    text(event, "user_login")?,
+                    text(event, "user_name"),
+                    None,
+                    None,
+                    tier(event),
+                )
+            }
+            "channel.cheer" => {
+                let anonymous = event
+                    .get("is_anonymous")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                let (login, name) = if anonymous {
+                    ("anonymous".to_string(), Some("Anonymous".to_string()))
+                } else {
+                    (text(event, "user_login")?, text(event, "user_name"))
+                };
+                (
+                    "cheer",
+                    login,
+                    name,
+                    event.get("bits").and_then(Value::as_f64),
+                    text(event, "message"),
+                    None,
+                )
+            }
+            "channel.raid" => (
+                "raid",
+                text(event, "from_broadcaster_user_login")?,
+                text(event, "from_broadcaster_user_name"),
+                event.get("viewers").and_then(Value::as_f64),
+                None,
+                None,
+            ),
+            _ => return None,
+        };
+
+    if let Some(user_id) =
+        text(event, "user_id").or_else(|| text(event, "from_broadcaster_user_id"))
+    {
+        metadata.insert("twitchUserId".to_string(), json!(user_id));
+    }
+
+    Some(IncomingEvent {
+        event_type: event_type.to_string(),
+        username: login,
+        display_name: name,
+        amount,
+        message,
+        tier,
+        platform: "twitch".to_string(),
+        gifter: None,
+        metadata,
+        timestamp: Some(notification.timestamp.clone()),
+    })
+}
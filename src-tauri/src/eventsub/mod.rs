@@ -0,0 +1,621 @@
+//! Twitch EventSub WebSocket client.
+//!
+//! Connects to EventSub, subscribes the session to follows, subs, cheers
+//! and raids, and feeds each notification into the event pipeline
+//! (`crate::pipeline::ingest`) as a `twitch` event.
+//!
+//! ```text
+//! connect -> session_welcome -> subscribe (helix) -> notifications...
+//!                 ^                                      |
+//!                 +-- session_reconnect: new URL, -------+
+//!                     subscriptions carry over
+//! ```
+//!
+//! Twitch sends `session_keepalive` whenever it has nothing else to send;
+//! if no message at all arrives within the welcome's keepalive timeout the
+//! connection is treated as dead. A `session_reconnect` hands over to a
+//! new URL: the client connects there, waits for its welcome, reads what
+//! is left on the old connection and closes it. Lost connections are
+//! retried with a fresh session, which needs new subscriptions. The delay
+//! doubles up to a minute and only starts over once a session has proved
+//! stable.
+//!
+//! `websocket_url` and `api_url` point at Twitch by default; set them to
+//! `ws://127.0.0.1:8080/ws` and `http://127.0.0.1:8080` to run against the
+//! Twitch CLI's mock server (`twitch event websocket start-server`).
+
+pub(crate) mod helix;
+pub(crate) mod messages;
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::WebSocket;
+
+use crate::pipeline;
+use helix::{SubscriptionStatus, SUBSCRIPTION_TYPES};
+use messages::Message;
+
+/// File in the app config dir holding the persisted `EventSubSettings`.
+const CONFIG_FILE: &str = "twitch_eventsub.json";
+
+/// Event emitted to the webview whenever the status changes.
+pub(crate) const EVENT_STATUS: &str = "eventsub://status";
+
+/// How often a blocked read wakes up to check for a stop or a dead
+/// connection.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for the welcome message after connecting.
+const WELCOME_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Extra time allowed past the keepalive timeout before giving up.
+const KEEPALIVE_GRACE: Duration = Duration::from_secs(5);
+
+/// Keepalive timeout assumed if the welcome doesn't carry one.
+const DEFAULT_KEEPALIVE: Duration = Duration::from_secs(10);
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A session that lasted this long, or delivered a notification, resets
+/// the backoff when it drops. Shorter ones keep doubling it, so a session
+/// Twitch closes right away (a bad token leaves it without subscriptions)
+/// isn't reopened and resubscribed every second.
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// Message IDs remembered to drop duplicate deliveries.
+const SEEN_MESSAGES: usize = 200;
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+// ---------------------------------------------------------------------------
+// Settings
+// ---------------------------------------------------------------------------
+
+/// Connection settings. The access token needs the scopes of the
+/// subscribed types (`moderator:read:followers`,
+/// `channel:read:subscriptions`, `bits:read`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct EventSubSettings {
+    pub(crate) enabled: bool,
+    pub(crate) websocket_url: String,
+    /// Helix base URL, without `/eventsub/subscriptions`.
+    pub(crate) api_url: String,
+    pub(crate) client_id: String,
+    /// User access token of the broadcaster.
+    pub(crate) access_token: String,
+    pub(crate) broadcaster_id: String,
+    /// Subscription types to create, e.g. `channel.follow`.
+    pub(crate) subscription_types: Vec<String>,
+}
+
+impl Default for EventSubSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            websocket_url: "wss://eventsub.wss.twitch.tv/ws".to_string(),
+            api_url: "https://api.twitch.tv/helix".to_string(),
+            client_id: String::new(),
+            access_token: String::new(),
+            broadcaster_id: String::new(),
+            subscription_types: SUBSCRIPTION_TYPES
+                .iter()
+                .map(|(subscription_type, _)| subscription_type.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl EventSubSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !self.websocket_url.starts_with("ws://") && !self.websocket_url.starts_with("wss://") {
+            return Err("WebSocket URL must start with ws:// or wss://".to_string());
+        }
+        if !self.api_url.starts_with("http://") && !self.api_url.starts_with("https://") {
+            return Err("API URL must start with http:// or https://".to_string());
+        }
+        if let Some(unknown) = self
+            .subscription_types
+            .iter()
+            .find(|t| !SUBSCRIPTION_TYPES.iter().any(|(known, _)| known == t))
+        {
+            return Err(format!("Unsupported subscription type {}", unknown));
+        }
+        if self.enabled
+            && [&self.client_id, &self.access_token, &self.broadcaster_id]
+                .iter()
+                .any(|value| value.trim().is_empty())
+        {
+            return Err("Client ID, access token and broadcaster ID are required".to_string());
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Status
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ConnectionState {
+    #[default]
+    Disabled,
+    Connecting,
+    Connected,
+    /// The connection was lost; waiting to retry.
+    Disconnected,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EventSubStatus {
+    pub(crate) state: ConnectionState,
+    pub(crate) session_id: Option<String>,
+    pub(crate) keepalive_secs: Option<u64>,
+    pub(crate) subscriptions: Vec<SubscriptionStatus>,
+    /// Notifications received since the client started.
+    pub(crate) notifications: u64,
+    pub(crate) last_error: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+pub(crate) struct EventSub(pub(crate) Mutex<EventSubState>);
+
+pub(crate) struct EventSubState {
+    pub(crate) settings: EventSubSettings,
+    pub(crate) status: EventSubStatus,
+    /// Bumped whenever the settings change; a client thread started for an
+    /// older generation stops.
+    generation: u64,
+}
+
+impl EventSubState {
+    pub(crate) fn new(settings: EventSubSettings) -> Self {
+        Self {
+            settings,
+            status: EventSubStatus::default(),
+            generation: 0,
+        }
+    }
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Loads the persisted settings, falling back to defaults if there are none
+/// or they can't be used.
+pub(crate) fn load(app: &AppHandle) -> EventSubSettings {
+    let Ok(path) = config_path(app) else {
+        return EventSubSettings::default();
+    };
+    let Ok(text) = fs::read_to_string(&path) else {
+        return EventSubSettings::default();
+    };
+
+    match serde_json::from_str::<EventSubSettings>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(|settings| settings.validate().map(|_| settings))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!(
+                "[Tauri] Ignoring invalid EventSub settings {}: {}",
+                path.display(),
+                e
+            );
+            EventSubSettings::default()
+        }
+    }
+}
+
+/// Writes the settings to the app config dir.
+fn save(app: &AppHandle, settings: &EventSubSettings) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize EventSub settings: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Validates, persists and applies new settings, restarting the client.
+pub(crate) fn update(
+    app: &AppHandle,
+    settings: EventSubSettings,
+) -> Result<EventSubSettings, String> {
+    settings.validate()?;
+    save(app, &settings)?;
+    let generation = {
+        let state = app.state::<EventSub>();
+        let mut state = state
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to update EventSub settings: {}", e))?;
+        state.settings = settings.clone();
+        state.generation += 1;
+        state.generation
+    };
+
+    if settings.enabled {
+        start(app, generation);
+    } else {
+        update_status(app, generation, |status| {
+            *status = EventSubStatus::default()
+        });
+    }
+    Ok(settings)
+}
+
+/// Returns a snapshot of the current status.
+pub(crate) fn current_status(app: &AppHandle) -> EventSubStatus {
+    app.state::<EventSub>()
+        .0
+        .lock()
+        .map(|state| state.status.clone())
+        .unwrap_or_default()
+}
+
+/// Applies `change` to the status and notifies the webview if it changed.
+/// Only the client thread of the current `generation` may touch it.
+fn update_status(app: &AppHandle, generation: u64, change: impl FnOnce(&mut EventSubStatus)) {
+    let status = {
+        let state = app.state::<EventSub>();
+        let Ok(mut state) = state.0.lock() else {
+            return;
+        };
+        if state.generation != generation {
+            return;
+        }
+        let mut status = state.status.clone();
+        change(&mut status);
+        if status == state.status {
+            return;
+        }
+        state.status = status.clone();
+        status
+    };
+
+    if let Err(e) = app.emit(EVENT_STATUS, &status) {
+        eprintln!("[Tauri] Failed to emit {}: {}", EVENT_STATUS, e);
+    }
+}
+
+/// Whether the client thread of `generation` should keep running.
+fn is_current(app: &AppHandle, generation: u64) -> bool {
+    app.state::<EventSub>()
+        .0
+        .lock()
+        .is_ok_and(|state| state.generation == generation && state.settings.enabled)
+}
+
+// ---------------------------------------------------------------------------
+// Client
+// ---------------------------------------------------------------------------
+
+/// Starts the client if it is enabled in the persisted settings.
+pub(crate) fn spawn_eventsub(app: AppHandle) {
+    let enabled = app
+        .state::<EventSub>()
+        .0
+        .lock()
+        .is_ok_and(|state| state.settings.enabled);
+    if enabled {
+        start(&app, 0);
+    }
+}
+
+fn start(app: &AppHandle, generation: u64) {
+    let app = app.clone();
+    std::thread::spawn(move || run(&app, generation));
+}
+
+/// An open connection whose welcome has arrived.
+struct Session {
+    socket: Socket,
+    id: String,
+    keepalive: Duration,
+    last_message: Instant,
+    opened_at: Instant,
+    /// Whether a notification arrived on this connection.
+    notified: bool,
+}
+
+impl Session {
+    /// Whether the session worked well enough to reset the backoff.
+    fn was_stable(&self) -> bool {
+        self.notified || self.opened_at.elapsed() >= STABLE_AFTER
+    }
+}
+
+/// Why `pump` returned.
+enum Outcome {
+    Stopped,
+    Reconnect(String),
+    Lost(String),
+}
+
+/// Recently delivered message IDs.
+#[derive(Default)]
+struct SeenMessages(VecDeque<String>);
+
+impl SeenMessages {
+    /// Records `id`, returning `false` if it was already delivered.
+    fn insert(&mut self, id: &str) -> bool {
+        if self.0.iter().any(|seen| seen == id) {
+            return false;
+        }
+        if self.0.len() >= SEEN_MESSAGES {
+            self.0.pop_front();
+        }
+        self.0.push_back(id.to_string());
+        true
+    }
+}
+
+fn is_timeout(error: &tungstenite::Error) -> bool {
+    matches!(error, tungstenite::Error::Io(e)
+        if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut))
+}
+
+/// Connects to `url` and waits for the session welcome.
+fn open(url: &str) -> Result<Session, String> {
+    let (mut socket, _) =
+        tungstenite::connect(url).map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+    let stream = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream,
+        MaybeTlsStream::Rustls(stream) => stream.get_ref(),
+        _ => return Err(format!("Unsupported stream for {}", url)),
+    };
+    stream
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .map_err(|e| format!("Failed to configure the connection: {}", e))?;
+
+    let deadline = Instant::now() + WELCOME_TIMEOUT;
+    while Instant::now() < deadline {
+        let text = match socket.read() {
+            Ok(tungstenite::Message::Text(text)) => text,
+            Ok(tungstenite::Message::Close(frame)) => {
+                return Err(format!("Connection closed before the welcome: {:?}", frame));
+            }
+            Ok(_) => continue,
+            Err(e) if is_timeout(&e) => continue,
+            Err(e) => return Err(format!("Failed to read the welcome: {}", e)),
+        };
+        if let Ok(Message::Welcome(session)) = messages::parse(text.as_str()) {
+            return Ok(Session {
+                socket,
+                id: session.id,
+                keepalive: session
+                    .keepalive_timeout_seconds
+                    .map(Duration::from_secs)
+                    .unwrap_or(DEFAULT_KEEPALIVE),
+                last_message: Instant::now(),
+                opened_at: Instant::now(),
+                notified: false,
+            });
+        }
+    }
+    Err(format!(
+        "No welcome from {} within {:?}",
+        url, WELCOME_TIMEOUT
+    ))
+}
+
+/// Hands a notification to the pipeline, once per message ID.
+fn deliver(
+    app: &AppHandle,
+    generation: u64,
+    seen: &mut SeenMessages,
+    notification: &messages::Notification,
+) {
+    if !seen.insert(&notification.message_id) {
+        return;
+    }
+    update_status(app, generation, |status| status.notifications += 1);
+    let Some(event) = messages::to_event(notification) else {
+        return;
+    };
+    if let Err(e) = pipeline::ingest(app, event) {
+        eprintln!(
+            "[Tauri] Failed to ingest {} notification: {}",
+            notification.subscription.subscription_type, e
+        );
+    }
+}
+
+/// Reads messages until the client is stopped, Twitch asks for a
+/// reconnect, or the connection is lost.
+fn pump(
+    app: &AppHandle,
+    generation: u64,
+    session: &mut Session,
+    seen: &mut SeenMessages,
+) -> Outcome {
+    loop {
+        if !is_current(app, generation) {
+            return Outcome::Stopped;
+        }
+        let text = match session.socket.read() {
+            Ok(tungstenite::Message::Text(text)) => text,
+            Ok(tungstenite::Message::Close(frame)) => {
+                return Outcome::Lost(format!("Connection closed by Twitch: {:?}", frame));
+            }
+            Ok(_) => {
+                session.last_message = Instant::now();
+                continue;
+            }
+            Err(e) if is_timeout(&e) => {
+                if session.last_message.elapsed() > session.keepalive + KEEPALIVE_GRACE {
+                    return Outcome::Lost("No keepalive from Twitch".to_string());
+                }
+                continue;
+            }
+            Err(e) => return Outcome::Lost(format!("Connection lost: {}", e)),
+        };
+        session.last_message = Instant::now();
+
+        match messages::parse(text.as_str()) {
+            Ok(Message::Notification(notification)) => {
+                session.notified = true;
+                deliver(app, generation, seen, &notification)
+            }
+            Ok(Message::Reconnect(target)) => match target.reconnect_url {
+                Some(url) => return Outcome::Reconnect(url),
+                None => eprintln!("[Tauri] EventSub reconnect message without a URL"),
+            },
+            Ok(Message::Revocation(subscription)) => {
+                eprintln!(
+                    "[Tauri] EventSub revoked {}: {}",
+                    subscription.subscription_type, subscription.status
+                );
+                update_status(app, generation, |status| {
+                    for entry in &mut status.subscriptions {
+                        if entry.id.as_deref() == Some(subscription.id.as_str()) {
+                            entry.status = subscription.status.clone();
+                        }
+                    }
+                });
+            }
+            Ok(Message::Welcome(_) | Message::Keepalive) => {}
+            Err(e) => eprintln!("[Tauri] Ignoring unreadable EventSub message: {}", e),
+        }
+    }
+}
+
+/// Delivers what is still buffered on a connection that is being replaced,
+/// then closes it.
+fn retire(app: &AppHandle, generation: u64, mut session: Session, seen: &mut SeenMessages) {
+    while let Ok(message) = session.socket.read() {
+        match message {
+            tungstenite::Message::Text(text) => {
+                if let Ok(Message::Notification(notification)) = messages::parse(text.as_str()) {
+                    deliver(app, generation, seen, &notification);
+                }
+            }
+            tungstenite::Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    let _ = session.socket.close(None);
+    let _ = session.socket.flush();
+}
+
+/// Waits out a backoff delay, returning `false` if the client was stopped
+/// meanwhile.
+fn wait(app: &AppHandle, generation: u64, delay: Duration) -> bool {
+    let until = Instant::now() + delay;
+    while Instant::now() < until {
+        if !is_current(app, generation) {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL.min(until - Instant::now()));
+    }
+    is_current(app, generation)
+}
+
+/// Client thread for one generation of settings.
+fn run(app: &AppHandle, generation: u64) {
+    let mut seen = SeenMessages::default();
+    let mut backoff = INITIAL_BACKOFF;
+    let mut handover: Option<Session> = None;
+
+    while is_current(app, generation) {
+        let settings = match app.state::<EventSub>().0.lock() {
+            Ok(state) => state.settings.clone(),
+            Err(_) => return,
+        };
+
+        let mut session = match handover.take() {
+            Some(session) => session,
+            None => {
+                update_status(app, generation, |status| {
+                    status.state = ConnectionState::Connecting;
+                    status.session_id = None;
+                    status.subscriptions.clear();
+                });
+                match open(&settings.websocket_url) {
+                    Ok(session) => {
+                        println!("[Tauri] EventSub session {} opened", session.id);
+                        let subscriptions = helix::subscribe_all(&settings, &session.id);
+                        update_status(app, generation, |status| {
+                            status.subscriptions = subscriptions
+                        });
+                        session
+                    }
+                    Err(e) => {
+                        eprintln!("[Tauri] {}", e);
+                        update_status(app, generation, |status| {
+                            status.state = ConnectionState::Disconnected;
+                            status.last_error = Some(e);
+                        });
+                        if !wait(app, generation, backoff) {
+                            break;
+                        }
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                        continue;
+                    }
+                }
+            }
+        };
+
+        update_status(app, generation, |status| {
+            status.state = ConnectionState::Connected;
+            status.session_id = Some(session.id.clone());
+            status.keepalive_secs = Some(session.keepalive.as_secs());
+        });
+
+        match pump(app, generation, &mut session, &mut seen) {
+            Outcome::Stopped => {
+                let _ = session.socket.close(None);
+                let _ = session.socket.flush();
+            }
+            Outcome::Reconnect(url) => {
+                println!("[Tauri] EventSub session {} moving to {}", session.id, url);
+                match open(&url) {
+                    Ok(next) => {
+                        retire(app, generation, session, &mut seen);
+                        handover = Some(next);
+                    }
+                    Err(e) => {
+                        eprintln!("[Tauri] EventSub reconnect failed, starting over: {}", e);
+                        let _ = session.socket.close(None);
+                    }
+                }
+            }
+            Outcome::Lost(e) => {
+                eprintln!("[Tauri] EventSub: {}", e);
+                update_status(app, generation, |status| {
+                    status.state = ConnectionState::Disconnected;
+                    status.last_error = Some(e);
+                });
+                if session.was_stable() {
+                    backoff = INITIAL_BACKOFF;
+                }
+                if !wait(app, generation, backoff) {
+                    break;
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
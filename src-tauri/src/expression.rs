@@ -0,0 +1,671 @@
+//! Boolean expressions shared by alert variation conditions
+//! (`condition.rs`) and `{#if}` tags in message templates
+//! (`message_template.rs`).
+//!
+//! Both read `or`/`and`/`not`, parentheses and the comparison operators
+//! the same way, but differ in details that saved conditions and templates
+//! rely on, so each picks a `Dialect`:
+//!
+//! | | `Condition` | `Template` |
+//! |---|---|---|
+//! | strings | `"..."` or `'...'`, `\` escapes | `"..."`, no escapes |
+//! | operands | stop at anything but `[A-Za-z0-9_]` | read up to a space, bracket or operator, so `-5`, `1.5` are numbers and `user.name` is an invalid name |
+//! | literals | `true`, `false` | none |
+//! | `between`, `contains`, `in`, `not in` | yes | no |
+//! | text comparison, `"false"` | case-insensitive | case-sensitive |
+//!
+//! Comparisons are numeric when both sides are numbers (numeric strings
+//! count) and the ordering operators are false otherwise. A bare operand is
+//! true unless it is missing, empty, `0` or `false`.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Byte range within the parsed text.
+pub(crate) type Span = (usize, usize);
+
+/// Which flavour of the language to parse; see the module docs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    Condition,
+    Template,
+}
+
+impl Dialect {
+    /// Words that can't name a variable.
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Condition => &[
+                "and", "or", "not", "between", "contains", "in", "true", "false",
+            ],
+            Self::Template => &["and", "or", "not"],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ParseError {
+    pub(crate) message: String,
+    pub(crate) span: Span,
+}
+
+pub(crate) fn error<T>(message: impl Into<String>, span: Span) -> Result<T, ParseError> {
+    Err(ParseError {
+        message: message.into(),
+        span,
+    })
+}
+
+/// A problem in a condition or template, located for highlighting in the
+/// editor.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SourceIssue {
+    pub(crate) message: String,
+    /// UTF-16 offsets, so they index JavaScript strings directly.
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl SourceIssue {
+    pub(crate) fn new(source: &str, message: String, (start, end): Span) -> Self {
+        let utf16 = |byte: usize| source[..byte].encode_utf16().count();
+        Self {
+            message,
+            start: utf16(start),
+            end: utf16(end),
+        }
+    }
+}
+
+pub(crate) fn is_variable(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// ---------------------------------------------------------------------------
+// Syntax Tree
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Truthy(Operand),
+    Compare(Operand, CompareOp, Operand),
+    Between(Operand, Operand, Operand),
+    Contains(Operand, Operand),
+    In(Operand, Vec<Operand>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug)]
+enum Operand {
+    Variable(String, Span),
+    Literal(Value),
+}
+
+/// A parsed expression.
+#[derive(Debug)]
+pub(crate) struct Expression {
+    expr: Expr,
+    dialect: Dialect,
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Text(String),
+    Op(&'static str),
+    Open,
+    Close,
+    OpenList,
+    CloseList,
+    Comma,
+}
+
+const OPERATORS: [&str; 6] = ["==", "!=", ">=", "<=", ">", "<"];
+
+/// Splits `text` into tokens. Spans are offset by `base`, where `text`
+/// starts in the source.
+fn tokenize(text: &str, base: usize, dialect: Dialect) -> Result<Vec<(Token, Span)>, ParseError> {
+    let condition = dialect == Dialect::Condition;
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        let start = base + i;
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let punctuation = match c {
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            '[' if condition => Some(Token::OpenList),
+            ']' if condition => Some(Token::CloseList),
+            ',' if condition => Some(Token::Comma),
+            _ => None,
+        };
+        if let Some(token) = punctuation {
+            chars.next();
+            tokens.push((token, (start, start + 1)));
+            continue;
+        }
+
+        if let Some(op) = OPERATORS.into_iter().find(|op| text[i..].starts_with(op)) {
+            chars.nth(op.len() - 1);
+            tokens.push((Token::Op(op), (start, start + op.len())));
+            continue;
+        }
+
+        if c == '"' || (condition && c == '\'') {
+            chars.next();
+            let mut value = String::new();
+            let mut end = None;
+            while let Some((j, next)) = chars.next() {
+                match next {
+                    '\\' if condition => match chars.next() {
+                        Some((_, escaped)) => value.push(escaped),
+                        None => break,
+                    },
+                    quote if quote == c => {
+                        end = Some(base + j + 1);
+                        break;
+                    }
+                    other => value.push(other),
+                }
+            }
+            let Some(end) = end else {
+                return error("Unclosed string", (start, base + text.len()));
+            };
+            tokens.push((Token::Text(value), (start, end)));
+            continue;
+        }
+
+        let (token, end) = match dialect {
+            Dialect::Condition => condition_word(text, i, &mut chars),
+            Dialect::Template => template_word(text, i, &mut chars),
+        };
+        let span = (start, base + end);
+        match token {
+            Some(token) => tokens.push((token, span)),
+            None if end == i => {
+                return error(
+                    format!("Unexpected \"{}\"", c),
+                    (start, start + c.len_utf8()),
+                )
+            }
+            None => return error(invalid_word(dialect, &text[i..end]), span),
+        }
+    }
+    Ok(tokens)
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+/// Consumes characters while `continues` holds, returning where they end.
+fn take_while(chars: &mut Chars, mut end: usize, continues: impl Fn(char) -> bool) -> usize {
+    while let Some(&(j, next)) = chars.peek() {
+        if !continues(next) {
+            break;
+        }
+        end = j + next.len_utf8();
+        chars.next();
+    }
+    end
+}
+
+/// A number (`12`, `-1.5`) or a name. `None` with the end of the invalid
+/// word, or with `start` if nothing here starts one.
+fn condition_word(text: &str, start: usize, chars: &mut Chars) -> (Option<Token>, usize) {
+    let c = text[start..].chars().next().unwrap_or_default();
+    let number = c.is_ascii_digit()
+        || (c == '-' && text[start + 1..].starts_with(|n: char| n.is_ascii_digit()));
+    if !(number || c.is_ascii_alphabetic() || c == '_') {
+        return (None, start);
+    }
+    chars.next();
+    let end = if number {
+        take_while(chars, start + 1, |n| n.is_ascii_digit() || n == '.')
+    } else {
+        take_while(chars, start + 1, |n| n.is_ascii_alphanumeric() || n == '_')
+    };
+    let word = &text[start..end];
+    let token = match number {
+        true => word.parse::<f64>().ok().map(Token::Number),
+        false => Some(Token::Word(word.to_string())),
+    };
+    (token, end)
+}
+
+/// Everything up to the next space, bracket or operator, which must be a
+/// number or a name.
+fn template_word(text: &str, start: usize, chars: &mut Chars) -> (Option<Token>, usize) {
+    let end = take_while(chars, start, |c| {
+        c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-'
+    });
+    let word = &text[start..end];
+    let token = match word.parse::<f64>() {
+        Ok(number) if number.is_finite() => Some(Token::Number(number)),
+        _ if is_variable(word) => Some(Token::Word(word.to_string())),
+        _ => None,
+    };
+    (token, end)
+}
+
+fn invalid_word(dialect: Dialect, word: &str) -> String {
+    match dialect {
+        Dialect::Condition => format!("Invalid number \"{}\"", word),
+        Dialect::Template => format!("Invalid name \"{}\"", word),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser {
+    tokens: Vec<(Token, Span)>,
+    pos: usize,
+    /// Empty span at the end of the text, for "expected ..." errors.
+    end: Span,
+    dialect: Dialect,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn span(&self) -> Span {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |(_, span)| *span)
+    }
+
+    fn is_keyword(&self, offset: usize, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos + offset), Some((Token::Word(word), _)) if word == keyword)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(0, keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), ParseError> {
+        if self.peek() != Some(&token) {
+            return error(format!("Expected {}", what), self.span());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.not()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, ParseError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.pos += 1;
+            let expr = self.or()?;
+            self.expect(Token::Close, "\")\"")?;
+            return Ok(expr);
+        }
+        self.test()
+    }
+
+    fn test(&mut self) -> Result<Expr, ParseError> {
+        let left = self.operand()?;
+
+        if let Some(Token::Op(op)) = self.peek() {
+            let op = match *op {
+                "==" => CompareOp::Eq,
+                "!=" => CompareOp::Ne,
+                ">" => CompareOp::Gt,
+                ">=" => CompareOp::Ge,
+                "<" => CompareOp::Lt,
+                _ => CompareOp::Le,
+            };
+            self.pos += 1;
+            return Ok(Expr::Compare(left, op, self.operand()?));
+        }
+        if self.dialect == Dialect::Template {
+            return Ok(Expr::Truthy(left));
+        }
+        if self.keyword("between") {
+            let low = self.operand()?;
+            if !self.keyword("and") {
+                return error("Expected \"and\" in \"between ... and ...\"", self.span());
+            }
+            return Ok(Expr::Between(left, low, self.operand()?));
+        }
+        if self.keyword("contains") {
+            return Ok(Expr::Contains(left, self.operand()?));
+        }
+        if self.is_keyword(0, "not") && self.is_keyword(1, "in") {
+            self.pos += 2;
+            return Ok(Expr::Not(Box::new(Expr::In(left, self.list()?))));
+        }
+        if self.keyword("in") {
+            return Ok(Expr::In(left, self.list()?));
+        }
+        Ok(Expr::Truthy(left))
+    }
+
+    fn list(&mut self) -> Result<Vec<Operand>, ParseError> {
+        self.expect(Token::OpenList, "\"[\" to start a list")?;
+        let mut items = Vec::new();
+        if self.peek() == Some(&Token::CloseList) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(self.operand()?);
+            match self.peek() {
+                Some(Token::Comma) => self.pos += 1,
+                Some(Token::CloseList) => {
+                    self.pos += 1;
+                    return Ok(items);
+                }
+                _ => return error("Expected \",\" or \"]\"", self.span()),
+            }
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, ParseError> {
+        let span = self.span();
+        let condition = self.dialect == Dialect::Condition;
+        let operand = match self.peek() {
+            Some(Token::Word(word)) if condition && (word == "true" || word == "false") => {
+                Operand::Literal(Value::Bool(word == "true"))
+            }
+            Some(Token::Word(word)) if self.dialect.keywords().contains(&word.as_str()) => {
+                return error(format!("Expected a value, found \"{}\"", word), span)
+            }
+            Some(Token::Word(word)) => Operand::Variable(word.clone(), span),
+            Some(Token::Number(n)) => Operand::Literal(
+                serde_json::Number::from_f64(*n).map_or(Value::Null, Value::Number),
+            ),
+            Some(Token::Text(text)) => Operand::Literal(Value::String(text.clone())),
+            Some(_) => return error("Expected a variable, number or string", span),
+            None => return error("Incomplete condition", span),
+        };
+        self.pos += 1;
+        Ok(operand)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Values
+// ---------------------------------------------------------------------------
+
+/// A value as a number: JSON numbers and numeric strings.
+pub(crate) fn as_number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok().filter(|n| n.is_finite()),
+        _ => None,
+    }
+}
+
+/// A value as display text; missing and null are empty.
+pub(crate) fn as_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(other) => other.to_string(),
+    }
+}
+
+impl Dialect {
+    fn truthy(self, value: Option<&Value>) -> bool {
+        match value {
+            None | Some(Value::Null) => false,
+            Some(Value::Bool(b)) => *b,
+            Some(Value::Number(n)) => n.as_f64() != Some(0.0),
+            Some(Value::String(s)) => !(s.is_empty() || s == "0" || self.text_eq(s, "false")),
+            Some(Value::Array(a)) => !a.is_empty(),
+            Some(Value::Object(o)) => !o.is_empty(),
+        }
+    }
+
+    fn text_eq(self, a: &str, b: &str) -> bool {
+        match self {
+            Self::Condition => a.to_lowercase() == b.to_lowercase(),
+            Self::Template => a == b,
+        }
+    }
+
+    fn equals(self, left: Option<&Value>, right: Option<&Value>) -> bool {
+        match (as_number(left), as_number(right)) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.text_eq(&as_text(left), &as_text(right)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+impl Operand {
+    fn value<'v>(&'v self, variables: &'v Map<String, Value>) -> Option<&'v Value> {
+        match self {
+            Self::Variable(name, _) => variables.get(name),
+            Self::Literal(value) => Some(value),
+        }
+    }
+}
+
+impl Expr {
+    fn evaluate(&self, variables: &Map<String, Value>, dialect: Dialect) -> bool {
+        let evaluate = |expr: &Expr| expr.evaluate(variables, dialect);
+        match self {
+            Self::Or(a, b) => evaluate(a) || evaluate(b),
+            Self::And(a, b) => evaluate(a) && evaluate(b),
+            Self::Not(a) => !evaluate(a),
+            Self::Truthy(operand) => dialect.truthy(operand.value(variables)),
+            Self::Compare(left, op, right) => {
+                let (left, right) = (left.value(variables), right.value(variables));
+                let ordering = || {
+                    as_number(left)
+                        .zip(as_number(right))
+                        .and_then(|(a, b)| a.partial_cmp(&b))
+                };
+                use std::cmp::Ordering::*;
+                match op {
+                    CompareOp::Eq => dialect.equals(left, right),
+                    CompareOp::Ne => !dialect.equals(left, right),
+                    CompareOp::Gt => ordering() == Some(Greater),
+                    CompareOp::Ge => matches!(ordering(), Some(Greater | Equal)),
+                    CompareOp::Lt => ordering() == Some(Less),
+                    CompareOp::Le => matches!(ordering(), Some(Less | Equal)),
+                }
+            }
+            Self::Between(value, low, high) => {
+                match (
+                    as_number(value.value(variables)),
+                    as_number(low.value(variables)),
+                    as_number(high.value(variables)),
+                ) {
+                    (Some(value), Some(low), Some(high)) => low <= value && value <= high,
+                    _ => false,
+                }
+            }
+            Self::Contains(haystack, needle) => {
+                let needle = needle.value(variables);
+                match haystack.value(variables) {
+                    Some(Value::Array(items)) => {
+                        items.iter().any(|item| dialect.equals(Some(item), needle))
+                    }
+                    haystack => as_text(haystack)
+                        .to_lowercase()
+                        .contains(&as_text(needle).to_lowercase()),
+                }
+            }
+            Self::In(value, items) => {
+                let value = value.value(variables);
+                items
+                    .iter()
+                    .any(|item| dialect.equals(value, item.value(variables)))
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+impl Expression {
+    /// Parses `text`, which starts at byte `base` of the source that spans
+    /// refer to. `text` must not be blank; callers report that themselves.
+    pub(crate) fn parse(text: &str, base: usize, dialect: Dialect) -> Result<Self, ParseError> {
+        let end = base + text.len();
+        let mut parser = Parser {
+            tokens: tokenize(text, base, dialect)?,
+            pos: 0,
+            end: (end, end),
+            dialect,
+        };
+        let expr = parser.or()?;
+        if parser.pos < parser.tokens.len() {
+            let message = match dialect {
+                Dialect::Condition => "Unexpected text after the condition",
+                Dialect::Template => "Unexpected text after condition",
+            };
+            return error(message, parser.span());
+        }
+        Ok(Self { expr, dialect })
+    }
+
+    pub(crate) fn evaluate(&self, variables: &Map<String, Value>) -> bool {
+        self.expr.evaluate(variables, self.dialect)
+    }
+
+    /// Every variable reference, in order.
+    pub(crate) fn variables(&self) -> Vec<(&str, Span)> {
+        fn operand<'e>(operand: &'e Operand, refs: &mut Vec<(&'e str, Span)>) {
+            if let Operand::Variable(name, span) = operand {
+                refs.push((name, *span));
+            }
+        }
+        fn walk<'e>(expr: &'e Expr, refs: &mut Vec<(&'e str, Span)>) {
+            match expr {
+                Expr::Or(a, b) | Expr::And(a, b) => {
+                    walk(a, refs);
+                    walk(b, refs);
+                }
+                Expr::Not(a) => walk(a, refs),
+                Expr::Truthy(a) => operand(a, refs),
+                Expr::Compare(a, _, b) | Expr::Contains(a, b) => {
+                    operand(a, refs);
+                    operand(b, refs);
+                }
+                Expr::Between(a, b, c) => {
+                    for o in [a, b, c] {
+                        operand(o, refs);
+                    }
+                }
+                Expr::In(a, items) => {
+                    operand(a, refs);
+                    for item in items {
+                        operand(item, refs);
+                    }
+                }
+            }
+        }
+        let mut refs = Vec::new();
+        walk(&self.expr, &mut refs);
+        refs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn evaluate(text: &str, dialect: Dialect) -> Result<bool, String> {
+        let variables = json!({ "type": "cheer" });
+        Expression::parse(text, 0, dialect)
+            .map(|expression| expression.evaluate(variables.as_object().unwrap()))
+            .map_err(|e| e.message)
+    }
+
+    #[test]
+    fn dialects_differ_where_saved_text_relies_on_it() {
+        type Outcome = Result<bool, &'static str>;
+        let cases: [(&str, Outcome, Outcome); 7] = [
+            ("type == \"Cheer\"", Ok(true), Ok(false)),
+            ("\"FALSE\"", Ok(false), Ok(true)),
+            ("true", Ok(true), Ok(false)),
+            ("type == 'cheer'", Ok(true), Err("Unexpected \"'\"")),
+            ("-5 < 1.5", Ok(true), Ok(true)),
+            (
+                "user.name",
+                Err("Unexpected \".\""),
+                Err("Invalid name \"user.name\""),
+            ),
+            (
+                "type contains \"he\"",
+                Ok(true),
+                Err("Unexpected text after condition"),
+            ),
+        ];
+        for (text, condition, template) in cases {
+            let expected = |r: Outcome| r.map_err(String::from);
+            assert_eq!(
+                evaluate(text, Dialect::Condition),
+                expected(condition),
+                "{}",
+                text
+            );
+            assert_eq!(
+                evaluate(text, Dialect::Template),
+                expected(template),
+                "{}",
+                text
+            );
+        }
+    }
+}
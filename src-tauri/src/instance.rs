@@ -0,0 +1,345 @@
+//! Single-instance enforcement.
+//!
+//! The first StreamForge process takes an exclusive lock on `instance.lock`
+//! in the app's local data dir and listens on a loopback socket whose port
+//! (plus a random token) it publishes in `instance.json`. A later launch
+//! fails to take the lock, forwards its arguments over that socket so the
+//! running instance can focus its window, and exits before any sidecar is
+//! spawned.
+//!
+//! The file lock is released by the OS when the process dies, so a crashed
+//! instance never blocks the next launch.
+
+use std::collections::hash_map::RandomState;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::tray;
+
+/// Emitted in the running instance with `SecondLaunch` whenever another
+/// launch was redirected to it.
+pub(crate) const EVENT_SECOND_LAUNCH: &str = "instance://second-launch";
+
+const LOCK_FILE: &str = "instance.lock";
+const INFO_FILE: &str = "instance.json";
+
+/// How long a second launch waits for the primary to publish `instance.json`
+/// (it may have taken the lock a moment ago).
+const INFO_WAIT: Duration = Duration::from_secs(3);
+
+/// Connect/read timeout for talking to the primary instance.
+const IPC_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Requests are single JSON lines; anything longer is not ours.
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Published by the primary instance for later launches to find it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstanceInfo {
+    pid: u32,
+    ipc_port: u16,
+    token: String,
+}
+
+/// Message sent by a later launch to the primary instance.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IpcRequest {
+    token: String,
+    #[serde(flatten)]
+    command: IpcCommand,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+enum IpcCommand {
+    /// Someone launched the app again: focus the window.
+    Activate(SecondLaunch),
+}
+
+/// Payload of `EVENT_SECOND_LAUNCH`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SecondLaunch {
+    /// Arguments of the redirected launch, without the program name.
+    pub(crate) args: Vec<String>,
+    pub(crate) cwd: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IpcResponse {
+    ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Held by the primary instance for its whole lifetime.
+pub(crate) struct InstanceGuard {
+    /// Dropping the file releases the lock.
+    _lock: File,
+    listener: Option<TcpListener>,
+    token: String,
+    info_path: PathBuf,
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.info_path);
+    }
+}
+
+/// Result of `acquire`.
+pub(crate) enum Instance {
+    /// This process is the only instance and must keep the guard alive.
+    Primary(InstanceGuard),
+    /// Another instance is running (and has been told about this launch if
+    /// it could be reached). This process should exit.
+    Secondary,
+}
+
+// ---------------------------------------------------------------------------
+// Acquisition
+// ---------------------------------------------------------------------------
+
+/// Directory holding the instance files -- the same place Tauri resolves
+/// `app_local_data_dir` to for `identifier`.
+pub(crate) fn runtime_dir(identifier: &str) -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(identifier))
+}
+
+/// Becomes the primary instance, or hands this launch over to the running
+/// one. Errors mean single-instance enforcement isn't possible here; the
+/// caller should carry on as if it were the only instance.
+pub(crate) fn acquire(identifier: &str) -> Result<Instance, String> {
+    let dir = runtime_dir(identifier).ok_or("Failed to resolve the app data dir")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let lock_path = dir.join(LOCK_FILE);
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open {}: {}", lock_path.display(), e))?;
+
+    match lock.try_lock() {
+        Ok(()) => become_primary(lock, &dir).map(Instance::Primary),
+        Err(TryLockError::WouldBlock) => {
+            // Even if the hand-over fails, a second server must not start
+            if let Err(e) = forward_launch(&dir) {
+                eprintln!("[Tauri] {}", e);
+            }
+            Ok(Instance::Secondary)
+        }
+        Err(TryLockError::Error(e)) => {
+            Err(format!("Failed to lock {}: {}", lock_path.display(), e))
+        }
+    }
+}
+
+fn become_primary(lock: File, dir: &Path) -> Result<InstanceGuard, String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| format!("Failed to open instance socket: {}", e))?;
+    let ipc_port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read instance socket address: {}", e))?
+        .port();
+
+    let info = InstanceInfo {
+        pid: std::process::id(),
+        ipc_port,
+        token: random_token(),
+    };
+    let info_path = dir.join(INFO_FILE);
+    let text = serde_json::to_string(&info)
+        .map_err(|e| format!("Failed to serialize instance info: {}", e))?;
+    fs::write(&info_path, text)
+        .map_err(|e| format!("Failed to write {}: {}", info_path.display(), e))?;
+
+    Ok(InstanceGuard {
+        _lock: lock,
+        listener: Some(listener),
+        token: info.token,
+        info_path,
+    })
+}
+
+/// Not cryptographic, but unpredictable enough to keep other local
+/// processes from poking the socket by accident.
+fn random_token() -> String {
+    (0..2)
+        .map(|_| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u32(std::process::id());
+            hasher.write_u128(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or_default(),
+            );
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Second Launch
+// ---------------------------------------------------------------------------
+
+/// Reads the primary's `instance.json`, waiting briefly if it hasn't been
+/// written yet.
+fn read_info(dir: &Path) -> Result<InstanceInfo, String> {
+    let path = dir.join(INFO_FILE);
+    let deadline = Instant::now() + INFO_WAIT;
+    loop {
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(info) => return Ok(info),
+            Err(_) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => {
+                return Err(format!(
+                    "StreamForge is already running, but {} is unreadable: {}",
+                    path.display(),
+                    e
+                ))
+            }
+        }
+    }
+}
+
+fn send(info: &InstanceInfo, command: IpcCommand) -> Result<IpcResponse, String> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, info.ipc_port));
+    let mut stream = TcpStream::connect_timeout(&addr, IPC_TIMEOUT)
+        .map_err(|e| format!("Failed to reach the running instance: {}", e))?;
+    stream
+        .set_read_timeout(Some(IPC_TIMEOUT))
+        .map_err(|e| format!("Failed to configure instance socket: {}", e))?;
+
+    let request = IpcRequest {
+        token: info.token.clone(),
+        command,
+    };
+    let mut line = serde_json::to_string(&request)
+        .map_err(|e| format!("Failed to serialize instance request: {}", e))?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to send instance request: {}", e))?;
+
+    let mut response = String::new();
+    BufReader::new(stream.take(MAX_REQUEST_BYTES))
+        .read_line(&mut response)
+        .map_err(|e| format!("No response from the running instance: {}", e))?;
+    serde_json::from_str(&response).map_err(|e| format!("Invalid instance response: {}", e))
+}
+
+fn forward_launch(dir: &Path) -> Result<(), String> {
+    let info = read_info(dir)?;
+    let launch = SecondLaunch {
+        args: std::env::args().skip(1).collect(),
+        cwd: std::env::current_dir()
+            .ok()
+            .map(|dir| dir.to_string_lossy().into_owned()),
+    };
+
+    let response = send(&info, IpcCommand::Activate(launch))?;
+    if !response.ok {
+        return Err(response
+            .error
+            .unwrap_or_else(|| "The running instance refused the request".to_string()));
+    }
+    println!(
+        "[Tauri] StreamForge is already running (pid {}), focused its window",
+        info.pid
+    );
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Primary Instance
+// ---------------------------------------------------------------------------
+
+/// Starts answering later launches. Call once from `setup`.
+pub(crate) fn serve(app: &AppHandle, guard: &mut InstanceGuard) {
+    let Some(listener) = guard.listener.take() else {
+        return;
+    };
+    let token = guard.token.clone();
+    let app = app.clone();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = handle_connection(&app, &token, stream) {
+                        eprintln!("[Tauri] Instance request failed: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("[Tauri] Instance socket error: {}", e),
+            }
+        }
+    });
+}
+
+fn handle_connection(app: &AppHandle, token: &str, stream: TcpStream) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(IPC_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    BufReader::new(stream.take(MAX_REQUEST_BYTES))
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+
+    let response = match serde_json::from_str::<IpcRequest>(&line) {
+        Ok(request) if request.token == token => handle_command(app, request.command),
+        Ok(_) => IpcResponse {
+            ok: false,
+            error: Some("Invalid instance token".to_string()),
+        },
+        Err(e) => IpcResponse {
+            ok: false,
+            error: Some(format!("Invalid instance request: {}", e)),
+        },
+    };
+
+    let mut text = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+    text.push('\n');
+    writer.write_all(text.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn handle_command(app: &AppHandle, command: IpcCommand) -> IpcResponse {
+    match command {
+        IpcCommand::Activate(launch) => {
+            println!(
+                "[Tauri] Second launch redirected to this instance (args: {:?})",
+                launch.args
+            );
+            // A redundant `--headless` launch shouldn't pop up the dashboard
+            if !launch.args.iter().any(|arg| arg == "--headless") {
+                tray::show_main_window(app);
+            }
+            let _ = app.emit(EVENT_SECOND_LAUNCH, launch);
+            IpcResponse {
+                ok: true,
+                error: None,
+            }
+        }
+    }
+}
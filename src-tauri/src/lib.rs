@@ -1,21 +1,50 @@
+mod alert_queue;
+mod channels;
+mod cli;
+mod condition;
+mod db;
+mod eventsub;
+mod expression;
+mod instance;
+mod media;
+mod message_template;
+mod notifications;
+mod pipeline;
+mod port_file;
+mod profile;
+mod resolution;
+mod sidecar;
+mod signals;
+mod template_pack;
+mod tray;
+
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
 
+use alert_queue::schedule::{AlertScheduler, SchedulePreview, SchedulerState, SchedulingSettings};
+use alert_queue::AlertBacklog;
 use tauri::Manager;
-use tauri_plugin_shell::process::CommandChild;
-use tauri_plugin_shell::ShellExt;
-
-// ---------------------------------------------------------------------------
-// Managed State
-// ---------------------------------------------------------------------------
-
-/// Holds the port number reported by the sidecar server.
-/// `None` means the port hasn't been received yet.
-/// Wrapped in Arc so we can share it with the background reader thread.
-struct ServerPort(Arc<Mutex<Option<u16>>>);
 
-/// Holds the sidecar child process handle so we can kill it on exit.
-struct SidecarProcess(Mutex<Option<CommandChild>>);
+use channels::AlertChannel;
+use condition::ConditionCheck;
+use db::backup::{BackupInfo, BackupSettings, Backups, IntegrityReport, RestoreReport};
+use db::migrations::MigrationInfo;
+use eventsub::{EventSub, EventSubSettings, EventSubState, EventSubStatus};
+use instance::Instance;
+use message_template::TemplateCheck;
+use notifications::{NotificationSettings, Notifications};
+use pipeline::coalesce::CoalescingSettings;
+use pipeline::{EventPipeline, IncomingEvent, PipelineState};
+use profile::{ConflictStrategy, ExportReport, ImportReport, ProfilePreview};
+use resolution::ResolutionTrace;
+use sidecar::launch::{LaunchConfig, SidecarLaunchConfig};
+use sidecar::logs::{LogBuffer, LogEntry, LogLevel, SidecarLogs};
+use sidecar::status::{SidecarState, SidecarStatus};
+use sidecar::watchdog::{SidecarWatchdog, WatchdogState};
+use sidecar::{
+    ServerPort, SidecarDetails, SidecarInfo, SidecarInfoSnapshot, SidecarProcess,
+    SidecarSupervisor, SupervisorState,
+};
+use template_pack::{PackImportReport, PackInfo, PackReport};
 
 // ---------------------------------------------------------------------------
 // Tauri Commands
@@ -33,87 +62,594 @@ fn get_server_port(state: tauri::State<'_, ServerPort>) -> Result<u16, String> {
     port.ok_or_else(|| "Server port not available yet".to_string())
 }
 
-// ---------------------------------------------------------------------------
-// Sidecar Management
-// ---------------------------------------------------------------------------
+/// Returns the sidecar lifecycle state so the frontend can tell "starting"
+/// apart from "crashed" or "binary missing".
+/// Live changes are pushed as `sidecar://status` events.
+#[tauri::command]
+fn get_sidecar_status(state: tauri::State<'_, SidecarState>) -> Result<SidecarStatus, String> {
+    let status = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read sidecar status: {}", e))?;
 
-/// Spawns the sidecar binary and wires up stdout parsing + state storage.
-fn start_sidecar(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let shell = app.shell();
+    Ok(status.clone())
+}
 
-    let command = shell.sidecar("binaries/streamforge-server").map_err(|e| {
-        format!(
-            "Failed to create sidecar command: {}. \
-             Make sure the binary exists in src-tauri/binaries/",
-            e
-        )
-    })?;
+/// Returns what the sidecar reported in its handshake (versions, PID, data
+/// dir) plus heartbeat freshness and the last fatal error, if any.
+#[tauri::command]
+fn get_sidecar_info(state: tauri::State<'_, SidecarInfo>) -> Result<SidecarInfoSnapshot, String> {
+    let info = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read sidecar info: {}", e))?;
 
-    let (mut rx, child) = command.spawn().map_err(|e| {
-        format!(
-            "Failed to spawn sidecar process: {}. \
-             The binary may be missing or not executable.",
-            e
-        )
-    })?;
+    Ok(info.snapshot())
+}
+
+/// Returns captured sidecar log lines, oldest first.
+///
+/// * `since` -- only entries with a sequence number greater than this
+/// * `level` -- minimum level (`info`, `warn`, `error`)
+/// * `limit` -- keep only the most recent N matching entries
+///
+/// New lines are also pushed live as `sidecar://log` events.
+#[tauri::command]
+fn get_sidecar_logs(
+    state: tauri::State<'_, SidecarLogs>,
+    since: Option<u64>,
+    level: Option<LogLevel>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let logs = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read sidecar logs: {}", e))?;
+
+    Ok(logs.query(since, level, limit))
+}
+
+/// Returns the health watchdog's view of the sidecar: consecutive failed
+/// checks, the last failure reason and whether it restarts automatically.
+#[tauri::command]
+fn get_watchdog_status(state: tauri::State<'_, SidecarWatchdog>) -> Result<WatchdogState, String> {
+    let watchdog = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read watchdog status: {}", e))?;
+
+    Ok(watchdog.clone())
+}
+
+/// Enables or disables restarting the sidecar when it stops answering
+/// health checks. When disabled it is only marked unhealthy.
+#[tauri::command]
+fn set_watchdog_auto_restart(
+    state: tauri::State<'_, SidecarWatchdog>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut watchdog = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to update watchdog: {}", e))?;
+
+    watchdog.auto_restart = enabled;
+    Ok(())
+}
+
+/// Returns the launch config (ports, bind address, data dir, extra env)
+/// used when spawning the sidecar.
+#[tauri::command]
+fn get_launch_config(state: tauri::State<'_, SidecarLaunchConfig>) -> Result<LaunchConfig, String> {
+    let config = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read launch config: {}", e))?;
+
+    Ok(config.clone())
+}
+
+/// Validates and persists a new launch config, then restarts the sidecar in
+/// the background so it takes effect. The frontend is told about the new
+/// port through the usual `sidecar://restarted` event.
+#[tauri::command]
+fn set_launch_config(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, SidecarLaunchConfig>,
+    config: LaunchConfig,
+) -> Result<LaunchConfig, String> {
+    config.validate()?;
+    sidecar::launch::save(&app, &config)?;
 
-    // Store the child handle so we can kill it on app exit
-    let sidecar_state = app.state::<SidecarProcess>();
     {
-        let mut handle = sidecar_state.0.lock().unwrap();
-        *handle = Some(child);
+        let mut current = state
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to update launch config: {}", e))?;
+        if *current == config {
+            return Ok(config);
+        }
+        *current = config.clone();
     }
 
-    // Clone the inner Mutex (via Arc-like managed state) for the background thread
-    let port_state = app.state::<ServerPort>().inner().0.clone();
-
-    // Spawn a background thread to read sidecar stdout/stderr lines
+    // Stopping the old sidecar can take seconds -- keep it off the IPC thread
     std::thread::spawn(move || {
-        use tauri_plugin_shell::process::CommandEvent;
-
-        // Block on the receiver -- it yields until the sidecar exits
-        while let Some(event) = rx.blocking_recv() {
-            match event {
-                CommandEvent::Stdout(line) => {
-                    let text = String::from_utf8_lossy(&line);
-                    let trimmed = text.trim();
-
-                    // Parse the port announcement line
-                    if let Some(port_str) = trimmed.strip_prefix("SERVER_PORT=") {
-                        if let Ok(port) = port_str.parse::<u16>() {
-                            if let Ok(mut p) = port_state.lock() {
-                                *p = Some(port);
-                            }
-                            println!("[Tauri] Sidecar server port: {}", port);
-                        } else {
-                            eprintln!("[Tauri] Failed to parse port from sidecar: {:?}", port_str);
-                        }
-                    } else {
-                        // Forward other sidecar stdout for debugging
-                        println!("[Sidecar] {}", trimmed);
-                    }
-                }
-                CommandEvent::Stderr(line) => {
-                    let text = String::from_utf8_lossy(&line);
-                    eprintln!("[Sidecar:err] {}", text.trim());
-                }
-                CommandEvent::Terminated(payload) => {
-                    println!(
-                        "[Tauri] Sidecar process terminated (code: {:?}, signal: {:?})",
-                        payload.code, payload.signal
-                    );
-                    break;
-                }
-                CommandEvent::Error(err) => {
-                    eprintln!("[Tauri] Sidecar command error: {}", err);
-                    break;
-                }
-                _ => {}
-            }
+        if let Err(e) = sidecar::restart_sidecar(&app) {
+            eprintln!("[Tauri] Sidecar restart after config change failed: {}", e);
         }
     });
 
-    Ok(())
+    Ok(config)
+}
+
+/// Returns the native notification settings (do-not-disturb and per event
+/// type rules).
+#[tauri::command]
+fn get_notification_settings(
+    state: tauri::State<'_, Notifications>,
+) -> Result<NotificationSettings, String> {
+    let settings = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read notification settings: {}", e))?;
+
+    Ok(settings.clone())
+}
+
+/// Replaces and persists the notification settings.
+#[tauri::command]
+fn set_notification_settings(
+    app: tauri::AppHandle,
+    settings: NotificationSettings,
+) -> Result<NotificationSettings, String> {
+    notifications::update(&app, |current| *current = settings)
+}
+
+/// Mutes or unmutes notifications for one event type (e.g. `follow`).
+#[tauri::command]
+fn set_notification_muted(
+    app: tauri::AppHandle,
+    event_type: String,
+    muted: bool,
+) -> Result<NotificationSettings, String> {
+    notifications::update(&app, |settings| {
+        settings.rules.entry(event_type).or_default().muted = muted;
+    })
+}
+
+/// Turns do-not-disturb on or off.
+#[tauri::command]
+fn set_do_not_disturb(
+    app: tauri::AppHandle,
+    enabled: bool,
+) -> Result<NotificationSettings, String> {
+    notifications::update(&app, |settings| settings.do_not_disturb = enabled)
+}
+
+/// Returns the numeric prefix of the newest applied migration (0 for a
+/// fresh database).
+#[tauri::command]
+fn get_schema_version(app: tauri::AppHandle) -> Result<u32, String> {
+    db::migrations::schema_version(&app)
+}
+
+/// Returns every embedded migration with when it was applied, plus any
+/// applied migrations this build doesn't ship.
+#[tauri::command]
+fn list_migrations(app: tauri::AppHandle) -> Result<Vec<MigrationInfo>, String> {
+    db::migrations::list(&app)
+}
+
+/// Returns the automatic backup settings.
+#[tauri::command]
+fn get_backup_settings(state: tauri::State<'_, Backups>) -> Result<BackupSettings, String> {
+    let settings = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read backup settings: {}", e))?;
+
+    Ok(settings.clone())
+}
+
+/// Validates and persists new automatic backup settings.
+#[tauri::command]
+fn set_backup_settings(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Backups>,
+    settings: BackupSettings,
+) -> Result<BackupSettings, String> {
+    settings.validate()?;
+    db::backup::save(&app, &settings)?;
+
+    let mut current = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to update backup settings: {}", e))?;
+    *current = settings.clone();
+    Ok(settings)
+}
+
+/// Takes a manual backup of the live database.
+#[tauri::command]
+async fn backup_database(app: tauri::AppHandle) -> Result<BackupInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        db::backup::create(&app, db::backup::BackupKind::Manual)
+    })
+    .await
+    .map_err(|e| format!("Failed to back up the database: {}", e))?
+}
+
+/// Returns the backups in the data dir, newest first.
+#[tauri::command]
+fn list_backups(app: tauri::AppHandle) -> Result<Vec<BackupInfo>, String> {
+    db::backup::list(&app)
+}
+
+/// Runs `PRAGMA integrity_check` on the live database.
+#[tauri::command]
+async fn check_database_integrity(app: tauri::AppHandle) -> Result<IntegrityReport, String> {
+    tauri::async_runtime::spawn_blocking(move || db::backup::check(&app))
+        .await
+        .map_err(|e| format!("Failed to check the database: {}", e))?
+}
+
+/// Replaces the live database with `path`, or with a file the user picks
+/// when no path is given. Returns `None` if the picker was cancelled.
+/// The sidecar is stopped during the restore and started again afterwards.
+#[tauri::command]
+async fn restore_database(
+    app: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<Option<RestoreReport>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => match db::backup::pick_source(&app)? {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        db::backup::restore(&app, &source).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Failed to restore the database: {}", e))?
+}
+
+/// Bundles alerts, variations, user templates, settings and their media into
+/// a profile archive at `path`, or where the user picks when no path is
+/// given. Returns `None` if the save dialog was cancelled.
+#[tauri::command]
+async fn export_profile(
+    app: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<Option<ExportReport>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dest = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => match profile::pick_destination(&app)? {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        profile::export(&app, &dest).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Failed to export the profile: {}", e))?
+}
+
+/// Validates a profile archive (picked by the user when no path is given)
+/// and lists what importing it would collide with, without changing
+/// anything. Returns `None` if the picker was cancelled.
+#[tauri::command]
+async fn inspect_profile(
+    app: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<Option<ProfilePreview>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => match profile::pick_source(&app)? {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        profile::inspect(&app, &source).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Failed to read the profile: {}", e))?
+}
+
+/// Imports a profile archive, resolving collisions with `strategy`.
+#[tauri::command]
+async fn import_profile(
+    app: tauri::AppHandle,
+    path: String,
+    strategy: Option<ConflictStrategy>,
+) -> Result<ImportReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        profile::import(
+            &app,
+            std::path::Path::new(&path),
+            strategy.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to import the profile: {}", e))?
+}
+
+/// Opens a template pack (archive or folder, picked by the user when no
+/// path is given) and reports every problem found in it. Returns `None` if
+/// the picker was cancelled.
+#[tauri::command]
+async fn validate_template_pack(
+    app: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<Option<PackReport>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => match template_pack::pick_pack(&app)? {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        template_pack::inspect(&source).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Failed to read the template pack: {}", e))?
+}
+
+/// Zips the pack folder `dir` into the archive `dest`.
+#[tauri::command]
+async fn pack_template_pack(dir: String, dest: String) -> Result<PackReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        template_pack::pack(std::path::Path::new(&dir), std::path::Path::new(&dest))
+    })
+    .await
+    .map_err(|e| format!("Failed to pack the template pack: {}", e))?
+}
+
+/// Extracts the pack archive `path` into the folder `dest_dir`.
+#[tauri::command]
+async fn unpack_template_pack(path: String, dest_dir: String) -> Result<PackReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        template_pack::unpack(std::path::Path::new(&path), std::path::Path::new(&dest_dir))
+    })
+    .await
+    .map_err(|e| format!("Failed to unpack the template pack: {}", e))?
+}
+
+/// Packs the given stored templates and their media into `path`, or where
+/// the user picks when no path is given. Returns `None` if the save dialog
+/// was cancelled; a report with issues means nothing was written.
+#[tauri::command]
+async fn export_template_pack(
+    app: tauri::AppHandle,
+    template_ids: Vec<String>,
+    info: PackInfo,
+    path: Option<String>,
+) -> Result<Option<PackReport>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dest = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => match template_pack::pick_destination(&app, &info.name)? {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        template_pack::export(&app, &template_ids, info, &dest).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Failed to export the template pack: {}", e))?
+}
+
+/// Adds the templates from a valid pack as user templates.
+#[tauri::command]
+async fn import_template_pack(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<PackImportReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        template_pack::import(&app, std::path::Path::new(&path))
+    })
+    .await
+    .map_err(|e| format!("Failed to import the template pack: {}", e))?
+}
+
+/// Parses an alert message template for the editor, returning syntax
+/// errors and unknown variables with their positions.
+#[tauri::command]
+fn check_message_template(template: String) -> TemplateCheck {
+    message_template::check(&template)
+}
+
+/// Renders an alert message template with sample values for previews.
+#[tauri::command]
+fn render_message_template(
+    template: String,
+    variables: serde_json::Map<String, serde_json::Value>,
+) -> Result<String, String> {
+    message_template::render(&template, &variables)
+}
+
+/// Parses a variation condition for the editor and, given sample event
+/// data, reports whether it would match.
+#[tauri::command]
+fn check_variation_condition(
+    condition: String,
+    event: Option<serde_json::Map<String, serde_json::Value>>,
+) -> ConditionCheck {
+    condition::check(&condition, event.as_ref())
+}
+
+/// Works out which alert (and variation) the server would pick for an
+/// event, returning every decision along the way. Reads the database only;
+/// nothing is queued.
+#[tauri::command]
+async fn simulate_alert(
+    app: tauri::AppHandle,
+    event_type: String,
+    event_data: Option<serde_json::Map<String, serde_json::Value>>,
+) -> Result<ResolutionTrace, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        resolution::run(&app, &event_type, event_data.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Failed to simulate the alert: {}", e))?
+}
+
+/// Lists alerts the shell will replay to the next sidecar, and optionally
+/// the recently finished ones.
+#[tauri::command]
+async fn list_alert_backlog(
+    app: tauri::AppHandle,
+    include_finished: Option<bool>,
+) -> Result<AlertBacklog, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        alert_queue::list(&app, include_finished.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Failed to read the alert backlog: {}", e))?
+}
+
+/// Drops the pending alerts (and optionally the history) from the durable
+/// queue. Returns the number of entries removed.
+#[tauri::command]
+async fn purge_alert_backlog(
+    app: tauri::AppHandle,
+    include_finished: Option<bool>,
+) -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        alert_queue::clear(&app, include_finished.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Failed to purge the alert backlog: {}", e))?
+}
+
+/// Returns the alert scheduling settings (priority classes, promotion and
+/// maximum queue age).
+#[tauri::command]
+fn get_alert_scheduling(
+    state: tauri::State<'_, AlertScheduler>,
+) -> Result<SchedulingSettings, String> {
+    let state = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read alert scheduling settings: {}", e))?;
+
+    Ok(state.settings.clone())
+}
+
+/// Replaces and persists the alert scheduling settings, and reorders the
+/// running server's queue to match.
+#[tauri::command]
+async fn set_alert_scheduling(
+    app: tauri::AppHandle,
+    settings: SchedulingSettings,
+) -> Result<SchedulingSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || alert_queue::schedule::update(&app, settings))
+        .await
+        .map_err(|e| format!("Failed to update alert scheduling settings: {}", e))?
+}
+
+/// Lists the waiting alerts with their priority classes, and the order the
+/// scheduler would put them in right now.
+#[tauri::command]
+async fn preview_alert_schedule(app: tauri::AppHandle) -> Result<SchedulePreview, String> {
+    tauri::async_runtime::spawn_blocking(move || alert_queue::schedule::preview(&app))
+        .await
+        .map_err(|e| format!("Failed to preview the alert schedule: {}", e))?
+}
+
+/// Returns the alert coalescing settings (window, batch size and the
+/// aggregate templates per event type).
+#[tauri::command]
+fn get_alert_coalescing(
+    state: tauri::State<'_, EventPipeline>,
+) -> Result<CoalescingSettings, String> {
+    let state = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read alert coalescing settings: {}", e))?;
+
+    Ok(state.settings.clone())
+}
+
+/// Replaces and persists the alert coalescing settings.
+#[tauri::command]
+async fn set_alert_coalescing(
+    app: tauri::AppHandle,
+    settings: CoalescingSettings,
+) -> Result<CoalescingSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || pipeline::update(&app, settings))
+        .await
+        .map_err(|e| format!("Failed to update alert coalescing settings: {}", e))?
+}
+
+/// Feeds a stream event into the shell's event pipeline, where it may be
+/// coalesced with others of its type before it reaches the alert queue.
+#[tauri::command]
+async fn ingest_event(app: tauri::AppHandle, event: IncomingEvent) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || pipeline::ingest(&app, event))
+        .await
+        .map_err(|e| format!("Failed to ingest the event: {}", e))?
+}
+
+/// Lists the alert channels and the alert types routed to each.
+#[tauri::command]
+async fn list_alert_channels(app: tauri::AppHandle) -> Result<Vec<AlertChannel>, String> {
+    tauri::async_runtime::spawn_blocking(move || channels::list(&app))
+        .await
+        .map_err(|e| format!("Failed to list alert channels: {}", e))?
+}
+
+/// Creates or updates an alert channel and has the server reload its
+/// channels.
+#[tauri::command]
+async fn save_alert_channel(
+    app: tauri::AppHandle,
+    channel: AlertChannel,
+) -> Result<AlertChannel, String> {
+    tauri::async_runtime::spawn_blocking(move || channels::save(&app, channel))
+        .await
+        .map_err(|e| format!("Failed to save the alert channel: {}", e))?
+}
+
+/// Deletes an alert channel. Returns false if there was no such channel.
+#[tauri::command]
+async fn delete_alert_channel(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || channels::delete(&app, &id))
+        .await
+        .map_err(|e| format!("Failed to delete the alert channel: {}", e))?
+}
+
+/// Returns the Twitch EventSub connection settings.
+#[tauri::command]
+fn get_eventsub_settings(state: tauri::State<'_, EventSub>) -> Result<EventSubSettings, String> {
+    let state = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to read EventSub settings: {}", e))?;
+
+    Ok(state.settings.clone())
+}
+
+/// Replaces and persists the Twitch EventSub settings, and reconnects (or
+/// disconnects) the client.
+#[tauri::command]
+async fn set_eventsub_settings(
+    app: tauri::AppHandle,
+    settings: EventSubSettings,
+) -> Result<EventSubSettings, String> {
+    tauri::async_runtime::spawn_blocking(move || eventsub::update(&app, settings))
+        .await
+        .map_err(|e| format!("Failed to update EventSub settings: {}", e))?
+}
+
+/// Returns the EventSub connection state and its subscriptions.
+#[tauri::command]
+fn get_eventsub_status(app: tauri::AppHandle) -> EventSubStatus {
+    eventsub::current_status(&app)
 }
 
 // ---------------------------------------------------------------------------
@@ -122,39 +658,146 @@ fn start_sidecar(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let context = tauri::generate_context!();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Scripting subcommands talk to the running instance and exit
+    if let Some(code) = cli::run(&context.config().identifier, &args) {
+        std::process::exit(code);
+    }
+
+    // `--headless` runs the server and tray without opening the dashboard
+    let headless = args.iter().any(|arg| arg == "--headless");
+
+    // Hand this launch over to an already running instance instead of
+    // starting a second sidecar on another port
+    let instance = match instance::acquire(&context.config().identifier) {
+        Ok(Instance::Primary(guard)) => Some(guard),
+        Ok(Instance::Secondary) => return,
+        Err(e) => {
+            eprintln!("[Tauri] Single-instance check failed, continuing: {}", e);
+            None
+        }
+    };
+
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(ServerPort(Arc::new(Mutex::new(None))))
         .manage(SidecarProcess(Mutex::new(None)))
-        .invoke_handler(tauri::generate_handler![get_server_port])
-        .setup(|app| {
-            if let Err(e) = start_sidecar(app) {
+        .manage(SidecarSupervisor(Mutex::new(SupervisorState::default())))
+        .manage(SidecarState(Mutex::new(SidecarStatus::default())))
+        .manage(SidecarInfo(Mutex::new(SidecarDetails::default())))
+        .manage(SidecarWatchdog(Mutex::new(WatchdogState::default())))
+        .invoke_handler(tauri::generate_handler![
+            get_server_port,
+            get_sidecar_status,
+            get_sidecar_info,
+            get_sidecar_logs,
+            get_watchdog_status,
+            set_watchdog_auto_restart,
+            get_launch_config,
+            set_launch_config,
+            get_notification_settings,
+            set_notification_settings,
+            set_notification_muted,
+            set_do_not_disturb,
+            get_schema_version,
+            list_migrations,
+            get_backup_settings,
+            set_backup_settings,
+            backup_database,
+            list_backups,
+            check_database_integrity,
+            restore_database,
+            export_profile,
+            inspect_profile,
+            import_profile,
+            validate_template_pack,
+            pack_template_pack,
+            unpack_template_pack,
+            export_template_pack,
+            import_template_pack,
+            check_message_template,
+            render_message_template,
+            check_variation_condition,
+            simulate_alert,
+            list_alert_backlog,
+            purge_alert_backlog,
+            get_alert_scheduling,
+            set_alert_scheduling,
+            preview_alert_schedule,
+            get_alert_coalescing,
+            set_alert_coalescing,
+            ingest_event,
+            list_alert_channels,
+            save_alert_channel,
+            delete_alert_channel,
+            get_eventsub_settings,
+            set_eventsub_settings,
+            get_eventsub_status
+        ])
+        .on_window_event(tray::on_window_event)
+        .setup(move |app| {
+            if let Some(mut guard) = instance {
+                instance::serve(app.handle(), &mut guard);
+                app.manage(guard);
+            }
+
+            // Persist sidecar output next to the other app logs so it can be
+            // inspected in release builds where there is no console.
+            let log_dir = app.path().app_log_dir().ok();
+            app.manage(SidecarLogs(Mutex::new(LogBuffer::new(log_dir))));
+            app.manage(SidecarLaunchConfig(Mutex::new(sidecar::launch::load(
+                app.handle(),
+            ))));
+            app.manage(Notifications(Mutex::new(notifications::load(app.handle()))));
+            app.manage(Backups(Mutex::new(db::backup::load(app.handle()))));
+            app.manage(EventPipeline(Mutex::new(PipelineState::new(
+                pipeline::load(app.handle()),
+            ))));
+            app.manage(AlertScheduler(Mutex::new(SchedulerState::new(
+                alert_queue::schedule::load(app.handle()),
+            ))));
+            app.manage(EventSub(Mutex::new(EventSubState::new(eventsub::load(
+                app.handle(),
+            )))));
+            if headless {
+                println!("[Tauri] Running headless -- open the dashboard from the tray");
+            } else {
+                tray::create_main_window(app.handle())?;
+            }
+            tray::create_tray(app.handle())?;
+            notifications::listen_for_events(app.handle());
+            port_file::watch(app.handle());
+            signals::exit_on_signal(app.handle());
+
+            db::backup::backup_on_start(app.handle());
+            if let Err(e) = sidecar::start_sidecar(app.handle()) {
                 eprintln!("[Tauri] Sidecar startup failed: {}", e);
                 // Don't crash the app -- the frontend will show an error
                 // when it can't get the port.
             }
+            sidecar::watchdog::spawn_watchdog(app.handle().clone());
+            alert_queue::schedule::spawn_scheduler(app.handle().clone());
+            eventsub::spawn_eventsub(app.handle().clone());
             Ok(())
         })
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error while building StreamForge");
 
     app.run(|app_handle, event| {
         if let tauri::RunEvent::Exit = event {
-            // Gracefully kill the sidecar when the app exits
-            let state = app_handle.state::<SidecarProcess>();
-            let mut child_opt = match state.0.lock() {
-                Ok(guard) => guard,
-                Err(_) => return,
-            };
-            if let Some(child) = child_opt.take() {
-                println!("[Tauri] Shutting down sidecar...");
-                if let Err(e) = child.kill() {
-                    eprintln!("[Tauri] Failed to kill sidecar: {}", e);
-                }
-                // Give the sidecar a moment to clean up
-                std::thread::sleep(Duration::from_millis(500));
+            // Stop the supervisor first so the terminating sidecar isn't respawned
+            if let Ok(mut supervisor) = app_handle.state::<SidecarSupervisor>().0.lock() {
+                supervisor.begin_shutdown();
             }
+
+            // Ask the sidecar to close its database and sockets, and only
+            // kill it if it doesn't confirm in time
+            sidecar::shutdown::stop_sidecar(app_handle, sidecar::shutdown::shutdown_timeout());
+            port_file::remove(app_handle);
         }
     });
 }
@@ -0,0 +1,169 @@
+//! Uploaded sound and image files.
+//!
+//! The server stores uploads in `<data dir>/streamforge-data/{sounds,images}`
+//! under unique names and references them from `sound_path`/`image_path` as
+//! the URL it serves them on (`/sounds/<file>`, `/images/<file>`); see
+//! `server/routes/upload.js`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::db;
+
+const MEDIA_DIR: &str = "streamforge-data";
+
+/// Same limit as uploads through the server.
+pub(crate) const MAX_MEDIA_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum MediaKind {
+    Sound,
+    Image,
+}
+
+impl MediaKind {
+    /// Upload subfolder, which is also the URL prefix the server uses.
+    pub(crate) fn subdir(self) -> &'static str {
+        match self {
+            Self::Sound => "sounds",
+            Self::Image => "images",
+        }
+    }
+
+    /// Extensions the server accepts for uploads of this kind.
+    pub(crate) fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Sound => &["mp3", "wav", "ogg"],
+            Self::Image => &["png", "jpg", "jpeg", "gif", "webp"],
+        }
+    }
+
+    /// Whether `file_name` has one of `extensions()`.
+    pub(crate) fn accepts(self, file_name: &str) -> bool {
+        Path::new(file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                self.extensions()
+                    .contains(&ext.to_ascii_lowercase().as_str())
+            })
+    }
+
+    /// The column holding a reference to this kind of file.
+    pub(crate) fn column(self) -> &'static str {
+        match self {
+            Self::Sound => "sound_path",
+            Self::Image => "image_path",
+        }
+    }
+
+    pub(crate) const ALL: [MediaKind; 2] = [Self::Sound, Self::Image];
+}
+
+/// Where a stored file ended up.
+#[derive(Clone, Debug)]
+pub(crate) struct StoredMedia {
+    /// Value for `sound_path`/`image_path`.
+    pub(crate) url: String,
+    /// `false` if an identical file was already present.
+    pub(crate) created: bool,
+    /// Set when a different file already had the requested name.
+    pub(crate) renamed_from: Option<String>,
+    pub(crate) path: PathBuf,
+}
+
+fn kind_dir(app: &AppHandle, kind: MediaKind) -> Result<PathBuf, String> {
+    db::data_dir(app).map(|dir| dir.join(MEDIA_DIR).join(kind.subdir()))
+}
+
+/// A bare file name, rejecting anything that could escape the media folder.
+fn plain_file_name(name: &str) -> Option<&str> {
+    let file_name = Path::new(name).file_name()?.to_str()?;
+    (file_name == name && !file_name.starts_with('.')).then_some(file_name)
+}
+
+/// The local file a `sound_path`/`image_path` value refers to: an uploaded
+/// file (`/sounds/<file>`) or an absolute path. `None` for remote URLs and
+/// files that don't exist.
+pub(crate) fn resolve(app: &AppHandle, kind: MediaKind, value: &str) -> Option<PathBuf> {
+    let prefix = format!("/{}/", kind.subdir());
+    let path = match value.strip_prefix(&prefix) {
+        Some(name) => kind_dir(app, kind).ok()?.join(plain_file_name(name)?),
+        None if Path::new(value).is_absolute() => PathBuf::from(value),
+        None => return None,
+    };
+    path.is_file().then_some(path)
+}
+
+/// Saves `bytes` as an uploaded file called `file_name` and returns the
+/// value to reference it by. An identical existing file is reused; a
+/// different one with the same name is left alone and the new file gets a
+/// numbered name instead.
+pub(crate) fn store(
+    app: &AppHandle,
+    kind: MediaKind,
+    file_name: &str,
+    bytes: &[u8],
+) -> Result<StoredMedia, String> {
+    let file_name = plain_file_name(file_name)
+        .ok_or_else(|| format!("Invalid media file name {:?}", file_name))?;
+    if !kind.accepts(file_name) {
+        return Err(format!(
+            "Unsupported {} file {:?} (allowed: {})",
+            kind.subdir(),
+            file_name,
+            kind.extensions().join(", ")
+        ));
+    }
+    let path = Path::new(file_name);
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("file");
+
+    let dir = kind_dir(app, kind)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let mut candidate = file_name.to_string();
+    let mut n = 1;
+    loop {
+        let target = dir.join(&candidate);
+        let renamed_from = (n > 1).then(|| file_name.to_string());
+        let url = format!("/{}/{}", kind.subdir(), candidate);
+
+        match fs::read(&target) {
+            Ok(existing) if existing == bytes => {
+                return Ok(StoredMedia {
+                    url,
+                    created: false,
+                    renamed_from,
+                    path: target,
+                })
+            }
+            Ok(_) => {
+                n += 1;
+                candidate = format!("{}-{}.{}", stem, n, extension);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                fs::write(&target, bytes)
+                    .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+                return Ok(StoredMedia {
+                    url,
+                    created: true,
+                    renamed_from,
+                    path: target,
+                });
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", target.display(), e)),
+        }
+    }
+}
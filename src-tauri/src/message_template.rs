@@ -0,0 +1,811 @@
+//! Alert message templates.
+//!
+//! Grammar (whitespace inside tags is ignored):
+//!
+//! ```text
+//! template  := ( text | "{{" | "}}" | value | if )*
+//! value     := "{" variable ( "|" filter )* "}"
+//! filter    := name ( ":" arg ( "," arg )* )?
+//! arg       := bare word | "double-quoted string"
+//! if        := "{#if " cond "}" template
+//!              ( "{#elif " cond "}" template )*
+//!              ( "{#else}" template )?
+//!              "{/if}"
+//! cond      := and ( "or" and )*
+//! and       := not ( "and" not )*
+//! not       := "not" not | compare
+//! compare   := operand ( ( "==" | "!=" | ">" | ">=" | "<" | "<=" ) operand )?
+//!            | "(" cond ")"
+//! operand   := variable | number | "double-quoted string"
+//! variable  := [A-Za-z_][A-Za-z0-9_]*
+//! ```
+//!
+//! `{{` and `}}` are literal braces. Missing variables render as empty
+//! text. A bare operand is true unless it is missing, empty, `0` or
+//! `false`; comparisons are numeric when both sides are numbers.
+//!
+//! Template conditions are parsed by `expression.rs` in its `Template`
+//! dialect, which deliberately differs from the variation condition
+//! language in `condition.rs`: text comparisons here are case-sensitive
+//! (`{#if type == "Cheer"}` doesn't match `cheer`), and operands are read
+//! up to the next space, bracket or operator, so `-5` and `1.5` are
+//! numbers and `user.name` is an invalid name rather than two tokens.
+//! Templates saved with this grammar must keep rendering the same.
+//!
+//! Filters:
+//!
+//! | filter                 | effect                                          |
+//! |------------------------|-------------------------------------------------|
+//! | `upper`, `lower`       | change case                                     |
+//! | `capitalize`           | uppercase the first letter                      |
+//! | `number[:decimals]`    | `1234.5` → `1,234.5` (`number:2` → `1,234.50`)  |
+//! | `currency[:CODE]`      | `5` → `$5.00`; defaults to USD                  |
+//! | `plural[:one[,other]]` | `one` if the value is 1, else `other`; defaults |
+//! |                        | to `""`/`"s"`, i.e. a plural suffix             |
+//! | `default:text`         | `text` if the value is missing or empty         |
+//! | `truncate:length`      | cut to `length` characters, adding `…`          |
+//!
+//! Example: `{username|upper} cheered {amount|number} bit{amount|plural}!
+//! {#if amount >= 1000}HYPE!{/if}`
+//!
+//! Output is plain text; the overlay escapes it before display.
+//!
+//! The server renders live alerts with the same language in
+//! `server/alerts/messageTemplates.js`; keep the two in step.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::expression::{
+    as_number, as_text, error, is_variable, Dialect, Expression, ParseError, SourceIssue, Span,
+};
+
+/// Variables every alert provides; others only warn when used.
+const KNOWN_VARIABLES: [&str; 4] = ["username", "amount", "message", "type"];
+
+// ---------------------------------------------------------------------------
+// Syntax Tree
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Value {
+        variable: String,
+        span: Span,
+        filters: Vec<Filter>,
+    },
+    If {
+        branches: Vec<(Expression, Vec<Node>)>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Debug)]
+enum Filter {
+    Upper,
+    Lower,
+    Capitalize,
+    Number(Option<usize>),
+    Currency(String),
+    Plural(String, String),
+    Default(String),
+    Truncate(usize),
+}
+
+/// A parsed template, ready to render.
+#[derive(Debug)]
+pub(crate) struct Template {
+    nodes: Vec<Node>,
+}
+
+// ---------------------------------------------------------------------------
+// Checks
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TemplateCheck {
+    pub(crate) valid: bool,
+    pub(crate) errors: Vec<SourceIssue>,
+    /// Variables alerts don't provide, which will render empty.
+    pub(crate) warnings: Vec<SourceIssue>,
+    /// Variables the template uses, in order of first use.
+    pub(crate) variables: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/// A `{...}` tag: its trimmed content and where that content sits.
+struct Tag<'a> {
+    content: &'a str,
+    start: usize,
+    span: Span,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+/// Splits `text` on `separator` outside double quotes, keeping each
+/// piece's offset.
+fn split_unquoted(text: &str, separator: char) -> Vec<(usize, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push((start, &text[start..i]));
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push((start, &text[start..]));
+    parts
+}
+
+/// A filter argument: trimmed, with surrounding quotes removed.
+fn argument(text: &str) -> String {
+    let text = text.trim();
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text)
+        .to_string()
+}
+
+impl<'a> Parser<'a> {
+    /// Parses nodes until end of input or a closing `{#elif}`, `{#else}` or
+    /// `{/if}` tag, which is returned unconsumed.
+    fn nodes(&mut self) -> Result<(Vec<Node>, Option<Tag<'a>>), ParseError> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+        while self.pos < self.source.len() {
+            let rest = &self.source[self.pos..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                text.push_str(&rest[..1]);
+                self.pos += 2;
+                continue;
+            }
+            if rest.starts_with('}') {
+                return error(
+                    "Unmatched \"}\" (write \"}}\" for a literal brace)",
+                    (self.pos, self.pos + 1),
+                );
+            }
+            if !rest.starts_with('{') {
+                let c = rest.chars().next().unwrap_or_default();
+                text.push(c);
+                self.pos += c.len_utf8();
+                continue;
+            }
+
+            let tag = self.tag()?;
+            if tag.content.starts_with("#elif")
+                || tag.content.starts_with("#else")
+                || tag.content == "/if"
+            {
+                if !text.is_empty() {
+                    nodes.push(Node::Text(text));
+                }
+                return Ok((nodes, Some(tag)));
+            }
+            if !text.is_empty() {
+                nodes.push(Node::Text(std::mem::take(&mut text)));
+            }
+            nodes.push(match tag.content.strip_prefix("#if") {
+                Some(cond) => self.if_block(cond, &tag)?,
+                None if tag.content.starts_with(['#', '/']) => {
+                    return error(format!("Unknown tag \"{{{}}}\"", tag.content), tag.span)
+                }
+                None => value(&tag)?,
+            });
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok((nodes, None))
+    }
+
+    /// Reads the `{...}` tag at the cursor.
+    fn tag(&mut self) -> Result<Tag<'a>, ParseError> {
+        let open = self.pos;
+        let mut quoted = false;
+        for (i, c) in self.source[open + 1..].char_indices() {
+            match c {
+                '"' => quoted = !quoted,
+                '{' if !quoted => break,
+                '}' if !quoted => {
+                    let inner = &self.source[open + 1..open + 1 + i];
+                    let start = open + 1 + (inner.len() - inner.trim_start().len());
+                    let content = inner.trim();
+                    self.pos = open + i + 2;
+                    return Ok(Tag {
+                        content,
+                        start,
+                        span: (open, self.pos),
+                    });
+                }
+                _ => {}
+            }
+        }
+        error(
+            "Unclosed \"{\" (write \"{{\" for a literal brace)",
+            (open, open + 1),
+        )
+    }
+
+    fn if_block(&mut self, cond: &str, open: &Tag) -> Result<Node, ParseError> {
+        let mut branches = Vec::new();
+        let mut cond_tag = (cond, open.start + 3, open.span);
+        loop {
+            let (text, start, span) = cond_tag;
+            let condition = condition(text, start, span)?;
+            let (body, end) = self.nodes()?;
+            branches.push((condition, body));
+            let Some(end) = end else {
+                return error("\"{#if}\" is missing its \"{/if}\"", open.span);
+            };
+            if let Some(cond) = end.content.strip_prefix("#elif") {
+                cond_tag = (cond, end.start + 5, end.span);
+                continue;
+            }
+            if end.content.starts_with("#else") {
+                if end.content != "#else" {
+                    return error(
+                        "\"{#else}\" takes no condition (use \"{#elif ...}\")",
+                        end.span,
+                    );
+                }
+                let (otherwise, close) = self.nodes()?;
+                match close {
+                    Some(close) if close.content == "/if" => {}
+                    Some(close) => {
+                        return error("Expected \"{/if}\" after \"{#else}\"", close.span)
+                    }
+                    None => return error("\"{#if}\" is missing its \"{/if}\"", open.span),
+                }
+                return Ok(Node::If {
+                    branches,
+                    otherwise,
+                });
+            }
+            return Ok(Node::If {
+                branches,
+                otherwise: Vec::new(),
+            });
+        }
+    }
+}
+
+/// Parses the contents of a `{variable|filter...}` tag.
+fn value(tag: &Tag) -> Result<Node, ParseError> {
+    let mut parts = split_unquoted(tag.content, '|').into_iter();
+    let (_, variable) = parts.next().unwrap_or_default();
+    let variable = variable.trim();
+    let variable_span = (tag.start, tag.start + variable.len());
+    if variable.is_empty() {
+        return error("Empty placeholder", tag.span);
+    }
+    if !is_variable(variable) {
+        return error(
+            format!("Invalid variable name \"{}\"", variable),
+            variable_span,
+        );
+    }
+
+    let filters = parts
+        .map(|(offset, text)| {
+            let leading = text.len() - text.trim_start().len();
+            let start = tag.start + offset + leading;
+            filter(text.trim(), (start, start + text.trim().len()))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Node::Value {
+        variable: variable.to_string(),
+        span: variable_span,
+        filters,
+    })
+}
+
+fn filter(text: &str, span: Span) -> Result<Filter, ParseError> {
+    let (name, args) = match text.split_once(':') {
+        Some((name, args)) => (
+            name.trim(),
+            split_unquoted(args, ',')
+                .into_iter()
+                .map(|(_, arg)| argument(arg))
+                .collect(),
+        ),
+        None => (text, Vec::new()),
+    };
+    let arity = |min: usize, max: usize| {
+        if args.len() < min || args.len() > max {
+            let expected = match (min, max) {
+                (0, 0) => "no arguments".to_string(),
+                (a, b) if a == b => format!("{} argument(s)", a),
+                (a, b) => format!("{} to {} arguments", a, b),
+            };
+            return error(format!("\"{}\" takes {}", name, expected), span);
+        }
+        Ok(())
+    };
+    let count = |arg: &str| {
+        arg.parse::<usize>().or_else(|_| {
+            error(
+                format!("\"{}\" expects a whole number, got \"{}\"", name, arg),
+                span,
+            )
+        })
+    };
+
+    let filter = match name {
+        "" => return error("Empty filter after \"|\"", span),
+        "upper" => arity(0, 0).map(|_| Filter::Upper)?,
+        "lower" => arity(0, 0).map(|_| Filter::Lower)?,
+        "capitalize" => arity(0, 0).map(|_| Filter::Capitalize)?,
+        "number" => {
+            arity(0, 1)?;
+            let decimals = args.first().map(|arg| count(arg)).transpose()?;
+            if decimals.is_some_and(|d| d > 10) {
+                return error("\"number\" supports up to 10 decimals", span);
+            }
+            Filter::Number(decimals)
+        }
+        "currency" => {
+            arity(0, 1)?;
+            let code = args.first().map_or("USD".to_string(), |c| c.to_ascii_uppercase());
+            if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+                return error(format!("\"{}\" is not a currency code like USD", code), span);
+            }
+            Filter::Currency(code)
+        }
+        "plural" => {
+            arity(0, 2)?;
+            let one = args.first().cloned().unwrap_or_default();
+            let other = args.get(1).cloned().unwrap_or_else(|| format!("{}s", one));
+            Filter::Plural(one, other)
+        }
+        "default" => arity(1, 1).map(|_| Filter::Default(args[0].clone()))?,
+        "truncate" => {
+            arity(1, 1)?;
+            Filter::Truncate(count(&args[0])?)
+        }
+        _ => {
+            return error(
+                format!(
+                    "Unknown filter \"{}\" (available: upper, lower, capitalize, number, currency, plural, default, truncate)",
+                    name
+                ),
+                span,
+            )
+        }
+    };
+    Ok(filter)
+}
+
+// ---------------------------------------------------------------------------
+// Conditions
+// ---------------------------------------------------------------------------
+
+/// Parses the condition of an `{#if}`/`{#elif}` tag; `start` is where
+/// `text` begins in the template.
+fn condition(text: &str, start: usize, tag: Span) -> Result<Expression, ParseError> {
+    if text.trim().is_empty() {
+        return error("Missing condition", tag);
+    }
+    if !text.starts_with(char::is_whitespace) {
+        return error("Expected a space before the condition", tag);
+    }
+    Expression::parse(text, start, Dialect::Template)
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+/// `1234.5` with `decimals` → `1,234.50`; without, as many as needed.
+fn group_thousands(number: f64, decimals: Option<usize>) -> String {
+    let formatted = match decimals {
+        Some(decimals) => format!("{:.*}", decimals, number.abs()),
+        None => number.abs().to_string(),
+    };
+    let (whole, fraction) = match formatted.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    if number < 0.0 && grouped.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+fn currency(number: f64, code: &str) -> String {
+    let (symbol, decimals) = match code {
+        "USD" => ("$", 2),
+        "EUR" => ("€", 2),
+        "GBP" => ("£", 2),
+        "JPY" => ("¥", 0),
+        "CAD" => ("CA$", 2),
+        "AUD" => ("A$", 2),
+        "BRL" => ("R$", 2),
+        "INR" => ("₹", 2),
+        _ => return format!("{} {}", group_thousands(number, Some(2)), code),
+    };
+    let amount = group_thousands(number, Some(decimals));
+    match amount.strip_prefix('-') {
+        Some(amount) => format!("-{}{}", symbol, amount),
+        None => format!("{}{}", symbol, amount),
+    }
+}
+
+fn apply(filter: &Filter, text: String, number: Option<f64>) -> String {
+    match filter {
+        Filter::Upper => text.to_uppercase(),
+        Filter::Lower => text.to_lowercase(),
+        Filter::Capitalize => {
+            let mut chars = text.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => text,
+            }
+        }
+        Filter::Number(decimals) => number.map_or(text, |n| group_thousands(n, *decimals)),
+        Filter::Currency(code) => number.map_or(text, |n| currency(n, code)),
+        Filter::Plural(one, other) => match number {
+            Some(1.0) => one.clone(),
+            _ => other.clone(),
+        },
+        Filter::Default(fallback) if text.is_empty() => fallback.clone(),
+        Filter::Default(_) => text,
+        Filter::Truncate(length) if text.chars().count() > *length => {
+            let mut cut: String = text.chars().take(*length).collect();
+            cut.push('…');
+            cut
+        }
+        Filter::Truncate(_) => text,
+    }
+}
+
+fn render_nodes(nodes: &[Node], variables: &Map<String, Value>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value {
+                variable, filters, ..
+            } => {
+                let value = variables.get(variable);
+                let number = as_number(value);
+                let text = filters
+                    .iter()
+                    .fold(as_text(value), |text, filter| apply(filter, text, number));
+                out.push_str(&text);
+            }
+            Node::If {
+                branches,
+                otherwise,
+            } => {
+                let body = branches
+                    .iter()
+                    .find(|(cond, _)| cond.evaluate(variables))
+                    .map_or(otherwise, |(_, body)| body);
+                render_nodes(body, variables, out);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+impl Template {
+    pub(crate) fn parse(source: &str) -> Result<Self, SourceIssue> {
+        let mut parser = Parser { source, pos: 0 };
+        let result = parser.nodes().and_then(|(nodes, stray)| match stray {
+            Some(tag) => error(
+                format!("\"{{{}}}\" without a matching \"{{#if}}\"", tag.content),
+                tag.span,
+            ),
+            None => Ok(nodes),
+        });
+        result
+            .map(|nodes| Self { nodes })
+            .map_err(|e| SourceIssue::new(source, e.message, e.span))
+    }
+
+    pub(crate) fn render(&self, variables: &Map<String, Value>) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, variables, &mut out);
+        out
+    }
+
+    /// Every variable reference, in order.
+    fn references(&self) -> Vec<(&str, Span)> {
+        fn node_refs<'t>(nodes: &'t [Node], refs: &mut Vec<(&'t str, Span)>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Value { variable, span, .. } => refs.push((variable, *span)),
+                    Node::If {
+                        branches,
+                        otherwise,
+                    } => {
+                        for (cond, body) in branches {
+                            refs.extend(cond.variables());
+                            node_refs(body, refs);
+                        }
+                        node_refs(otherwise, refs);
+                    }
+                }
+            }
+        }
+        let mut refs = Vec::new();
+        node_refs(&self.nodes, &mut refs);
+        refs
+    }
+}
+
+/// Parses `source` and reports syntax errors and unknown variables.
+pub(crate) fn check(source: &str) -> TemplateCheck {
+    let template = match Template::parse(source) {
+        Ok(template) => template,
+        Err(issue) => {
+            return TemplateCheck {
+                valid: false,
+                errors: vec![issue],
+                warnings: Vec::new(),
+                variables: Vec::new(),
+            }
+        }
+    };
+
+    let mut variables: Vec<String> = Vec::new();
+    let mut warnings = Vec::new();
+    for (name, span) in template.references() {
+        if !KNOWN_VARIABLES.contains(&name) {
+            warnings.push(SourceIssue::new(
+                source,
+                format!(
+                    "\"{}\" is not provided by alerts (available: {}) and will be empty",
+                    name,
+                    KNOWN_VARIABLES.join(", ")
+                ),
+                span,
+            ));
+        }
+        if !variables.iter().any(|v| v == name) {
+            variables.push(name.to_string());
+        }
+    }
+    TemplateCheck {
+        valid: true,
+        errors: Vec::new(),
+        warnings,
+        variables,
+    }
+}
+
+/// Parses and renders `source` with `variables`.
+pub(crate) fn render(source: &str, variables: &Map<String, Value>) -> Result<String, String> {
+    Template::parse(source)
+        .map(|template| template.render(variables))
+        .map_err(|issue| issue.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn variables() -> Map<String, Value> {
+        json!({
+            "username": "alice",
+            "amount": 1234.5,
+            "message": "",
+            "type": "cheer",
+            "yes": 1,
+            "no": 0,
+            "count": "1000",
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    }
+
+    /// Renders every `(template, expected)` with `variables()`.
+    fn assert_renders(cases: &[(&str, &str)]) {
+        let variables = variables();
+        for (source, expected) in cases {
+            let rendered = render(source, &variables)
+                .unwrap_or_else(|e| panic!("{:?} failed to parse: {}", source, e));
+            assert_eq!(rendered, *expected, "{}", source);
+        }
+    }
+
+    /// Parses `source`, expecting an error with `message` at UTF-16 `span`.
+    fn assert_error(source: &str, message: &str, span: Span) {
+        let issue = Template::parse(source).expect_err(source);
+        assert_eq!(
+            (issue.message.as_str(), (issue.start, issue.end)),
+            (message, span),
+            "{}",
+            source
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        assert_renders(&[
+            // and binds tighter than or
+            ("{#if yes or yes and no}Y{#else}N{/if}", "Y"),
+            ("{#if no and yes or yes}Y{#else}N{/if}", "Y"),
+            ("{#if (yes or yes) and no}Y{#else}N{/if}", "N"),
+            // not binds tighter than and
+            ("{#if not no and no}Y{#else}N{/if}", "N"),
+            ("{#if not (no and no)}Y{#else}N{/if}", "Y"),
+            ("{#if not not yes}Y{#else}N{/if}", "Y"),
+            ("{#if not amount > 2000 and yes}Y{#else}N{/if}", "Y"),
+        ]);
+    }
+
+    #[test]
+    fn branches() {
+        assert_renders(&[
+            (
+                "{#if amount >= 1000}big{#elif amount >= 100}mid{#else}small{/if}",
+                "big",
+            ),
+            (
+                "{#if amount >= 5000}big{#elif amount >= 100}mid{#else}small{/if}",
+                "mid",
+            ),
+            ("{#if no}a{#elif no}b{/if}!", "!"),
+            ("{#if yes}{#if no}x{#else}y{/if}{/if}", "y"),
+        ]);
+    }
+
+    #[test]
+    fn numeric_and_text_comparison() {
+        assert_renders(&[
+            // Numeric when both sides are numbers, numeric strings included
+            ("{#if count == 1000}Y{#else}N{/if}", "Y"),
+            ("{#if count == \"1000.0\"}Y{#else}N{/if}", "Y"),
+            ("{#if amount > -5}Y{#else}N{/if}", "Y"),
+            ("{#if amount <= 1234.5}Y{#else}N{/if}", "Y"),
+            ("{#if amount < count}Y{#else}N{/if}", "N"),
+            // Text otherwise, and case-sensitive
+            ("{#if type == \"cheer\"}Y{#else}N{/if}", "Y"),
+            ("{#if type == \"Cheer\"}Y{#else}N{/if}", "N"),
+            ("{#if type != \"Cheer\"}Y{#else}N{/if}", "Y"),
+            // Ordering needs numbers
+            ("{#if type > \"a\"}Y{#else}N{/if}", "N"),
+            ("{#if missing < 1}Y{#else}N{/if}", "N"),
+            ("{#if missing != 1}Y{#else}N{/if}", "Y"),
+            // Truthiness of bare operands
+            ("{#if message}Y{#else}N{/if}", "N"),
+            ("{#if missing}Y{#else}N{/if}", "N"),
+            ("{#if no}Y{#else}N{/if}", "N"),
+            ("{#if \"false\"}Y{#else}N{/if}", "N"),
+            ("{#if \"False\"}Y{#else}N{/if}", "Y"),
+        ]);
+    }
+
+    #[test]
+    fn escapes_and_quoted_arguments() {
+        assert_renders(&[
+            ("{{username}} is {username}", "{username} is alice"),
+            ("}}{{", "}{"),
+            ("{message|default:\"a, b | c\"}", "a, b | c"),
+            ("{message|default:\"{}\"}", "{}"),
+            ("{#if type == \"a}b\"}Y{#else}N{/if}", "N"),
+        ]);
+    }
+
+    #[test]
+    fn filters() {
+        assert_renders(&[
+            ("{username|upper}", "ALICE"),
+            ("{username|capitalize}", "Alice"),
+            ("{amount|number}", "1,234.5"),
+            ("{amount|number:2}", "1,234.50"),
+            ("{count|number}", "1,000"),
+            ("{amount|currency}", "$1,234.50"),
+            ("{amount|currency:eur}", "€1,234.50"),
+            ("{amount|currency:CHF}", "1,234.50 CHF"),
+            ("bit{yes|plural}", "bit"),
+            ("bit{count|plural}", "bits"),
+            ("{yes|plural:person,people}", "person"),
+            ("{message|default:anonymous|upper}", "ANONYMOUS"),
+            ("{username|truncate:3}", "ali…"),
+            ("{missing}", ""),
+        ]);
+    }
+
+    #[test]
+    fn error_spans() {
+        assert_error(
+            "Hi {username",
+            "Unclosed \"{\" (write \"{{\" for a literal brace)",
+            (3, 4),
+        );
+        assert_error(
+            "Hi }",
+            "Unmatched \"}\" (write \"}}\" for a literal brace)",
+            (3, 4),
+        );
+        assert_error("{#if amount >}x{/if}", "Incomplete condition", (13, 13));
+        assert_error(
+            "{#if amount}x",
+            "\"{#if}\" is missing its \"{/if}\"",
+            (0, 12),
+        );
+        assert_error("{#if (yes}x{/if}", "Expected \")\"", (9, 9));
+        assert_error(
+            "{#if yes no}x{/if}",
+            "Unexpected text after condition",
+            (9, 11),
+        );
+        assert_error(
+            "{#if user.name}x{/if}",
+            "Invalid name \"user.name\"",
+            (5, 14),
+        );
+        assert_error(
+            "{#ifyes}x{/if}",
+            "Expected a space before the condition",
+            (0, 8),
+        );
+        assert_error(
+            "{#else}",
+            "\"{#else}\" without a matching \"{#if}\"",
+            (0, 7),
+        );
+        assert_error(
+            "{username|shout}",
+            "Unknown filter \"shout\" (available: upper, lower, capitalize, number, currency, plural, default, truncate)",
+            (10, 15),
+        );
+        assert_error(
+            "{user name}",
+            "Invalid variable name \"user name\"",
+            (1, 10),
+        );
+        // Offsets count UTF-16 units, as the editor's JavaScript strings do
+        assert_error("é ✨ {#if}x{/if}", "Missing condition", (4, 9));
+    }
+
+    #[test]
+    fn check_reports_unknown_variables() {
+        let result = check("{#if tier == 2}{username}{/if}{tier}");
+        assert!(result.valid);
+        assert_eq!(result.variables, ["tier", "username"]);
+        let spans: Vec<(usize, usize)> = result
+            .warnings
+            .iter()
+            .map(|warning| (warning.start, warning.end))
+            .collect();
+        assert_eq!(spans, [(5, 9), (31, 35)]);
+
+        let result = check("{#if}");
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 1);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Listener, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::sidecar::protocol::StreamEvent;
+use crate::sidecar::EVENT_STREAM_EVENT;
+
+/// File in the app config dir holding the persisted `NotificationSettings`.
+const CONFIG_FILE: &str = "notifications.json";
+
+// ---------------------------------------------------------------------------
+// Settings
+// ---------------------------------------------------------------------------
+
+/// Per event type notification rule.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct EventRule {
+    pub(crate) muted: bool,
+    /// Events with a smaller amount (bits, viewers, money...) are skipped.
+    pub(crate) min_amount: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct NotificationSettings {
+    /// Suppresses every notification without touching the per-type rules.
+    pub(crate) do_not_disturb: bool,
+    /// Rules keyed by event type (`follow`, `subscribe`, `cheer`...). Types
+    /// without a rule don't notify.
+    pub(crate) rules: BTreeMap<String, EventRule>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        let rules = ["follow", "subscribe", "cheer", "raid", "donation"]
+            .into_iter()
+            .map(|event_type| (event_type.to_string(), EventRule::default()))
+            .collect();
+
+        Self {
+            do_not_disturb: false,
+            rules,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        for (event_type, rule) in &self.rules {
+            if event_type.trim().is_empty() {
+                return Err("Event type must not be empty".to_string());
+            }
+            if rule
+                .min_amount
+                .is_some_and(|min| !min.is_finite() || min < 0.0)
+            {
+                return Err(format!(
+                    "Minimum amount for {} must be zero or more",
+                    event_type
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `event` should raise a notification.
+    fn should_notify(&self, event: &StreamEvent) -> bool {
+        if self.do_not_disturb {
+            return false;
+        }
+        let Some(rule) = self.rules.get(&event.event_type) else {
+            return false;
+        };
+        if rule.muted {
+            return false;
+        }
+        match rule.min_amount {
+            Some(min) => event.amount.is_some_and(|amount| amount >= min),
+            None => true,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+pub(crate) struct Notifications(pub(crate) Mutex<NotificationSettings>);
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Loads the persisted settings, falling back to defaults if there are none
+/// or they can't be used.
+pub(crate) fn load(app: &AppHandle) -> NotificationSettings {
+    let Ok(path) = config_path(app) else {
+        return NotificationSettings::default();
+    };
+    let Ok(text) = fs::read_to_string(&path) else {
+        return NotificationSettings::default();
+    };
+
+    match serde_json::from_str::<NotificationSettings>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(|settings| settings.validate().map(|_| settings))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!(
+                "[Tauri] Ignoring invalid notification settings {}: {}",
+                path.display(),
+                e
+            );
+            NotificationSettings::default()
+        }
+    }
+}
+
+/// Writes the settings to the app config dir.
+pub(crate) fn save(app: &AppHandle, settings: &NotificationSettings) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize notification settings: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Applies `change` to the stored settings, persists them and returns the
+/// result.
+pub(crate) fn update(
+    app: &AppHandle,
+    change: impl FnOnce(&mut NotificationSettings),
+) -> Result<NotificationSettings, String> {
+    let state = app.state::<Notifications>();
+    let mut settings = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to update notification settings: {}", e))?;
+
+    let mut updated = settings.clone();
+    change(&mut updated);
+    updated.validate()?;
+    save(app, &updated)?;
+
+    *settings = updated.clone();
+    Ok(updated)
+}
+
+// ---------------------------------------------------------------------------
+// Delivery
+// ---------------------------------------------------------------------------
+
+/// Raises OS notifications for stream events reported by the sidecar.
+pub(crate) fn listen_for_events(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any(
+        EVENT_STREAM_EVENT,
+        move |event| match serde_json::from_str::<StreamEvent>(event.payload()) {
+            Ok(stream_event) => notify(&handle, &stream_event),
+            Err(e) => eprintln!("[Tauri] Ignoring malformed stream event: {}", e),
+        },
+    );
+}
+
+fn notify(app: &AppHandle, event: &StreamEvent) {
+    let enabled = app
+        .state::<Notifications>()
+        .0
+        .lock()
+        .map(|settings| settings.should_notify(event))
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    let (title, body) = describe(event);
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("[Tauri] Failed to show notification: {}", e);
+    }
+}
+
+/// Title and body for a notification about `event`.
+fn describe(event: &StreamEvent) -> (String, String) {
+    let name = event.display_name.as_deref().unwrap_or(&event.username);
+    let amount = event
+        .amount
+        .map(|a| {
+            if a.fract() == 0.0 {
+                format!("{}", a as i64)
+            } else {
+                format!("{:.2}", a)
+            }
+        })
+        .unwrap_or_default();
+
+    match event.event_type.as_str() {
+        "follow" => (
+            "New follower".to_string(),
+            format!("{} just followed", name),
+        ),
+        "subscribe" => ("New subscriber".to_string(), format!("{} subscribed", name)),
+        "cheer" => (
+            "Cheer".to_string(),
+            format!("{} cheered {} bits", name, amount),
+        ),
+        "raid" => (
+            "Raid".to_string(),
+            format!("{} is raiding with {} viewers", name, amount),
+        ),
+        "donation" => (
+            "Donation".to_string(),
+            format!("{} donated {}", name, amount),
+        ),
+        other => {
+            let mut title: Vec<char> = other.chars().collect();
+            if let Some(first) = title.first_mut() {
+                *first = first.to_ascii_uppercase();
+            }
+            (title.into_iter().collect(), name.to_string())
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//! Coalescing of event bursts.
+//!
+//! During a follow train or a mass gift sub the overlay would otherwise
+//! play dozens of near-identical alerts. Events of a coalesced type are
+//! held for `window_ms` from the first one; everything of the same type
+//! that arrives in that window becomes a single aggregate alert ("12 new
+//! followers: a, b, c and 9 more"). Gifted subs are batched per gifter
+//! instead and use the rule's `gift_template` ("x gifted 50 subs!"). A
+//! window that only caught one event passes it on unchanged, and a batch
+//! that reaches `max_batch` is flushed early.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::IncomingEvent;
+
+// ---------------------------------------------------------------------------
+// Settings
+// ---------------------------------------------------------------------------
+
+/// How the aggregate alert for a type reads. `{username}` shows the
+/// summarized names (or the gifter) and `{amount}` the number of events.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CoalesceRule {
+    pub(crate) template: String,
+    /// Used for subs gifted by one viewer; without it gifted subs are
+    /// batched with the others.
+    #[serde(default)]
+    pub(crate) gift_template: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct CoalescingSettings {
+    pub(crate) enabled: bool,
+    /// How long a batch stays open after its first event.
+    pub(crate) window_ms: u64,
+    /// A batch this big is flushed without waiting for the window.
+    pub(crate) max_batch: usize,
+    /// Names spelled out before "and N more".
+    pub(crate) names_shown: usize,
+    /// Rules keyed by event type; other types are never coalesced.
+    pub(crate) rules: BTreeMap<String, CoalesceRule>,
+}
+
+impl Default for CoalescingSettings {
+    fn default() -> Self {
+        let rules = BTreeMap::from([
+            (
+                "follow".to_string(),
+                CoalesceRule {
+                    template: "{amount} new followers: {username}".to_string(),
+                    gift_template: None,
+                },
+            ),
+            (
+                "subscribe".to_string(),
+                CoalesceRule {
+                    template: "{amount} new subscribers: {username}".to_string(),
+                    gift_template: Some("{username} gifted {amount} subs!".to_string()),
+                },
+            ),
+        ]);
+
+        Self {
+            enabled: true,
+            window_ms: 3000,
+            max_batch: 100,
+            names_shown: 3,
+            rules,
+        }
+    }
+}
+
+impl CoalescingSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(100..=60_000).contains(&self.window_ms) {
+            return Err("Coalescing window must be between 100 ms and 60 s".to_string());
+        }
+        if self.max_batch < 2 {
+            return Err("Maximum batch size must be at least 2".to_string());
+        }
+        for (event_type, rule) in &self.rules {
+            if event_type.trim().is_empty() {
+                return Err("Event type must not be empty".to_string());
+            }
+            if rule.template.trim().is_empty()
+                || rule
+                    .gift_template
+                    .as_ref()
+                    .is_some_and(|t| t.trim().is_empty())
+            {
+                return Err(format!("Template for {} must not be empty", event_type));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Batching
+// ---------------------------------------------------------------------------
+
+/// Events batched together: same type, and the same gifter for gift subs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct BatchKey {
+    pub(crate) event_type: String,
+    pub(crate) gifter: Option<String>,
+}
+
+struct Batch {
+    id: u64,
+    events: Vec<IncomingEvent>,
+}
+
+/// What to do after an event was pushed.
+pub(crate) enum Push {
+    /// Turn these events into an alert now.
+    Flush(Vec<IncomingEvent>),
+    /// The event opened a batch; flush it with `take` once the window ends.
+    Opened(BatchKey, u64),
+    /// The event joined an open batch.
+    Held,
+}
+
+#[derive(Default)]
+pub(crate) struct Coalescer {
+    batches: HashMap<BatchKey, Batch>,
+    next_id: u64,
+}
+
+impl Coalescer {
+    pub(crate) fn push(&mut self, settings: &CoalescingSettings, event: IncomingEvent) -> Push {
+        let Some(rule) = settings
+            .rules
+            .get(&event.event_type)
+            .filter(|_| settings.enabled)
+        else {
+            return Push::Flush(vec![event]);
+        };
+
+        let key = BatchKey {
+            event_type: event.event_type.clone(),
+            gifter: event
+                .gifter
+                .clone()
+                .filter(|_| rule.gift_template.is_some()),
+        };
+        if let Some(batch) = self.batches.get_mut(&key) {
+            batch.events.push(event);
+            if batch.events.len() >= settings.max_batch {
+                let batch = self.batches.remove(&key).map(|b| b.events);
+                return Push::Flush(batch.unwrap_or_default());
+            }
+            return Push::Held;
+        }
+
+        self.next_id += 1;
+        self.batches.insert(
+            key.clone(),
+            Batch {
+                id: self.next_id,
+                events: vec![event],
+            },
+        );
+        Push::Opened(key, self.next_id)
+    }
+
+    /// Removes the batch opened as `id`, unless it was already flushed.
+    pub(crate) fn take(&mut self, key: &BatchKey, id: u64) -> Option<Vec<IncomingEvent>> {
+        if self.batches.get(key)?.id != id {
+            return None;
+        }
+        self.batches.remove(key).map(|batch| batch.events)
+    }
+
+    /// Removes every open batch, e.g. when coalescing is switched off.
+    pub(crate) fn drain(&mut self) -> Vec<Vec<IncomingEvent>> {
+        self.batches
+            .drain()
+            .map(|(_, batch)| batch.events)
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Aggregation
+// ---------------------------------------------------------------------------
+
+/// The aggregate alert's `username`, `displayName`, `amount` and message
+/// template.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Aggregate {
+    pub(crate) username: String,
+    pub(crate) display_name: String,
+    pub(crate) amount: f64,
+    pub(crate) template: String,
+}
+
+/// "a", "a and b", "a, b and c", "a, b, c and 9 more".
+fn summarize_names(names: &[&str], shown: usize) -> String {
+    let shown = shown.max(1);
+    match names {
+        [] => String::new(),
+        [one] => one.to_string(),
+        _ if names.len() <= shown => format!(
+            "{} and {}",
+            names[..names.len() - 1].join(", "),
+            names[names.len() - 1]
+        ),
+        _ => format!(
+            "{} and {} more",
+            names[..shown].join(", "),
+            names.len() - shown
+        ),
+    }
+}
+
+/// Summarizes a batch of two or more events, or returns `None` for a lone
+/// event (or a type without a rule), which plays as a normal alert.
+pub(crate) fn aggregate(
+    settings: &CoalescingSettings,
+    events: &[IncomingEvent],
+) -> Option<Aggregate> {
+    let first = events.first().filter(|_| events.len() > 1)?;
+    let rule = settings.rules.get(&first.event_type)?;
+    let count = events.len() as f64;
+
+    if let (Some(gifter), Some(template)) = (&first.gifter, &rule.gift_template) {
+        return Some(Aggregate {
+            username: gifter.clone(),
+            display_name: gifter.clone(),
+            amount: count,
+            template: template.clone(),
+        });
+    }
+
+    let mut names: Vec<&str> = Vec::new();
+    for event in events {
+        let name = event.display_name.as_deref().unwrap_or(&event.username);
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Some(Aggregate {
+        username: first.username.clone(),
+        display_name: summarize_names(&names, settings.names_shown),
+        amount: count,
+        template: rule.template.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Map;
+
+    use super::*;
+
+    fn event(event_type: &str, username: &str, gifter: Option<&str>) -> IncomingEvent {
+        IncomingEvent {
+            event_type: event_type.to_string(),
+            username: username.to_string(),
+            display_name: None,
+            amount: None,
+            message: None,
+            tier: None,
+            platform: "twitch".to_string(),
+            gifter: gifter.map(String::from),
+            metadata: Map::new(),
+            timestamp: None,
+        }
+    }
+
+    fn opened(push: Push) -> (BatchKey, u64) {
+        match push {
+            Push::Opened(key, id) => (key, id),
+            Push::Flush(_) => panic!("expected a new batch, got a flush"),
+            Push::Held => panic!("expected a new batch, got a held event"),
+        }
+    }
+
+    fn usernames(events: &[IncomingEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.username.as_str()).collect()
+    }
+
+    #[test]
+    fn takes_a_batch_when_its_window_ends() {
+        let settings = CoalescingSettings::default();
+        let mut coalescer = Coalescer::default();
+        let (key, id) = opened(coalescer.push(&settings, event("follow", "a", None)));
+        assert!(matches!(
+            coalescer.push(&settings, event("follow", "b", None)),
+            Push::Held
+        ));
+
+        let events = coalescer.take(&key, id).expect("batch is open");
+        assert_eq!(usernames(&events), ["a", "b"]);
+        // The window's timer fires once; a second take finds nothing
+        assert!(coalescer.take(&key, id).is_none());
+
+        // A stale timer from an earlier window doesn't flush a newer batch
+        let (key, newer) = opened(coalescer.push(&settings, event("follow", "c", None)));
+        assert!(coalescer.take(&key, id).is_none());
+        assert_eq!(usernames(&coalescer.take(&key, newer).unwrap()), ["c"]);
+    }
+
+    #[test]
+    fn flushes_a_full_batch_early() {
+        let settings = CoalescingSettings {
+            max_batch: 3,
+            ..CoalescingSettings::default()
+        };
+        let mut coalescer = Coalescer::default();
+        let (key, id) = opened(coalescer.push(&settings, event("follow", "a", None)));
+        coalescer.push(&settings, event("follow", "b", None));
+        match coalescer.push(&settings, event("follow", "c", None)) {
+            Push::Flush(events) => assert_eq!(usernames(&events), ["a", "b", "c"]),
+            _ => panic!("expected the full batch to flush"),
+        }
+        assert!(coalescer.take(&key, id).is_none());
+    }
+
+    #[test]
+    fn merges_gift_subs_per_gifter() {
+        let settings = CoalescingSettings::default();
+        let mut coalescer = Coalescer::default();
+        let (alice, alice_id) =
+            opened(coalescer.push(&settings, event("subscribe", "r1", Some("alice"))));
+        assert!(matches!(
+            coalescer.push(&settings, event("subscribe", "r2", Some("alice"))),
+            Push::Held
+        ));
+        let (bob, bob_id) =
+            opened(coalescer.push(&settings, event("subscribe", "r3", Some("bob"))));
+        let (own, own_id) = opened(coalescer.push(&settings, event("subscribe", "self", None)));
+        assert_ne!(alice, bob);
+        assert_ne!(alice, own);
+
+        let gifted = coalescer.take(&alice, alice_id).unwrap();
+        assert_eq!(usernames(&gifted), ["r1", "r2"]);
+        assert_eq!(
+            aggregate(&settings, &gifted),
+            Some(Aggregate {
+                username: "alice".to_string(),
+                display_name: "alice".to_string(),
+                amount: 2.0,
+                template: "{username} gifted {amount} subs!".to_string(),
+            })
+        );
+        assert_eq!(usernames(&coalescer.take(&bob, bob_id).unwrap()), ["r3"]);
+        assert_eq!(usernames(&coalescer.take(&own, own_id).unwrap()), ["self"]);
+    }
+
+    #[test]
+    fn keeps_types_apart_and_passes_others_through() {
+        let settings = CoalescingSettings::default();
+        let mut coalescer = Coalescer::default();
+        let (follows, _) = opened(coalescer.push(&settings, event("follow", "a", None)));
+        let (subs, _) = opened(coalescer.push(&settings, event("subscribe", "a", None)));
+        assert_ne!(follows, subs);
+
+        // No rule for cheers, so they never wait
+        match coalescer.push(&settings, event("cheer", "a", None)) {
+            Push::Flush(events) => assert_eq!(usernames(&events), ["a"]),
+            _ => panic!("expected a cheer to pass through"),
+        }
+
+        let disabled = CoalescingSettings {
+            enabled: false,
+            ..CoalescingSettings::default()
+        };
+        assert!(matches!(
+            coalescer.push(&disabled, event("follow", "b", None)),
+            Push::Flush(_)
+        ));
+    }
+
+    #[test]
+    fn drains_open_batches() {
+        let settings = CoalescingSettings::default();
+        let mut coalescer = Coalescer::default();
+        let (key, id) = opened(coalescer.push(&settings, event("follow", "a", None)));
+        coalescer.push(&settings, event("follow", "b", None));
+        coalescer.push(&settings, event("subscribe", "c", Some("alice")));
+
+        let mut drained: Vec<Vec<&str>> = Vec::new();
+        let batches = coalescer.drain();
+        for batch in &batches {
+            drained.push(usernames(batch));
+        }
+        drained.sort();
+        assert_eq!(drained, [vec!["a", "b"], vec!["c"]]);
+        assert!(coalescer.drain().is_empty());
+        assert!(coalescer.take(&key, id).is_none());
+    }
+
+    #[test]
+    fn aggregates_distinct_names() {
+        let settings = CoalescingSettings {
+            names_shown: 2,
+            ..CoalescingSettings::default()
+        };
+        let mut named = event("follow", "b", None);
+        named.display_name = Some("Bee".to_string());
+        let events = [
+            event("follow", "a", None),
+            named.clone(),
+            named,
+            event("follow", "c", None),
+            event("follow", "d", None),
+        ];
+        let summary = aggregate(&settings, &events).unwrap();
+        assert_eq!(summary.display_name, "a, Bee and 2 more");
+        assert_eq!(summary.amount, 5.0);
+        assert_eq!(summary.template, "{amount} new followers: {username}");
+
+        // A lone event plays as a normal alert
+        assert_eq!(aggregate(&settings, &events[..1]), None);
+    }
+}
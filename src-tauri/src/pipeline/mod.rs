@@ -0,0 +1,347 @@
+//! Event pipeline: turns stream events from the shell's own sources into
+//! alerts on the server.
+//!
+//! ```text
+//! ingest -> coalesce -> resolve -> enqueue (server)
+//! ```
+//!
+//! Events pass through `coalesce`, which may hold them for a short window
+//! and merge a burst into one aggregate alert. The alert's config is then
+//! resolved with the same rules the server uses (`crate::resolution`),
+//! and the result is sent to the server in the shape `enqueueAlert` takes,
+//! together with every underlying event so each one is still written to
+//! `event_log` on its own.
+
+pub(crate) mod coalesce;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::db;
+use crate::resolution;
+use crate::sidecar::protocol::{ShellMessage, ENQUEUE_PROTOCOL_VERSION};
+use crate::sidecar::{send_to_sidecar, SidecarInfo};
+use coalesce::{BatchKey, Coalescer, CoalescingSettings, Push};
+
+/// File in the app config dir holding the persisted `CoalescingSettings`.
+const CONFIG_FILE: &str = "alert_coalescing.json";
+
+/// Alert columns copied into the alert's `config`, as the dashboard's
+/// `alert:trigger` handler does.
+const CONFIG_FIELDS: [&str; 17] = [
+    "message_template",
+    "duration_ms",
+    "animation_in",
+    "animation_out",
+    "sound_path",
+    "sound_volume",
+    "image_path",
+    "font_family",
+    "font_size",
+    "text_color",
+    "bg_color",
+    "custom_css",
+    "tts_enabled",
+    "tts_voice",
+    "tts_rate",
+    "tts_pitch",
+    "tts_volume",
+];
+
+/// A stream event entering the pipeline.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IncomingEvent {
+    /// `follow`, `subscribe`, `cheer`, `raid`...
+    pub(crate) event_type: String,
+    pub(crate) username: String,
+    #[serde(default)]
+    pub(crate) display_name: Option<String>,
+    #[serde(default)]
+    pub(crate) amount: Option<f64>,
+    #[serde(default)]
+    pub(crate) message: Option<String>,
+    #[serde(default)]
+    pub(crate) tier: Option<String>,
+    #[serde(default = "default_platform")]
+    pub(crate) platform: String,
+    /// Who gifted the sub, for gifted subscriptions.
+    #[serde(default)]
+    pub(crate) gifter: Option<String>,
+    /// Platform-specific extras stored in `event_log.metadata`.
+    #[serde(default)]
+    pub(crate) metadata: Map<String, Value>,
+    /// When the event arrived; set by `ingest` if missing.
+    #[serde(default)]
+    pub(crate) timestamp: Option<String>,
+}
+
+fn default_platform() -> String {
+    "internal".to_string()
+}
+
+impl IncomingEvent {
+    /// The fields variation conditions can test.
+    fn condition_data(&self) -> Map<String, Value> {
+        let mut data = Map::new();
+        data.insert("username".to_string(), json!(self.username));
+        data.insert("amount".to_string(), json!(self.amount));
+        data.insert("message".to_string(), json!(self.message));
+        data.insert("tier".to_string(), json!(self.tier));
+        data.insert("platform".to_string(), json!(self.platform));
+        data.insert("type".to_string(), json!(self.event_type));
+        data
+    }
+
+    /// The row `logger.logEvent` writes to `event_log`.
+    fn log_entry(&self) -> Value {
+        let mut metadata = self.metadata.clone();
+        if let Some(gifter) = &self.gifter {
+            metadata.insert("gifter".to_string(), json!(gifter));
+        }
+        json!({
+            "platform": self.platform,
+            "event_type": self.event_type,
+            "username": self.username,
+            "display_name": self.display_name.as_deref().unwrap_or(&self.username),
+            "amount": self.amount,
+            "message": self.message,
+            "metadata": metadata,
+            "alert_fired": 1,
+            "timestamp": self.timestamp,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+pub(crate) struct EventPipeline(pub(crate) Mutex<PipelineState>);
+
+pub(crate) struct PipelineState {
+    pub(crate) settings: CoalescingSettings,
+    coalescer: Coalescer,
+}
+
+impl PipelineState {
+    pub(crate) fn new(settings: CoalescingSettings) -> Self {
+        Self {
+            settings,
+            coalescer: Coalescer::default(),
+        }
+    }
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Loads the persisted settings, falling back to defaults if there are none
+/// or they can't be used.
+pub(crate) fn load(app: &AppHandle) -> CoalescingSettings {
+    let Ok(path) = config_path(app) else {
+        return CoalescingSettings::default();
+    };
+    let Ok(text) = fs::read_to_string(&path) else {
+        return CoalescingSettings::default();
+    };
+
+    match serde_json::from_str::<CoalescingSettings>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(|settings| settings.validate().map(|_| settings))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!(
+                "[Tauri] Ignoring invalid alert coalescing settings {}: {}",
+                path.display(),
+                e
+            );
+            CoalescingSettings::default()
+        }
+    }
+}
+
+/// Writes the settings to the app config dir.
+fn save(app: &AppHandle, settings: &CoalescingSettings) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize alert coalescing settings: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Validates, persists and applies new settings. Turning coalescing off
+/// flushes the open batches right away.
+pub(crate) fn update(
+    app: &AppHandle,
+    settings: CoalescingSettings,
+) -> Result<CoalescingSettings, String> {
+    settings.validate()?;
+    save(app, &settings)?;
+    let flushed = {
+        let state = app.state::<EventPipeline>();
+        let mut state = state
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to update alert coalescing settings: {}", e))?;
+        state.settings = settings.clone();
+        if settings.enabled {
+            Vec::new()
+        } else {
+            state.coalescer.drain()
+        }
+    };
+    for events in flushed {
+        if let Err(e) = emit(app, events) {
+            eprintln!("[Tauri] Failed to flush coalesced events: {}", e);
+        }
+    }
+    Ok(settings)
+}
+
+// ---------------------------------------------------------------------------
+// Pipeline
+// ---------------------------------------------------------------------------
+
+/// Feeds an event into the pipeline. It reaches the server now, or when
+/// its coalescing window closes.
+pub(crate) fn ingest(app: &AppHandle, mut event: IncomingEvent) -> Result<(), String> {
+    if event.event_type.trim().is_empty() || event.username.trim().is_empty() {
+        return Err("Events need a type and a username".to_string());
+    }
+    if event.timestamp.is_none() {
+        event.timestamp = Some(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true));
+    }
+
+    let (push, window) = {
+        let state = app.state::<EventPipeline>();
+        let mut state = state
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to read alert coalescing settings: {}", e))?;
+        let settings = state.settings.clone();
+        (
+            state.coalescer.push(&settings, event),
+            Duration::from_millis(settings.window_ms),
+        )
+    };
+
+    match push {
+        Push::Flush(events) => emit(app, events),
+        Push::Opened(key, id) => {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(window);
+                flush(&app, &key, id);
+            });
+            Ok(())
+        }
+        Push::Held => Ok(()),
+    }
+}
+
+/// Sends a batch once its window has closed, unless it was flushed early.
+fn flush(app: &AppHandle, key: &BatchKey, id: u64) {
+    let events = app
+        .state::<EventPipeline>()
+        .0
+        .lock()
+        .ok()
+        .and_then(|mut state| state.coalescer.take(key, id));
+    if let Some(events) = events {
+        if let Err(e) = emit(app, events) {
+            eprintln!(
+                "[Tauri] Failed to send coalesced {} alert: {}",
+                key.event_type, e
+            );
+        }
+    }
+}
+
+/// Builds the alert for a batch (one event or several) and hands it to
+/// the server.
+fn emit(app: &AppHandle, events: Vec<IncomingEvent>) -> Result<(), String> {
+    let accepts = app
+        .state::<SidecarInfo>()
+        .0
+        .lock()
+        .is_ok_and(|info| info.protocol >= Some(ENQUEUE_PROTOCOL_VERSION));
+    if !accepts {
+        return Err("The server is not running or too old to accept alerts".to_string());
+    }
+
+    let settings = app
+        .state::<EventPipeline>()
+        .0
+        .lock()
+        .map(|state| state.settings.clone())
+        .map_err(|e| format!("Failed to read alert coalescing settings: {}", e))?;
+    let alert = build_alert(app, &settings, &events)?;
+    let events = events.iter().map(IncomingEvent::log_entry).collect();
+    send_to_sidecar(app, &ShellMessage::Enqueue { alert, events })
+}
+
+/// The `enqueueAlert` payload for a batch, with the config of the alert
+/// (and variation) the server would pick for it.
+fn build_alert(
+    app: &AppHandle,
+    settings: &CoalescingSettings,
+    events: &[IncomingEvent],
+) -> Result<Value, String> {
+    let first = events
+        .first()
+        .ok_or_else(|| "Cannot build an alert without events".to_string())?;
+    let aggregate = coalesce::aggregate(settings, events);
+
+    let mut data = first.condition_data();
+    if let Some(aggregate) = &aggregate {
+        data.insert("username".to_string(), json!(aggregate.username));
+        data.insert("amount".to_string(), json!(aggregate.amount));
+        data.insert("message".to_string(), Value::Null);
+    }
+
+    let conn = db::open(app)?;
+    let trace = resolution::simulate(&conn, &first.event_type, data.clone())?;
+    let mut config = Map::new();
+    if let Some(matched) = &trace.config {
+        for field in CONFIG_FIELDS {
+            if let Some(value) = matched.get(field).filter(|v| !v.is_null()) {
+                config.insert(field.to_string(), value.clone());
+            }
+        }
+    }
+
+    let mut alert = json!({
+        "type": first.event_type,
+        "username": data["username"],
+        "displayName": first.display_name.as_deref().unwrap_or(&first.username),
+        "amount": data["amount"],
+        "message": data["message"],
+        "tier": first.tier,
+        "platform": first.platform,
+        "alertConfigId": trace.alert_id,
+        "timestamp": first.timestamp,
+    });
+    if let Some(aggregate) = aggregate {
+        alert["displayName"] = json!(aggregate.display_name);
+        alert["coalescedCount"] = json!(events.len());
+        config.insert("message_template".to_string(), json!(aggregate.template));
+    }
+    alert["config"] = Value::Object(config);
+    Ok(alert)
+}
@@ -0,0 +1,63 @@
+//! Publishes the sidecar's port in a well-known file so scripts (and the
+//! CLI) can find the server without going through the UI.
+//!
+//! The file lives next to the instance lock (see `instance::runtime_dir`)
+//! and holds just the port number followed by a newline. It exists only
+//! while the server is listening.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Listener};
+
+use crate::instance;
+use crate::sidecar::status::{current_status, SidecarStatus, EVENT_STATUS};
+
+const PORT_FILE: &str = "server.port";
+
+/// Location of the port file for the app with `identifier`.
+pub(crate) fn path(identifier: &str) -> Option<PathBuf> {
+    instance::runtime_dir(identifier).map(|dir| dir.join(PORT_FILE))
+}
+
+/// Reads the port of the running server, if one is published.
+pub(crate) fn read(identifier: &str) -> Option<u16> {
+    let text = fs::read_to_string(path(identifier)?).ok()?;
+    text.trim().parse().ok()
+}
+
+/// Keeps the port file in sync with the sidecar status for the lifetime of
+/// the app.
+pub(crate) fn watch(app: &AppHandle) {
+    let Some(path) = path(&app.config().identifier) else {
+        eprintln!("[Tauri] Port file disabled: could not resolve the app data dir");
+        return;
+    };
+
+    let handle = app.clone();
+    app.listen_any(EVENT_STATUS, move |_| sync(&handle, &path));
+    remove(app);
+}
+
+/// Deletes the port file, e.g. on exit.
+pub(crate) fn remove(app: &AppHandle) {
+    if let Some(path) = path(&app.config().identifier) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn sync(app: &AppHandle, path: &Path) {
+    match current_status(app) {
+        SidecarStatus::Ready { port } | SidecarStatus::Unhealthy { port, .. } => {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            if let Err(e) = fs::write(path, format!("{}\n", port)) {
+                eprintln!("[Tauri] Failed to write {}: {}", path.display(), e);
+            }
+        }
+        _ => {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,808 @@
+//! Profile export/import, for moving an alert setup between machines.
+//!
+//! A profile is a zip archive:
+//!
+//! ```text
+//! manifest.json          format/app/schema versions, counts, media index
+//! profile.json           alerts, alert_variations, user alert_templates, settings
+//! media/sounds/<file>    every sound file the rows reference
+//! media/images/<file>    every image file the rows reference
+//! ```
+//!
+//! Rows keep their original values. On import, each `sound_path` and
+//! `image_path` listed in the media index (including inside template data)
+//! is rewritten to where its file was stored in this machine's data dir.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, SecondsFormat, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::db::{self, migrations, Row};
+use crate::media::{self, MediaKind, MAX_MEDIA_BYTES};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DATA_FILE: &str = "profile.json";
+const MEDIA_PREFIX: &str = "media";
+
+/// Identifies profile archives in `Manifest::format`.
+const FORMAT: &str = "streamforge-profile";
+
+/// Bumped whenever the archive layout changes incompatibly.
+const FORMAT_VERSION: u32 = 1;
+
+/// Upper bound for `manifest.json` and `profile.json` when importing.
+const MAX_DATA_BYTES: u64 = 64 * 1024 * 1024;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Manifest {
+    pub(crate) format: String,
+    pub(crate) format_version: u32,
+    /// StreamForge version that wrote the archive.
+    pub(crate) app_version: String,
+    /// Schema version of the exporting database.
+    pub(crate) schema_version: u32,
+    pub(crate) exported_at: String,
+    pub(crate) counts: ProfileCounts,
+    pub(crate) media: Vec<MediaEntry>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProfileCounts {
+    pub(crate) alerts: usize,
+    pub(crate) variations: usize,
+    pub(crate) templates: usize,
+    pub(crate) settings: usize,
+    pub(crate) media: usize,
+}
+
+/// A media file bundled in the archive.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MediaEntry {
+    pub(crate) kind: MediaKind,
+    /// The `sound_path`/`image_path` value the rows use for this file.
+    pub(crate) reference: String,
+    /// `media/<sounds|images>/<file>` inside the archive.
+    pub(crate) archive_path: String,
+    pub(crate) size_bytes: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileData {
+    alerts: Vec<Row>,
+    variations: Vec<Row>,
+    templates: Vec<Row>,
+    settings: Vec<Row>,
+}
+
+/// How to treat imported rows whose id (or settings key) already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ConflictStrategy {
+    /// Keep what's there.
+    #[default]
+    Skip,
+    /// Overwrite with the imported row. A replaced alert takes the
+    /// archive's variations with it.
+    Replace,
+    /// Import under a new id next to the existing row. Settings can't be
+    /// duplicated and are skipped.
+    KeepBoth,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ConflictKind {
+    Alert,
+    Variation,
+    Template,
+    Setting,
+    Media,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Resolution {
+    Skipped,
+    Replaced,
+    /// Imported under `Conflict::new_key`.
+    Duplicated,
+    /// A media file stored under `Conflict::new_key` because a different
+    /// file had its name.
+    Renamed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Conflict {
+    pub(crate) kind: ConflictKind,
+    /// Row id, settings key or media reference.
+    pub(crate) key: String,
+    /// Display name of the row, if it has one.
+    pub(crate) name: Option<String>,
+    /// `None` in previews.
+    pub(crate) resolution: Option<Resolution>,
+    pub(crate) new_key: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExportReport {
+    pub(crate) path: String,
+    pub(crate) counts: ProfileCounts,
+    /// Referenced media files that couldn't be bundled.
+    pub(crate) warnings: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProfilePreview {
+    pub(crate) path: String,
+    pub(crate) manifest: Manifest,
+    /// What would collide with existing data.
+    pub(crate) conflicts: Vec<Conflict>,
+    pub(crate) warnings: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportReport {
+    pub(crate) manifest: Manifest,
+    /// Rows written (including replaced and duplicated ones) and media
+    /// files stored.
+    pub(crate) imported: ProfileCounts,
+    pub(crate) skipped: usize,
+    pub(crate) conflicts: Vec<Conflict>,
+    pub(crate) warnings: Vec<String>,
+}
+
+/// A table in `profile.json`.
+#[derive(Clone, Copy)]
+struct Section {
+    table: &'static str,
+    key: &'static str,
+    kind: ConflictKind,
+}
+
+const ALERTS: Section = Section {
+    table: "alerts",
+    key: "id",
+    kind: ConflictKind::Alert,
+};
+const VARIATIONS: Section = Section {
+    table: "alert_variations",
+    key: "id",
+    kind: ConflictKind::Variation,
+};
+const TEMPLATES: Section = Section {
+    table: "alert_templates",
+    key: "id",
+    kind: ConflictKind::Template,
+};
+const SETTINGS: Section = Section {
+    table: "settings",
+    key: "key",
+    kind: ConflictKind::Setting,
+};
+
+impl ProfileData {
+    fn sections(&self) -> [(Section, &Vec<Row>); 4] {
+        [
+            (ALERTS, &self.alerts),
+            (VARIATIONS, &self.variations),
+            (TEMPLATES, &self.templates),
+            (SETTINGS, &self.settings),
+        ]
+    }
+
+    /// Calls `visit` with every media reference in the rows, letting it
+    /// rewrite them. Template references live inside `template_data` JSON.
+    fn visit_media(&mut self, visit: &mut dyn FnMut(MediaKind, &mut String)) {
+        for row in self.alerts.iter_mut().chain(self.variations.iter_mut()) {
+            visit_fields(row, visit);
+        }
+        for row in &mut self.templates {
+            let Some(Value::String(text)) = row.get_mut("template_data") else {
+                continue;
+            };
+            let Ok(Value::Object(mut data)) = serde_json::from_str::<Value>(text) else {
+                continue;
+            };
+            if visit_fields(&mut data, visit) {
+                *text = Value::Object(data).to_string();
+            }
+        }
+    }
+}
+
+/// Returns whether any reference was changed.
+fn visit_fields(row: &mut Row, visit: &mut dyn FnMut(MediaKind, &mut String)) -> bool {
+    let mut changed = false;
+    for kind in MediaKind::ALL {
+        if let Some(Value::String(reference)) = row.get_mut(kind.column()) {
+            if reference.is_empty() {
+                continue;
+            }
+            let before = reference.clone();
+            visit(kind, reference);
+            changed |= *reference != before;
+        }
+    }
+    changed
+}
+
+fn row_key(row: &Row, section: Section) -> Option<&str> {
+    row.get(section.key).and_then(Value::as_str)
+}
+
+fn row_name(row: &Row) -> Option<String> {
+    row.get("name").and_then(Value::as_str).map(str::to_string)
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// Writes the current profile to `dest`.
+pub(crate) fn export(app: &AppHandle, dest: &Path) -> Result<ExportReport, String> {
+    let conn = db::open(app)?;
+    let mut data = ProfileData {
+        alerts: db::select_rows(&conn, "SELECT * FROM alerts ORDER BY created_at", [])?,
+        variations: db::select_rows(
+            &conn,
+            "SELECT * FROM alert_variations ORDER BY parent_alert_id, priority DESC",
+            [],
+        )?,
+        templates: db::select_rows(
+            &conn,
+            "SELECT * FROM alert_templates WHERE is_builtin = 0 ORDER BY created_at",
+            [],
+        )?,
+        settings: db::select_rows(&conn, "SELECT * FROM settings ORDER BY key", [])?,
+    };
+
+    // Index every referenced local file under a unique archive name
+    let mut warnings = Vec::new();
+    let mut indexed: HashSet<(MediaKind, String)> = HashSet::new();
+    let mut archive_paths: HashSet<String> = HashSet::new();
+    let mut files: Vec<(MediaEntry, PathBuf)> = Vec::new();
+    data.visit_media(&mut |kind, reference| {
+        if !indexed.insert((kind, reference.clone())) {
+            return;
+        }
+        let Some(path) = media::resolve(app, kind, reference) else {
+            if reference.starts_with('/') || Path::new(reference.as_str()).is_absolute() {
+                warnings.push(format!("Missing {} file {}", kind.subdir(), reference));
+            }
+            return;
+        };
+        let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if size_bytes > MAX_MEDIA_BYTES {
+            warnings.push(format!("Skipped {} (larger than 100MB)", reference));
+            return;
+        }
+
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".to_string());
+        let mut archive_path = format!("{}/{}/{}", MEDIA_PREFIX, kind.subdir(), file_name);
+        let mut n = 1;
+        while archive_paths.contains(&archive_path) {
+            n += 1;
+            archive_path = format!("{}/{}/{}-{}", MEDIA_PREFIX, kind.subdir(), n, file_name);
+        }
+        archive_paths.insert(archive_path.clone());
+
+        files.push((
+            MediaEntry {
+                kind,
+                reference: reference.clone(),
+                archive_path,
+                size_bytes,
+            },
+            path,
+        ));
+    });
+
+    let counts = ProfileCounts {
+        alerts: data.alerts.len(),
+        variations: data.variations.len(),
+        templates: data.templates.len(),
+        settings: data.settings.len(),
+        media: files.len(),
+    };
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        format_version: FORMAT_VERSION,
+        app_version: app.package_info().version.to_string(),
+        schema_version: migrations::version(&conn)?,
+        exported_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        counts,
+        media: files.iter().map(|(entry, _)| entry.clone()).collect(),
+    };
+
+    write_archive(dest, &manifest, &data, &files).inspect_err(|_| {
+        let _ = fs::remove_file(dest);
+    })?;
+
+    println!("[Tauri] Profile exported to {}", dest.display());
+    Ok(ExportReport {
+        path: dest.display().to_string(),
+        counts,
+        warnings,
+    })
+}
+
+fn write_archive(
+    dest: &Path,
+    manifest: &Manifest,
+    data: &ProfileData,
+    files: &[(MediaEntry, PathBuf)],
+) -> Result<(), String> {
+    let file =
+        File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let zip_error = |e: zip::result::ZipError| format!("Failed to write the archive: {}", e);
+    let io_error = |e: std::io::Error| format!("Failed to write the archive: {}", e);
+
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Audio and images are already compressed
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    for (name, value) in [
+        (MANIFEST_FILE, serde_json::to_vec_pretty(manifest)),
+        (DATA_FILE, serde_json::to_vec_pretty(data)),
+    ] {
+        let bytes = value.map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+        zip.start_file(name, deflated).map_err(zip_error)?;
+        zip.write_all(&bytes).map_err(io_error)?;
+    }
+
+    for (entry, path) in files {
+        let mut source =
+            File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        zip.start_file(entry.archive_path.as_str(), stored)
+            .map_err(zip_error)?;
+        std::io::copy(&mut source, &mut zip).map_err(io_error)?;
+    }
+
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Reading & Validation
+// ---------------------------------------------------------------------------
+
+struct ProfileArchive {
+    zip: ZipArchive<File>,
+    manifest: Manifest,
+    data: ProfileData,
+    warnings: Vec<String>,
+}
+
+fn read_entry(zip: &mut ZipArchive<File>, name: &str, limit: u64) -> Result<Vec<u8>, String> {
+    let entry = zip
+        .by_name(name)
+        .map_err(|_| format!("The archive has no {}", name))?;
+    if entry.size() > limit {
+        return Err(format!("{} in the archive is too large", name));
+    }
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .take(limit)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {} from the archive: {}", name, e))?;
+    Ok(bytes)
+}
+
+/// Opens `path` and checks that it is a profile this build can import.
+fn read_archive(path: &Path) -> Result<ProfileArchive, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("{} is not a zip archive: {}", path.display(), e))?;
+
+    let manifest: Manifest =
+        serde_json::from_slice(&read_entry(&mut zip, MANIFEST_FILE, MAX_DATA_BYTES)?)
+            .map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))?;
+    if manifest.format != FORMAT {
+        return Err(format!("{} is not a StreamForge profile", path.display()));
+    }
+    if manifest.format_version == 0 || manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "The profile uses format version {}, this StreamForge reads up to {}",
+            manifest.format_version, FORMAT_VERSION
+        ));
+    }
+
+    let data: ProfileData =
+        serde_json::from_slice(&read_entry(&mut zip, DATA_FILE, MAX_DATA_BYTES)?)
+            .map_err(|e| format!("Invalid {}: {}", DATA_FILE, e))?;
+    let actual = ProfileCounts {
+        alerts: data.alerts.len(),
+        variations: data.variations.len(),
+        templates: data.templates.len(),
+        settings: data.settings.len(),
+        media: manifest.media.len(),
+    };
+    if actual != manifest.counts {
+        return Err("The profile's contents don't match its manifest".to_string());
+    }
+    for (section, rows) in data.sections() {
+        if rows.iter().any(|row| row_key(row, section).is_none()) {
+            return Err(format!(
+                "The profile has {} rows without a {}",
+                section.table, section.key
+            ));
+        }
+    }
+
+    for entry in &manifest.media {
+        let expected_dir = format!("{}/{}/", MEDIA_PREFIX, entry.kind.subdir());
+        let valid_name = entry
+            .archive_path
+            .strip_prefix(&expected_dir)
+            .is_some_and(|name| !name.is_empty() && !name.contains(['/', '\\']));
+        if !valid_name {
+            return Err(format!("Invalid media path {:?}", entry.archive_path));
+        }
+        let size = zip
+            .by_name(&entry.archive_path)
+            .map_err(|_| format!("The archive is missing {}", entry.archive_path))?
+            .size();
+        if size > MAX_MEDIA_BYTES {
+            return Err(format!("{} is larger than 100MB", entry.archive_path));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if manifest.schema_version > migrations::latest_version() {
+        warnings.push(format!(
+            "The profile comes from a newer StreamForge ({}); settings this version doesn't know are dropped",
+            manifest.app_version
+        ));
+    }
+
+    Ok(ProfileArchive {
+        zip,
+        manifest,
+        data,
+        warnings,
+    })
+}
+
+fn existing_value(conn: &Connection, section: Section, key: &str) -> Result<Option<Row>, String> {
+    let sql = format!("SELECT * FROM {} WHERE {} = ?1", section.table, section.key);
+    Ok(db::select_rows(conn, &sql, [key])?.into_iter().next())
+}
+
+/// Whether importing `row` would overwrite something. Settings only
+/// conflict if the value differs.
+fn conflicts_with(conn: &Connection, section: Section, row: &Row) -> Result<bool, String> {
+    let key = row_key(row, section).unwrap_or_default();
+    Ok(match existing_value(conn, section, key)? {
+        None => false,
+        Some(existing) if section.kind == ConflictKind::Setting => {
+            existing.get("value") != row.get("value")
+        }
+        Some(_) => true,
+    })
+}
+
+fn conflict(section: Section, row: &Row) -> Conflict {
+    Conflict {
+        kind: section.kind,
+        key: row_key(row, section).unwrap_or_default().to_string(),
+        name: row_name(row),
+        resolution: None,
+        new_key: None,
+    }
+}
+
+/// Validates the profile at `path` and lists what it would collide with.
+pub(crate) fn inspect(app: &AppHandle, path: &Path) -> Result<ProfilePreview, String> {
+    let mut archive = read_archive(path)?;
+    let conn = db::open(app)?;
+
+    let mut conflicts = Vec::new();
+    for (section, rows) in archive.data.sections() {
+        for row in rows {
+            if conflicts_with(&conn, section, row)? {
+                conflicts.push(conflict(section, row));
+            }
+        }
+    }
+    for entry in &archive.manifest.media {
+        let file_name = entry.archive_path.rsplit('/').next().unwrap_or_default();
+        let reference = format!("/{}/{}", entry.kind.subdir(), file_name);
+        let Some(existing) = media::resolve(app, entry.kind, &reference) else {
+            continue;
+        };
+        let bytes = read_entry(&mut archive.zip, &entry.archive_path, MAX_MEDIA_BYTES)?;
+        if fs::read(&existing).is_ok_and(|current| current != bytes) {
+            conflicts.push(Conflict {
+                kind: ConflictKind::Media,
+                key: entry.reference.clone(),
+                name: Some(file_name.to_string()),
+                resolution: None,
+                new_key: None,
+            });
+        }
+    }
+
+    Ok(ProfilePreview {
+        path: path.display().to_string(),
+        manifest: archive.manifest,
+        conflicts,
+        warnings: archive.warnings,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// Imports the profile at `path`, resolving id collisions with `strategy`.
+pub(crate) fn import(
+    app: &AppHandle,
+    path: &Path,
+    strategy: ConflictStrategy,
+) -> Result<ImportReport, String> {
+    let ProfileArchive {
+        mut zip,
+        manifest,
+        mut data,
+        mut warnings,
+    } = read_archive(path)?;
+
+    let mut report = ImportReport {
+        manifest: manifest.clone(),
+        imported: ProfileCounts::default(),
+        skipped: 0,
+        conflicts: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    // Store the media first so the rows can point at the new locations
+    let mut created: Vec<PathBuf> = Vec::new();
+    let mut relocated: HashMap<(MediaKind, String), String> = HashMap::new();
+    let stored = manifest.media.iter().try_for_each(|entry| {
+        let bytes = read_entry(&mut zip, &entry.archive_path, MAX_MEDIA_BYTES)?;
+        let file_name = entry.archive_path.rsplit('/').next().unwrap_or_default();
+        let stored = media::store(app, entry.kind, file_name, &bytes)?;
+        if stored.created {
+            created.push(stored.path.clone());
+            report.imported.media += 1;
+        }
+        if stored.renamed_from.is_some() {
+            report.conflicts.push(Conflict {
+                kind: ConflictKind::Media,
+                key: entry.reference.clone(),
+                name: Some(file_name.to_string()),
+                resolution: Some(Resolution::Renamed),
+                new_key: Some(stored.url.clone()),
+            });
+        }
+        relocated.insert((entry.kind, entry.reference.clone()), stored.url);
+        Ok::<(), String>(())
+    });
+
+    let result = stored.and_then(|_| {
+        data.visit_media(&mut |kind, reference| {
+            if let Some(url) = relocated.get(&(kind, reference.clone())) {
+                *reference = url.clone();
+            }
+        });
+
+        let mut conn = db::open(app)?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start the import: {}", e))?;
+        import_rows(&tx, &data, strategy, &mut report)?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit the import: {}", e))
+    });
+
+    if let Err(e) = result {
+        for path in created {
+            let _ = fs::remove_file(path);
+        }
+        return Err(e);
+    }
+
+    warnings.append(&mut report.warnings);
+    report.warnings = warnings;
+    println!(
+        "[Tauri] Profile imported from {} ({} alerts, {} templates, {} media files)",
+        path.display(),
+        report.imported.alerts,
+        report.imported.templates,
+        report.imported.media
+    );
+    Ok(report)
+}
+
+/// Writes the rows of every section, applying `strategy` to collisions.
+fn import_rows(
+    conn: &Connection,
+    data: &ProfileData,
+    strategy: ConflictStrategy,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    // Alerts whose variations follow them: skipped ones keep the existing
+    // variations, duplicated ones get their variations re-parented
+    let mut skipped_alerts: HashSet<String> = HashSet::new();
+    let mut new_alert_ids: HashMap<String, String> = HashMap::new();
+    let mut dropped_columns: BTreeSet<String> = BTreeSet::new();
+
+    for (section, rows) in data.sections() {
+        let table_columns = db::table_columns(conn, section.table)?;
+        if table_columns.is_empty() {
+            return Err(format!("The database has no {} table", section.table));
+        }
+
+        for row in rows {
+            let mut row = row.clone();
+            let key = row_key(&row, section).unwrap_or_default().to_string();
+
+            if section.kind == ConflictKind::Variation {
+                let parent = row
+                    .get("parent_alert_id")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                if skipped_alerts.contains(&parent) {
+                    report.skipped += 1;
+                    continue;
+                }
+                if let Some(new_parent) = new_alert_ids.get(&parent) {
+                    row.insert(
+                        "parent_alert_id".to_string(),
+                        Value::from(new_parent.clone()),
+                    );
+                } else if existing_value(conn, ALERTS, &parent)?.is_none() {
+                    report
+                        .warnings
+                        .push(format!("Skipped variation {} of a missing alert", key));
+                    report.skipped += 1;
+                    continue;
+                }
+            }
+            if section.kind == ConflictKind::Template {
+                row.insert("is_builtin".to_string(), Value::from(0));
+            }
+
+            let mut replaced = false;
+            if conflicts_with(conn, section, &row)? {
+                let mut entry = conflict(section, &row);
+                let resolution = match strategy {
+                    ConflictStrategy::Replace => Resolution::Replaced,
+                    ConflictStrategy::KeepBoth if section.kind != ConflictKind::Setting => {
+                        Resolution::Duplicated
+                    }
+                    _ => Resolution::Skipped,
+                };
+                entry.resolution = Some(resolution);
+
+                match resolution {
+                    Resolution::Skipped => {
+                        if section.kind == ConflictKind::Alert {
+                            skipped_alerts.insert(key);
+                        }
+                        report.conflicts.push(entry);
+                        report.skipped += 1;
+                        continue;
+                    }
+                    Resolution::Duplicated => {
+                        let new_key = uuid::Uuid::new_v4().to_string();
+                        row.insert(section.key.to_string(), Value::from(new_key.clone()));
+                        if let Some(name) = row_name(&row) {
+                            row.insert(
+                                "name".to_string(),
+                                Value::from(format!("{} (imported)", name)),
+                            );
+                        }
+                        if section.kind == ConflictKind::Alert {
+                            new_alert_ids.insert(key.clone(), new_key.clone());
+                        }
+                        entry.new_key = Some(new_key);
+                    }
+                    Resolution::Replaced => replaced = true,
+                    Resolution::Renamed => {}
+                }
+                report.conflicts.push(entry);
+            }
+
+            let columns: Vec<&str> = table_columns
+                .iter()
+                .map(String::as_str)
+                .filter(|column| row.contains_key(*column))
+                .collect();
+            dropped_columns.extend(
+                row.keys()
+                    .filter(|column| !table_columns.contains(column))
+                    .map(|column| format!("{}.{}", section.table, column)),
+            );
+            db::upsert(conn, section.table, section.key, &columns, &row)?;
+
+            // A replaced alert brings its own variations
+            if replaced && section.kind == ConflictKind::Alert {
+                conn.execute(
+                    "DELETE FROM alert_variations WHERE parent_alert_id = ?1",
+                    [&key],
+                )
+                .map_err(|e| format!("Failed to replace the variations of {}: {}", key, e))?;
+            }
+
+            match section.kind {
+                ConflictKind::Alert => report.imported.alerts += 1,
+                ConflictKind::Variation => report.imported.variations += 1,
+                ConflictKind::Template => report.imported.templates += 1,
+                ConflictKind::Setting => report.imported.settings += 1,
+                ConflictKind::Media => {}
+            }
+        }
+    }
+
+    if !dropped_columns.is_empty() {
+        report.warnings.push(format!(
+            "Ignored fields this version doesn't support: {}",
+            dropped_columns.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// File Dialogs
+// ---------------------------------------------------------------------------
+
+/// Asks where to save an export. `None` if cancelled.
+pub(crate) fn pick_destination(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    app.dialog()
+        .file()
+        .set_title("Export StreamForge profile")
+        .add_filter("StreamForge profile", &["zip"])
+        .set_file_name(format!(
+            "streamforge-profile-{}.zip",
+            Local::now().format("%Y%m%d")
+        ))
+        .blocking_save_file()
+        .map(|file| {
+            file.into_path()
+                .map_err(|e| format!("Unsupported file selection: {}", e))
+        })
+        .transpose()
+}
+
+/// Asks for a profile to import. `None` if cancelled.
+pub(crate) fn pick_source(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    app.dialog()
+        .file()
+        .set_title("Import StreamForge profile")
+        .add_filter("StreamForge profile", &["zip"])
+        .blocking_pick_file()
+        .map(|file| {
+            file.into_path()
+                .map_err(|e| format!("Unsupported file selection: {}", e))
+        })
+        .transpose()
+}
@@ -0,0 +1,360 @@
+//! Alert resolution simulator.
+//!
+//! Answers "which alert will fire for this event?" by replaying
+//! `findMatchingAlert` from `server/alerts/database.js` against the live
+//! database, read-only, and recording every decision on the way: which
+//! alerts of the event's type were looked at, which were skipped on
+//! `min_amount`, how each variation's condition evaluated (in priority
+//! order) and the merged config `mergeVariation` would produce. Nothing is
+//! queued.
+//!
+//! The comparisons deliberately follow the server's JavaScript semantics
+//! (`Number(...)`, `String(...)`), so a missing tier compares as
+//! `"undefined"` exactly as it does there.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::condition::Condition;
+use crate::db::{self, Row};
+
+/// Columns `mergeVariation` copies from a variation when they are not null.
+const OVERRIDE_FIELDS: [&str; 7] = [
+    "message_template",
+    "sound_path",
+    "sound_volume",
+    "image_path",
+    "animation_in",
+    "animation_out",
+    "custom_css",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AlertOutcome {
+    /// Not enabled, so never considered.
+    Disabled,
+    /// The event's amount is below the alert's `min_amount`.
+    BelowMinAmount,
+    /// The first alert that passed; its config (or a variation's) is used.
+    Selected,
+    /// An earlier alert was selected first.
+    NotReached,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum VariationOutcome {
+    Disabled,
+    /// The highest-priority matching variation; its overrides are applied.
+    Selected,
+    /// Matches, but a higher-priority variation was selected first.
+    Shadowed,
+    NoMatch,
+    /// The condition could not be parsed (or has an unknown type) and never
+    /// matches.
+    Invalid,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VariationTrace {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) priority: i64,
+    pub(crate) condition_type: String,
+    pub(crate) condition_value: String,
+    pub(crate) outcome: VariationOutcome,
+    pub(crate) reason: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlertTrace {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) min_amount: Option<f64>,
+    pub(crate) outcome: AlertOutcome,
+    pub(crate) reason: String,
+    /// Evaluated variations, highest priority first; empty unless the
+    /// alert was selected.
+    pub(crate) variations: Vec<VariationTrace>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolutionTrace {
+    pub(crate) event_type: String,
+    pub(crate) event_data: Map<String, Value>,
+    /// Enabled alerts in the order the server tries them, then disabled
+    /// ones.
+    pub(crate) alerts: Vec<AlertTrace>,
+    pub(crate) alert_id: Option<String>,
+    pub(crate) variation_id: Option<String>,
+    /// What `findMatchingAlert` returns: the alert row with the selected
+    /// variation's overrides and `_variation_id`/`_variation_name`, or
+    /// `None` when no alert would fire.
+    pub(crate) config: Option<Row>,
+}
+
+// ---------------------------------------------------------------------------
+// JavaScript Semantics
+// ---------------------------------------------------------------------------
+
+/// `Number(value)`.
+fn js_number(value: &Value) -> f64 {
+    match value {
+        Value::Null => 0.0,
+        Value::Bool(b) => f64::from(u8::from(*b)),
+        Value::Number(n) => n.as_f64().unwrap_or(f64::NAN),
+        Value::String(s) => {
+            let text = s.trim();
+            if text.is_empty() {
+                return 0.0;
+            }
+            if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                return u64::from_str_radix(hex, 16).map_or(f64::NAN, |n| n as f64);
+            }
+            match text.trim_start_matches(['+', '-']) {
+                "Infinity" if text.starts_with('-') => f64::NEG_INFINITY,
+                "Infinity" => f64::INFINITY,
+                // Rust also accepts "inf" and "nan", JavaScript doesn't
+                digits if digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') => {
+                    text.parse().unwrap_or(f64::NAN)
+                }
+                _ => f64::NAN,
+            }
+        }
+        Value::Array(_) | Value::Object(_) => f64::NAN,
+    }
+}
+
+/// `String(value)`, with `None` standing for `undefined`.
+fn js_string(value: Option<&Value>) -> String {
+    match value {
+        None => "undefined".to_string(),
+        Some(Value::Null) => "null".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 1e21 => format!("{:.0}", f),
+            _ => n.to_string(),
+        },
+        Some(other) => other.to_string(),
+    }
+}
+
+/// A column as text; empty if null.
+fn text(row: &Row, column: &str) -> String {
+    match row.get(column) {
+        None | Some(Value::Null) => String::new(),
+        value => js_string(value),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Simulation
+// ---------------------------------------------------------------------------
+
+/// Mirrors `doesVariationMatch`; `Err` explains why a condition can never
+/// match.
+fn variation_matches(variation: &Row, event: &Map<String, Value>) -> Result<bool, String> {
+    let condition_type = text(variation, "condition_type");
+    let value = variation.get("condition_value").unwrap_or(&Value::Null);
+    let value_text = js_string(Some(value));
+    match condition_type.as_str() {
+        "expression" => Condition::parse(&value_text)
+            .map(|condition| condition.evaluate(event))
+            .map_err(|e| e.message),
+        "tier" => Ok(js_string(event.get("tier")) == value_text),
+        "amount" => Ok(event
+            .get("amount")
+            .is_some_and(|amount| js_number(amount) >= js_number(value))),
+        "custom" => Ok(js_string(event.get("custom_value")) == value_text),
+        other => Err(format!("Unknown condition type \"{}\"", other)),
+    }
+}
+
+/// Mirrors `mergeVariation`.
+fn merge_variation(alert: &Row, variation: &Row) -> Row {
+    let mut merged = alert.clone();
+    for field in OVERRIDE_FIELDS {
+        if let Some(value) = variation.get(field).filter(|v| !v.is_null()) {
+            merged.insert(field.to_string(), value.clone());
+        }
+    }
+    merged.insert(
+        "_variation_id".to_string(),
+        variation.get("id").cloned().unwrap_or(Value::Null),
+    );
+    merged.insert(
+        "_variation_name".to_string(),
+        variation.get("name").cloned().unwrap_or(Value::Null),
+    );
+    merged
+}
+
+fn trace_variations(
+    conn: &Connection,
+    alert_id: &str,
+    event: &Map<String, Value>,
+) -> Result<(Vec<VariationTrace>, Option<Row>), String> {
+    // Same query as findMatchingAlert, so ties in priority resolve alike
+    let enabled = db::select_rows(
+        conn,
+        "SELECT * FROM alert_variations WHERE parent_alert_id = ?1 AND enabled = 1 ORDER BY priority DESC",
+        [alert_id],
+    )?;
+    let disabled = db::select_rows(
+        conn,
+        "SELECT * FROM alert_variations WHERE parent_alert_id = ?1 AND NOT (enabled = 1) ORDER BY priority DESC",
+        [alert_id],
+    )?;
+
+    let mut traces = Vec::new();
+    let mut selected: Option<Row> = None;
+    for variation in &enabled {
+        let (outcome, reason) = match variation_matches(variation, event) {
+            Ok(true) if selected.is_none() => {
+                selected = Some(variation.clone());
+                (VariationOutcome::Selected, "Condition matches".to_string())
+            }
+            Ok(true) => (
+                VariationOutcome::Shadowed,
+                "Condition matches, but a higher-priority variation was selected".to_string(),
+            ),
+            Ok(false) => (
+                VariationOutcome::NoMatch,
+                "Condition does not match".to_string(),
+            ),
+            Err(e) => (VariationOutcome::Invalid, format!("Never matches: {}", e)),
+        };
+        traces.push(variation_trace(variation, outcome, reason));
+    }
+    for variation in &disabled {
+        traces.push(variation_trace(
+            variation,
+            VariationOutcome::Disabled,
+            "Variation is disabled".to_string(),
+        ));
+    }
+    Ok((traces, selected))
+}
+
+fn variation_trace(row: &Row, outcome: VariationOutcome, reason: String) -> VariationTrace {
+    VariationTrace {
+        id: text(row, "id"),
+        name: text(row, "name"),
+        priority: row.get("priority").and_then(Value::as_i64).unwrap_or(0),
+        condition_type: text(row, "condition_type"),
+        condition_value: text(row, "condition_value"),
+        outcome,
+        reason,
+    }
+}
+
+fn alert_trace(row: &Row, outcome: AlertOutcome, reason: String) -> AlertTrace {
+    AlertTrace {
+        id: text(row, "id"),
+        name: text(row, "name"),
+        min_amount: row.get("min_amount").and_then(Value::as_f64),
+        outcome,
+        reason,
+        variations: Vec::new(),
+    }
+}
+
+/// Resolves `event_type`/`event_data` the way `findMatchingAlert` would,
+/// with `event_data` used as given (as `POST /api/alerts/match` does).
+pub(crate) fn simulate(
+    conn: &Connection,
+    event_type: &str,
+    event_data: Map<String, Value>,
+) -> Result<ResolutionTrace, String> {
+    let enabled = db::select_rows(
+        conn,
+        "SELECT * FROM alerts WHERE type = ?1 AND enabled = 1 ORDER BY created_at ASC",
+        [event_type],
+    )?;
+    let disabled = db::select_rows(
+        conn,
+        "SELECT * FROM alerts WHERE type = ?1 AND NOT (enabled = 1) ORDER BY created_at ASC",
+        [event_type],
+    )?;
+
+    let mut trace = ResolutionTrace {
+        event_type: event_type.to_string(),
+        event_data,
+        alerts: Vec::new(),
+        alert_id: None,
+        variation_id: None,
+        config: None,
+    };
+
+    for alert in &enabled {
+        if trace.config.is_some() {
+            trace.alerts.push(alert_trace(
+                alert,
+                AlertOutcome::NotReached,
+                "An earlier alert was selected".to_string(),
+            ));
+            continue;
+        }
+
+        let min_amount = alert.get("min_amount").filter(|v| !v.is_null());
+        if let (Some(min_amount), Some(amount)) = (min_amount, trace.event_data.get("amount")) {
+            let amount = js_number(amount);
+            if amount < js_number(min_amount) {
+                trace.alerts.push(alert_trace(
+                    alert,
+                    AlertOutcome::BelowMinAmount,
+                    format!(
+                        "Amount {} is below the minimum of {}",
+                        js_string(Some(&Value::from(amount))),
+                        js_string(Some(min_amount))
+                    ),
+                ));
+                continue;
+            }
+        }
+
+        let id = text(alert, "id");
+        let (variations, selected) = trace_variations(conn, &id, &trace.event_data)?;
+        let (config, reason) = match &selected {
+            Some(variation) => (
+                merge_variation(alert, variation),
+                format!("Selected with variation \"{}\"", text(variation, "name")),
+            ),
+            None => (
+                alert.clone(),
+                "Selected; no variation matched, so its own config is used".to_string(),
+            ),
+        };
+        let mut alert_trace = alert_trace(alert, AlertOutcome::Selected, reason);
+        alert_trace.variations = variations;
+        trace.alerts.push(alert_trace);
+        trace.alert_id = Some(id);
+        trace.variation_id = selected.map(|variation| text(&variation, "id"));
+        trace.config = Some(config);
+    }
+
+    for alert in &disabled {
+        trace.alerts.push(alert_trace(
+            alert,
+            AlertOutcome::Disabled,
+            "Alert is disabled".to_string(),
+        ));
+    }
+    Ok(trace)
+}
+
+/// Opens the database and runs `simulate`.
+pub(crate) fn run(
+    app: &AppHandle,
+    event_type: &str,
+    event_data: Map<String, Value>,
+) -> Result<ResolutionTrace, String> {
+    let conn = db::open(app)?;
+    simulate(&conn, event_type, event_data)
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// File in the app config dir holding the persisted `LaunchConfig`.
+const CONFIG_FILE: &str = "sidecar.json";
+
+/// Environment variables the server reads its launch settings from
+/// (see `server/config.js` and `server/database.js`).
+const ENV_PORT: &str = "STREAMFORGE_PORT";
+const ENV_PORT_MIN: &str = "STREAMFORGE_PORT_MIN";
+const ENV_PORT_MAX: &str = "STREAMFORGE_PORT_MAX";
+const ENV_HOST: &str = "STREAMFORGE_HOST";
+pub(crate) const ENV_DATA_DIR: &str = "STREAMFORGE_DATA_DIR";
+
+/// Inclusive port range the server scans when the preferred port is busy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PortRange {
+    pub(crate) min: u16,
+    pub(crate) max: u16,
+}
+
+/// How the sidecar is launched. Defaults mirror `server/config.js`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct LaunchConfig {
+    /// Port tried first, within `port_range`; falls back to scanning the
+    /// rest of the range.
+    pub(crate) preferred_port: u16,
+    pub(crate) port_range: PortRange,
+    /// Address the HTTP server binds to. `127.0.0.1` keeps it local-only.
+    pub(crate) bind_address: String,
+    /// Replaces the default app data dir (database + uploaded media).
+    pub(crate) data_dir: Option<String>,
+    /// Extra environment variables passed to the sidecar as-is.
+    pub(crate) env: BTreeMap<String, String>,
+}
+
+impl Default for LaunchConfig {
+    fn default() -> Self {
+        Self {
+            preferred_port: 39283,
+            port_range: PortRange {
+                min: 39283,
+                max: 39383,
+            },
+            bind_address: "127.0.0.1".to_string(),
+            data_dir: None,
+            env: BTreeMap::new(),
+        }
+    }
+}
+
+impl LaunchConfig {
+    /// Rejects configs the server couldn't start with.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.preferred_port == 0 {
+            return Err("Preferred port must be between 1 and 65535".to_string());
+        }
+        if self.port_range.min == 0 || self.port_range.min > self.port_range.max {
+            return Err(format!(
+                "Invalid port range {}-{}",
+                self.port_range.min, self.port_range.max
+            ));
+        }
+        if !(self.port_range.min..=self.port_range.max).contains(&self.preferred_port) {
+            return Err(format!(
+                "Preferred port {} must lie within the port range {}-{}",
+                self.preferred_port, self.port_range.min, self.port_range.max
+            ));
+        }
+        self.bind_address
+            .parse::<IpAddr>()
+            .map_err(|_| format!("Bind address {:?} is not an IP address", self.bind_address))?;
+        if let Some(dir) = &self.data_dir {
+            if !Path::new(dir).is_absolute() {
+                return Err(format!("Data directory {:?} must be an absolute path", dir));
+            }
+        }
+        for key in self.env.keys() {
+            if key.is_empty() || key.contains('=') || key.contains('\0') {
+                return Err(format!("Invalid environment variable name {:?}", key));
+            }
+        }
+        Ok(())
+    }
+
+    /// Environment for the sidecar process. Launch settings win over
+    /// same-named entries in `env`.
+    pub(crate) fn to_env(&self) -> BTreeMap<String, String> {
+        let mut vars = self.env.clone();
+        vars.insert(ENV_PORT.to_string(), self.preferred_port.to_string());
+        vars.insert(ENV_PORT_MIN.to_string(), self.port_range.min.to_string());
+        vars.insert(ENV_PORT_MAX.to_string(), self.port_range.max.to_string());
+        vars.insert(ENV_HOST.to_string(), self.bind_address.clone());
+        if let Some(dir) = &self.data_dir {
+            vars.insert(ENV_DATA_DIR.to_string(), dir.clone());
+        }
+        vars
+    }
+
+    /// Address the shell should use to reach the server. A wildcard bind
+    /// is still reachable on loopback.
+    pub(crate) fn probe_address(&self) -> IpAddr {
+        match self.bind_address.parse::<IpAddr>() {
+            Ok(ip) if !ip.is_unspecified() => ip,
+            _ => IpAddr::from([127, 0, 0, 1]),
+        }
+    }
+}
+
+/// HTTP client for requests to the server. `timeout` bounds each request as
+/// a whole. Proxy settings from the environment are ignored because the
+/// server is local, and error statuses are returned as responses so callers
+/// can read the JSON error body.
+pub(crate) fn server_agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .http_status_as_error(false)
+        .proxy(None)
+        .build()
+        .into()
+}
+
+/// URL of `path` on the server at `ip:port`.
+pub(crate) fn server_url(ip: IpAddr, port: u16, path: &str) -> String {
+    format!("http://{}{}", SocketAddr::new(ip, port), path)
+}
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+/// The launch config used for the next sidecar spawn.
+pub(crate) struct SidecarLaunchConfig(pub(crate) Mutex<LaunchConfig>);
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Loads the persisted config, falling back to defaults if there is none or
+/// it can't be used.
+pub(crate) fn load(app: &AppHandle) -> LaunchConfig {
+    match config_path(app) {
+        Ok(path) => load_file(&path),
+        Err(_) => LaunchConfig::default(),
+    }
+}
+
+/// Like `load`, for code running without a Tauri app (the CLI). Resolves the
+/// same file Tauri's `app_config_dir` points to.
+pub(crate) fn load_for_identifier(identifier: &str) -> LaunchConfig {
+    match dirs::config_dir() {
+        Some(dir) => load_file(&dir.join(identifier).join(CONFIG_FILE)),
+        None => LaunchConfig::default(),
+    }
+}
+
+fn load_file(path: &Path) -> LaunchConfig {
+    let Ok(text) = fs::read_to_string(path) else {
+        return LaunchConfig::default();
+    };
+
+    match serde_json::from_str::<LaunchConfig>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(|config| config.validate().map(|_| config))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!(
+                "[Tauri] Ignoring invalid sidecar config {}: {}",
+                path.display(),
+                e
+            );
+            LaunchConfig::default()
+        }
+    }
+}
+
+/// Writes the config to the app config dir.
+pub(crate) fn save(app: &AppHandle, config: &LaunchConfig) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize sidecar config: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Returns a copy of the current config.
+pub(crate) fn current(app: &AppHandle) -> LaunchConfig {
+    app.state::<SidecarLaunchConfig>()
+        .0
+        .lock()
+        .map(|c| c.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,228 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Emitted with each new `LogEntry` as it is captured.
+pub(crate) const EVENT_LOG: &str = "sidecar://log";
+
+/// Number of lines kept in memory for `get_sidecar_logs`.
+const BUFFER_CAPACITY: usize = 2000;
+
+/// Size at which `sidecar.log` is rotated.
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept next to the active one (`sidecar.1.log` ... `sidecar.N.log`).
+const MAX_ROTATED_FILES: usize = 4;
+
+const LOG_FILE_STEM: &str = "sidecar";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogStream {
+    Stdout,
+    Stderr,
+    /// Lines generated by the shell itself about the sidecar.
+    Shell,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogEntry {
+    /// Monotonic sequence number, usable as a cursor for `since`.
+    seq: u64,
+    /// Milliseconds since the Unix epoch.
+    timestamp: u64,
+    level: LogLevel,
+    stream: LogStream,
+    message: String,
+}
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+/// Captured sidecar output, shared between the reader thread and commands.
+pub(crate) struct SidecarLogs(pub(crate) Mutex<LogBuffer>);
+
+pub(crate) struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    next_seq: u64,
+    file: Option<RotatingFile>,
+}
+
+impl LogBuffer {
+    /// Creates an empty buffer. Lines are also persisted under `log_dir`
+    /// when it is given and writable.
+    pub(crate) fn new(log_dir: Option<PathBuf>) -> Self {
+        let file = log_dir.and_then(|dir| match RotatingFile::open(dir) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                eprintln!("[Tauri] Sidecar log file disabled: {}", e);
+                None
+            }
+        });
+
+        Self {
+            entries: VecDeque::with_capacity(BUFFER_CAPACITY),
+            next_seq: 1,
+            file,
+        }
+    }
+
+    fn push(&mut self, level: LogLevel, stream: LogStream, message: String) -> LogEntry {
+        let entry = LogEntry {
+            seq: self.next_seq,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            level,
+            stream,
+            message,
+        };
+        self.next_seq += 1;
+
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = file.write_entry(&entry) {
+                eprintln!("[Tauri] Failed to write sidecar log file: {}", e);
+                self.file = None;
+            }
+        }
+
+        if self.entries.len() == BUFFER_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry.clone());
+        entry
+    }
+
+    /// Returns entries newer than `since` (a sequence number) at or above
+    /// `level`, keeping the most recent `limit` of them in chronological order.
+    pub(crate) fn query(
+        &self,
+        since: Option<u64>,
+        level: Option<LogLevel>,
+        limit: Option<usize>,
+    ) -> Vec<LogEntry> {
+        let since = since.unwrap_or(0);
+        let min_level = level.unwrap_or(LogLevel::Info);
+
+        let mut matching: Vec<LogEntry> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| e.seq > since && e.level >= min_level)
+            .take(limit.unwrap_or(BUFFER_CAPACITY))
+            .cloned()
+            .collect();
+        matching.reverse();
+        matching
+    }
+}
+
+// ---------------------------------------------------------------------------
+// File Persistence
+// ---------------------------------------------------------------------------
+
+/// Append-only `sidecar.log` that rolls over to numbered files by size.
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("{}.log", LOG_FILE_STEM)))?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir, file, size })
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            self.dir.join(format!("{}.log", LOG_FILE_STEM))
+        } else {
+            self.dir.join(format!("{}.{}.log", LOG_FILE_STEM, index))
+        }
+    }
+
+    fn write_entry(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+        if self.size >= MAX_FILE_BYTES {
+            self.rotate()?;
+        }
+
+        let time = chrono::DateTime::from_timestamp_millis(entry.timestamp as i64)
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+            .unwrap_or_default();
+        let line = format!(
+            "{} {:<5} [{:?}] {}\n",
+            time,
+            format!("{:?}", entry.level).to_uppercase(),
+            entry.stream,
+            entry.message
+        );
+
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shifts `sidecar.N.log` up by one, dropping the oldest, and starts a
+    /// fresh `sidecar.log`.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let _ = fs::remove_file(self.path(MAX_ROTATED_FILES));
+        for index in (0..MAX_ROTATED_FILES).rev() {
+            let from = self.path(index);
+            if from.exists() {
+                fs::rename(&from, self.path(index + 1))?;
+            }
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(0))?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Capture
+// ---------------------------------------------------------------------------
+
+/// Stores a sidecar log line and pushes it to the webview.
+pub(crate) fn record(app: &AppHandle, level: LogLevel, stream: LogStream, message: &str) {
+    let entry = {
+        let state = app.state::<SidecarLogs>();
+        let mut buffer = match state.0.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        buffer.push(level, stream, message.to_string())
+    };
+
+    let _ = app.emit(EVENT_LOG, &entry);
+}
@@ -0,0 +1,711 @@
+pub(crate) mod launch;
+pub(crate) mod logs;
+pub(crate) mod protocol;
+pub(crate) mod shutdown;
+pub(crate) mod status;
+pub(crate) mod watchdog;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::{alert_queue, db};
+use logs::{LogLevel, LogStream};
+use protocol::{ControlLine, ControlMessage, ReadyMessage, ShellMessage};
+use shutdown::ShutdownSignal;
+use status::{set_status, SidecarStatus};
+
+// ---------------------------------------------------------------------------
+// Restart Policy
+// ---------------------------------------------------------------------------
+
+/// Delay before the first restart after a crash. Doubles on each further
+/// restart inside the current window.
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper bound for the exponential backoff delay.
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Sliding window used to count recent restarts.
+const RESTART_WINDOW: Duration = Duration::from_secs(300);
+
+/// Maximum number of restarts allowed inside `RESTART_WINDOW` before the
+/// supervisor gives up and leaves the sidecar stopped.
+const MAX_RESTARTS_IN_WINDOW: usize = 5;
+
+/// Emitted once a restarted sidecar has reported its new port.
+pub(crate) const EVENT_RESTARTED: &str = "sidecar://restarted";
+
+/// Emitted when the restart budget is exhausted and the sidecar stays down.
+pub(crate) const EVENT_RESTART_LIMIT: &str = "sidecar://restart-limit";
+
+/// Emitted with the per-namespace client counts whenever they change.
+pub(crate) const EVENT_CLIENTS: &str = "sidecar://clients";
+
+/// Emitted with each `StreamEvent` the server logs.
+pub(crate) const EVENT_STREAM_EVENT: &str = "sidecar://stream-event";
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+/// Holds the port number reported by the sidecar server.
+/// `None` means the port hasn't been received yet (or the sidecar is down).
+/// Wrapped in Arc so we can share it with the background reader thread.
+pub(crate) struct ServerPort(pub(crate) Arc<Mutex<Option<u16>>>);
+
+/// Holds the sidecar child process handle so we can kill it on exit.
+pub(crate) struct SidecarProcess(pub(crate) Mutex<Option<CommandChild>>);
+
+/// Bookkeeping for the restart supervisor.
+pub(crate) struct SidecarSupervisor(pub(crate) Mutex<SupervisorState>);
+
+/// Details the running sidecar reported over the control protocol.
+/// Reset whenever the process exits.
+pub(crate) struct SidecarInfo(pub(crate) Mutex<SidecarDetails>);
+
+#[derive(Default)]
+pub(crate) struct SidecarDetails {
+    pub(crate) handshake: Option<ReadyMessage>,
+    /// Control protocol version the sidecar's handshake used.
+    pub(crate) protocol: Option<u32>,
+    /// When the last heartbeat arrived and the uptime it reported.
+    pub(crate) last_heartbeat: Option<(Instant, f64)>,
+    pub(crate) last_fatal: Option<String>,
+    /// Connected Socket.io clients per namespace (`/alerts`, `/dashboard`...).
+    pub(crate) clients: BTreeMap<String, u32>,
+}
+
+impl SidecarDetails {
+    /// Serializable view of the details for the frontend.
+    pub(crate) fn snapshot(&self) -> SidecarInfoSnapshot {
+        SidecarInfoSnapshot {
+            shell_protocol_version: protocol::PROTOCOL_VERSION,
+            handshake: self.handshake.clone(),
+            secs_since_heartbeat: self
+                .last_heartbeat
+                .map(|(at, _)| at.elapsed().as_secs_f64()),
+            server_uptime: self.last_heartbeat.map(|(_, uptime)| uptime),
+            last_fatal: self.last_fatal.clone(),
+            clients: self.clients.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SupervisorState {
+    /// Timestamps of restarts inside the current window, oldest first.
+    recent_restarts: VecDeque<Instant>,
+    /// Total restarts since the app started, reported to the frontend.
+    total_restarts: u32,
+    /// Set on app exit so a terminating sidecar isn't respawned.
+    shutting_down: bool,
+    /// Set while the sidecar is being stopped on purpose (restart with a new
+    /// config, user stop) so its exit isn't treated as a crash. Cleared by
+    /// the next `start_sidecar`.
+    stop_requested: bool,
+    /// Incremented on every spawn so a reader thread can tell whether the
+    /// shared state still belongs to its process.
+    generation: u64,
+    /// Notified by the reader thread while graceful shutdowns are pending;
+    /// overlapping `stop_sidecar` calls each get every signal.
+    shutdown_waiters: Vec<mpsc::Sender<ShutdownSignal>>,
+}
+
+impl SupervisorState {
+    /// Records a restart attempt and returns how long to wait before it,
+    /// or `None` if the restart budget for the current window is spent.
+    fn next_restart_delay(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        while self
+            .recent_restarts
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > RESTART_WINDOW)
+        {
+            self.recent_restarts.pop_front();
+        }
+
+        if self.recent_restarts.len() >= MAX_RESTARTS_IN_WINDOW {
+            return None;
+        }
+
+        let exponent = self.recent_restarts.len() as u32;
+        let delay = RESTART_BASE_DELAY
+            .saturating_mul(1 << exponent)
+            .min(RESTART_MAX_DELAY);
+
+        self.recent_restarts.push_back(now);
+        self.total_restarts += 1;
+        Some(delay)
+    }
+
+    /// Marks the app as exiting. Any pending or future restart is abandoned.
+    pub(crate) fn begin_shutdown(&mut self) {
+        self.shutting_down = true;
+    }
+
+    /// Whether an exiting sidecar should be left down.
+    fn restart_suppressed(&self) -> bool {
+        self.shutting_down || self.stop_requested
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Event Payloads
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SidecarInfoSnapshot {
+    shell_protocol_version: u32,
+    handshake: Option<ReadyMessage>,
+    secs_since_heartbeat: Option<f64>,
+    server_uptime: Option<f64>,
+    last_fatal: Option<String>,
+    clients: BTreeMap<String, u32>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestartedPayload {
+    port: u16,
+    total_restarts: u32,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestartLimitPayload {
+    max_restarts: usize,
+    window_secs: u64,
+}
+
+// ---------------------------------------------------------------------------
+// Sidecar Management
+// ---------------------------------------------------------------------------
+
+/// Spawns the sidecar binary and wires up stdout parsing + state storage.
+///
+/// When the sidecar terminates unexpectedly the reader thread hands off to
+/// the supervisor, which respawns it with exponential backoff.
+pub(crate) fn start_sidecar(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    set_status(app, SidecarStatus::Spawning);
+
+    // Migrate before the server opens the database; if this fails the
+    // server's own runner reports the problem in its output
+    if let Err(e) = crate::db::migrations::run(app) {
+        shell_log(app, LogLevel::Error, &e);
+    }
+
+    let (mut rx, child) = spawn_command(app).inspect_err(|reason| {
+        set_status(
+            app,
+            SidecarStatus::FailedToSpawn {
+                reason: reason.clone(),
+            },
+        );
+    })?;
+
+    // Store the child handle so we can kill it on app exit
+    let sidecar_state = app.state::<SidecarProcess>();
+    {
+        let mut handle = sidecar_state.0.lock().unwrap();
+        *handle = Some(child);
+    }
+
+    // Clone the inner Mutex (via Arc-like managed state) for the background thread
+    let port_state = app.state::<ServerPort>().inner().0.clone();
+
+    let generation = {
+        let supervisor = app.state::<SidecarSupervisor>();
+        let mut state = supervisor.0.lock().unwrap();
+        state.stop_requested = false;
+        state.generation += 1;
+        state.generation
+    };
+
+    // A restarted sidecar announces its port through a separate event so the
+    // frontend knows to drop its cached port and reconnect.
+    let is_restart = generation > 1;
+
+    let app = app.clone();
+
+    // Spawn a background thread to read sidecar stdout/stderr lines
+    std::thread::spawn(move || {
+        let mut exit_code = None;
+        let mut exit_signal = None;
+
+        // Block on the receiver -- it yields until the sidecar exits
+        while let Some(event) = rx.blocking_recv() {
+            match event {
+                CommandEvent::Stdout(line) => {
+                    let text = String::from_utf8_lossy(&line);
+                    let trimmed = text.trim();
+
+                    match protocol::parse_line(trimmed) {
+                        Some(Ok(control)) => {
+                            handle_control(&app, &port_state, control, is_restart);
+                        }
+                        Some(Err(e)) => shell_log(
+                            &app,
+                            LogLevel::Warn,
+                            &format!("Malformed control message from sidecar: {}", e),
+                        ),
+                        // Forward other sidecar stdout for debugging
+                        None => {
+                            println!("[Sidecar] {}", trimmed);
+                            logs::record(&app, LogLevel::Info, LogStream::Stdout, trimmed);
+                        }
+                    }
+                }
+                CommandEvent::Stderr(line) => {
+                    let text = String::from_utf8_lossy(&line);
+                    let trimmed = text.trim();
+                    eprintln!("[Sidecar:err] {}", trimmed);
+                    logs::record(&app, stderr_level(trimmed), LogStream::Stderr, trimmed);
+                }
+                CommandEvent::Terminated(payload) => {
+                    shell_log(
+                        &app,
+                        LogLevel::Warn,
+                        &format!(
+                            "Sidecar process terminated (code: {:?}, signal: {:?})",
+                            payload.code, payload.signal
+                        ),
+                    );
+                    exit_code = payload.code;
+                    exit_signal = payload.signal;
+                    break;
+                }
+                CommandEvent::Error(err) => {
+                    shell_log(
+                        &app,
+                        LogLevel::Error,
+                        &format!("Sidecar command error: {}", err),
+                    );
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        // A newer sidecar may already be running if this one was killed
+        // after a restart timed out -- leave its state alone.
+        if current_generation(&app) != generation {
+            notify_shutdown(&app, ShutdownSignal::Terminated);
+            return;
+        }
+
+        // The sidecar is gone -- drop its stale port and handle, then let the
+        // supervisor decide whether to bring it back.
+        if let Ok(mut p) = port_state.lock() {
+            *p = None;
+        }
+        if let Ok(mut handle) = app.state::<SidecarProcess>().0.lock() {
+            handle.take();
+        }
+        if let Ok(mut info) = app.state::<SidecarInfo>().0.lock() {
+            info.handshake = None;
+            info.protocol = None;
+            info.last_heartbeat = None;
+            info.clients.clear();
+        }
+        let _ = app.emit(EVENT_CLIENTS, BTreeMap::<String, u32>::new());
+        set_status(
+            &app,
+            SidecarStatus::Exited {
+                code: exit_code,
+                signal: exit_signal,
+            },
+        );
+
+        // Decide before waking a pending `stop_sidecar`, whose caller may
+        // clear `stop_requested` by starting a new sidecar right away.
+        let restart = !restart_suppressed(&app);
+        notify_shutdown(&app, ShutdownSignal::Terminated);
+        if restart {
+            restart_after_exit(&app);
+        }
+    });
+
+    Ok(())
+}
+
+/// Applies a control message received from the sidecar.
+fn handle_control(
+    app: &AppHandle,
+    port_state: &Arc<Mutex<Option<u16>>>,
+    control: ControlLine,
+    is_restart: bool,
+) {
+    let info_state = app.state::<SidecarInfo>();
+
+    match control.message {
+        ControlMessage::Ready(ready) => {
+            // Refuse to talk to a server we can't understand -- leave the
+            // port unset so nothing connects to it.
+            if let Err(reason) = protocol::check_compatibility(control.protocol) {
+                shell_log(
+                    app,
+                    LogLevel::Error,
+                    &format!("Rejected sidecar handshake: {}", reason),
+                );
+                set_status(app, SidecarStatus::Incompatible { reason });
+                return;
+            }
+
+            let port = ready.port;
+            if let Ok(mut p) = port_state.lock() {
+                *p = Some(port);
+            }
+            shell_log(
+                app,
+                LogLevel::Info,
+                &format!(
+                    "Sidecar server ready on port {} (server v{}, schema {:?}, pid {}, protocol v{})",
+                    port, ready.server_version, ready.schema_version, ready.pid, control.protocol
+                ),
+            );
+            if let Ok(mut info) = info_state.0.lock() {
+                info.handshake = Some(ready);
+                info.protocol = Some(control.protocol);
+            }
+
+            set_status(app, SidecarStatus::Ready { port });
+            if is_restart {
+                emit_restarted(app, port);
+            }
+            if control.protocol >= protocol::QUEUE_PROTOCOL_VERSION {
+                replay_alerts(app);
+            }
+        }
+        ControlMessage::Heartbeat { uptime } => {
+            if let Ok(mut info) = info_state.0.lock() {
+                info.last_heartbeat = Some((Instant::now(), uptime));
+            }
+        }
+        ControlMessage::Fatal { message } => {
+            shell_log(
+                app,
+                LogLevel::Error,
+                &format!("Sidecar reported a fatal error: {}", message),
+            );
+            if let Ok(mut info) = info_state.0.lock() {
+                info.last_fatal = Some(message);
+            }
+        }
+        ControlMessage::Clients { clients } => {
+            if let Ok(mut info) = info_state.0.lock() {
+                info.clients = clients.clone();
+            }
+            let _ = app.emit(EVENT_CLIENTS, clients);
+        }
+        ControlMessage::Event { event } => {
+            let _ = app.emit(EVENT_STREAM_EVENT, event);
+        }
+        ControlMessage::Queue(message) => {
+            if let Err(e) = db::open(app).and_then(|conn| alert_queue::record(&conn, &message)) {
+                shell_log(app, LogLevel::Warn, &e);
+            }
+            if message.state == protocol::QueueState::Enqueued {
+                if let Err(e) = alert_queue::schedule::reschedule(app) {
+                    shell_log(
+                        app,
+                        LogLevel::Warn,
+                        &format!("Failed to schedule alerts: {}", e),
+                    );
+                }
+            }
+        }
+        ControlMessage::Stopped => {
+            shell_log(app, LogLevel::Info, "Sidecar acknowledged shutdown");
+            notify_shutdown(app, ShutdownSignal::Acknowledged);
+        }
+    }
+}
+
+/// Sends alerts a previous sidecar never finished to the one that just
+/// reported ready.
+fn replay_alerts(app: &AppHandle) {
+    let (alerts, given_up) = match db::open(app).and_then(|conn| alert_queue::take_replay(&conn)) {
+        Ok(replay) => replay,
+        Err(e) => {
+            shell_log(
+                app,
+                LogLevel::Warn,
+                &format!("Failed to read the alert backlog: {}", e),
+            );
+            return;
+        }
+    };
+
+    if given_up > 0 {
+        shell_log(
+            app,
+            LogLevel::Warn,
+            &format!(
+                "Skipped {} alert(s) that failed to finish after repeated replays",
+                given_up
+            ),
+        );
+    }
+    if alerts.is_empty() {
+        return;
+    }
+
+    let count = alerts.len();
+    match send_to_sidecar(app, &ShellMessage::Replay { alerts }) {
+        Ok(()) => shell_log(
+            app,
+            LogLevel::Info,
+            &format!("Replaying {} unfinished alert(s)", count),
+        ),
+        Err(e) => shell_log(
+            app,
+            LogLevel::Warn,
+            &format!("Failed to replay unfinished alerts: {}", e),
+        ),
+    }
+}
+
+/// Writes a control message to the running sidecar's stdin.
+pub(crate) fn send_to_sidecar(app: &AppHandle, message: &ShellMessage) -> Result<(), String> {
+    let state = app.state::<SidecarProcess>();
+    let mut child = state
+        .0
+        .lock()
+        .map_err(|_| "Sidecar handle lock poisoned".to_string())?;
+    let child = child
+        .as_mut()
+        .ok_or_else(|| "Sidecar is not running".to_string())?;
+    child
+        .write(message.to_line().as_bytes())
+        .map_err(|e| format!("Failed to write to the sidecar: {}", e))
+}
+
+/// Builds and spawns the sidecar command, returning a readable error on failure.
+fn spawn_command(
+    app: &AppHandle,
+) -> Result<(tauri::async_runtime::Receiver<CommandEvent>, CommandChild), String> {
+    let command = app
+        .shell()
+        .sidecar("binaries/streamforge-server")
+        .map_err(|e| {
+            format!(
+                "Failed to create sidecar command: {}. \
+                 Make sure the binary exists in src-tauri/binaries/",
+                e
+            )
+        })?
+        .envs(launch::current(app).to_env());
+
+    command.spawn().map_err(|e| {
+        format!(
+            "Failed to spawn sidecar process: {}. \
+             The binary may be missing or not executable.",
+            e
+        )
+    })
+}
+
+/// Respawns the sidecar after it exited, retrying with backoff until it
+/// starts, the restart budget runs out, or the app begins shutting down.
+fn restart_after_exit(app: &AppHandle) {
+    let generation = current_generation(app);
+    loop {
+        let delay = {
+            let supervisor = app.state::<SidecarSupervisor>();
+            let mut state = match supervisor.0.lock() {
+                Ok(guard) => guard,
+                Err(_) => return,
+            };
+            if state.restart_suppressed() {
+                return;
+            }
+            state.next_restart_delay()
+        };
+
+        let Some(delay) = delay else {
+            shell_log(
+                app,
+                LogLevel::Error,
+                &format!(
+                    "Sidecar crashed {} times within {}s, giving up",
+                    MAX_RESTARTS_IN_WINDOW,
+                    RESTART_WINDOW.as_secs()
+                ),
+            );
+            let _ = app.emit(
+                EVENT_RESTART_LIMIT,
+                RestartLimitPayload {
+                    max_restarts: MAX_RESTARTS_IN_WINDOW,
+                    window_secs: RESTART_WINDOW.as_secs(),
+                },
+            );
+            return;
+        };
+
+        shell_log(
+            app,
+            LogLevel::Info,
+            &format!("Restarting sidecar in {} ms...", delay.as_millis()),
+        );
+        std::thread::sleep(delay);
+
+        // The app may have started exiting, or the user stopped or started
+        // the server themselves while we were waiting
+        if restart_suppressed(app) || current_generation(app) != generation {
+            return;
+        }
+
+        match start_sidecar(app) {
+            Ok(()) => return,
+            Err(e) => shell_log(
+                app,
+                LogLevel::Error,
+                &format!("Sidecar restart failed: {}", e),
+            ),
+        }
+    }
+}
+
+/// Prints a shell message about the sidecar and captures it in the log buffer.
+fn shell_log(app: &AppHandle, level: LogLevel, message: &str) {
+    match level {
+        LogLevel::Info => println!("[Tauri] {}", message),
+        LogLevel::Warn | LogLevel::Error => eprintln!("[Tauri] {}", message),
+    }
+    logs::record(app, level, LogStream::Shell, message);
+}
+
+/// Node writes both `console.warn` and `console.error` to stderr; tell them
+/// apart by the conventional wording so warnings don't drown out errors.
+fn stderr_level(line: &str) -> LogLevel {
+    if line.to_ascii_lowercase().contains("warn") {
+        LogLevel::Warn
+    } else {
+        LogLevel::Error
+    }
+}
+
+/// Wakes every pending `stop_sidecar` call, dropping those that returned.
+fn notify_shutdown(app: &AppHandle, signal: ShutdownSignal) {
+    if let Ok(mut supervisor) = app.state::<SidecarSupervisor>().0.lock() {
+        supervisor
+            .shutdown_waiters
+            .retain(|waiter| waiter.send(signal).is_ok());
+    }
+}
+
+fn restart_suppressed(app: &AppHandle) -> bool {
+    app.state::<SidecarSupervisor>()
+        .0
+        .lock()
+        .map(|s| s.restart_suppressed())
+        .unwrap_or(true)
+}
+
+fn current_generation(app: &AppHandle) -> u64 {
+    app.state::<SidecarSupervisor>()
+        .0
+        .lock()
+        .map(|s| s.generation)
+        .unwrap_or(0)
+}
+
+/// Marks the upcoming exit as intentional so the supervisor leaves the
+/// sidecar down. Returns `false` if the app is already exiting.
+fn request_stop(app: &AppHandle) -> bool {
+    match app.state::<SidecarSupervisor>().0.lock() {
+        Ok(mut supervisor) if !supervisor.shutting_down => {
+            supervisor.stop_requested = true;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Stops the running sidecar on purpose and starts a fresh one, e.g. after
+/// the launch config changed. Blocks until the new process is spawned.
+pub(crate) fn restart_sidecar(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    if !request_stop(app) {
+        return Ok(());
+    }
+
+    shell_log(app, LogLevel::Info, "Restarting sidecar...");
+    shutdown::stop_sidecar(app, shutdown::shutdown_timeout());
+    start_sidecar(app)
+}
+
+/// Replaces a hung sidecar for the watchdog, waiting only `timeout` before
+/// killing it. Unlike a crash, this doesn't count against the restart
+/// budget, so a few hangs can't leave the server without crash recovery.
+pub(crate) fn restart_unresponsive_sidecar(
+    app: &AppHandle,
+    timeout: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !request_stop(app) {
+        return Ok(());
+    }
+
+    shutdown::stop_sidecar(app, timeout);
+    start_sidecar(app)
+}
+
+/// Stops the sidecar on the user's behalf and keeps it down until
+/// `start_stopped_sidecar` is called. Also cancels a pending crash restart.
+pub(crate) fn stop_sidecar_on_request(app: &AppHandle) {
+    if !request_stop(app) {
+        return;
+    }
+
+    shell_log(app, LogLevel::Info, "Stopping sidecar on request...");
+    shutdown::stop_sidecar(app, shutdown::shutdown_timeout());
+    set_status(app, SidecarStatus::Stopped);
+}
+
+/// Starts the sidecar again after it was stopped, crashed for good or
+/// failed to launch. Does nothing while a process is running.
+pub(crate) fn start_stopped_sidecar(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let running = app
+        .state::<SidecarProcess>()
+        .0
+        .lock()
+        .map(|child| child.is_some())
+        .unwrap_or(true);
+    if running
+        || app
+            .state::<SidecarSupervisor>()
+            .0
+            .lock()
+            .map_or(true, |s| s.shutting_down)
+    {
+        return Ok(());
+    }
+
+    shell_log(app, LogLevel::Info, "Starting sidecar on request...");
+    start_sidecar(app)
+}
+
+fn emit_restarted(app: &AppHandle, port: u16) {
+    let total_restarts = app
+        .state::<SidecarSupervisor>()
+        .0
+        .lock()
+        .map(|s| s.total_restarts)
+        .unwrap_or(0);
+
+    if let Err(e) = app.emit(
+        EVENT_RESTARTED,
+        RestartedPayload {
+            port,
+            total_restarts,
+        },
+    ) {
+        eprintln!("[Tauri] Failed to emit {}: {}", EVENT_RESTARTED, e);
+    }
+}
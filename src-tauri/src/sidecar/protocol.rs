@@ -0,0 +1,245 @@
+//! Line-delimited JSON control protocol spoken by the sidecar on stdout.
+//!
+//! Every control message is a single JSON object on its own line carrying a
+//! `streamforge` key with the protocol version and a `type` discriminator:
+//!
+//! ```text
+//! {"streamforge":5,"type":"ready","port":39283,"serverVersion":"0.1.0","schemaVersion":5,"pid":4242,"dataDir":"/home/me/.config/streamforge"}
+//! {"streamforge":5,"type":"heartbeat","uptime":12.5}
+//! {"streamforge":5,"type":"fatal","message":"EADDRINUSE"}
+//! {"streamforge":5,"type":"clients","clients":{"/alerts":1,"/chat":0,"/widgets":0,"/dashboard":1}}
+//! {"streamforge":5,"type":"event","event":{"id":"...","platform":"twitch","eventType":"cheer","username":"viewer1","displayName":"Viewer1","amount":500,"message":null,"alertFired":true,"timestamp":"..."}}
+//! {"streamforge":5,"type":"queue","state":"enqueued","alertId":"...","alert":{"id":"...","type":"cheer","username":"viewer1","config":{...},...}}
+//! {"streamforge":5,"type":"queue","state":"done","alertId":"..."}
+//! ```
+//!
+//! Any other stdout line is ordinary log output. The shell talks back over
+//! the sidecar's stdin using the same framing (see `ShellMessage`).
+//!
+//! The Node side lives in `server/utils/control.js` and must be kept in sync
+//! with this file.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::alert_queue::schedule::SchedulePlan;
+
+/// Protocol version spoken by this build of the shell.
+/// v2 added the `queue` transitions and the `replay` request, v3 the
+/// `schedule` request, v4 the `enqueue` request and v5 the `channels`
+/// request.
+pub(crate) const PROTOCOL_VERSION: u32 = 5;
+
+/// Oldest sidecar protocol version the shell still understands.
+pub(crate) const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// First protocol version that reports alert queue transitions and accepts
+/// `replay`.
+pub(crate) const QUEUE_PROTOCOL_VERSION: u32 = 2;
+
+/// First protocol version that accepts `schedule`.
+pub(crate) const SCHEDULE_PROTOCOL_VERSION: u32 = 3;
+
+/// First protocol version that accepts `enqueue`.
+pub(crate) const ENQUEUE_PROTOCOL_VERSION: u32 = 4;
+
+/// First protocol version that accepts `channels`.
+pub(crate) const CHANNELS_PROTOCOL_VERSION: u32 = 5;
+
+/// Key that marks a stdout line as a control message.
+const PROTOCOL_KEY: &str = "streamforge";
+
+/// Handshake sent once the HTTP server is listening.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReadyMessage {
+    pub(crate) port: u16,
+    pub(crate) server_version: String,
+    /// Highest applied migration, or `None` if the database failed to open.
+    pub(crate) schema_version: Option<u32>,
+    pub(crate) pid: u32,
+    pub(crate) data_dir: String,
+}
+
+/// A stream event (follow, sub, cheer...) as written to `event_log`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StreamEvent {
+    pub(crate) id: String,
+    pub(crate) platform: String,
+    pub(crate) event_type: String,
+    pub(crate) username: String,
+    pub(crate) display_name: Option<String>,
+    /// Bits, viewers, months or money depending on the event type. The
+    /// server passes through whatever the source sent, so numeric strings
+    /// are accepted too.
+    #[serde(default, deserialize_with = "lenient_amount")]
+    pub(crate) amount: Option<f64>,
+    pub(crate) message: Option<String>,
+    #[serde(default)]
+    pub(crate) alert_fired: bool,
+    pub(crate) timestamp: String,
+}
+
+/// A step in an alert's life in the server's queue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum QueueState {
+    Enqueued,
+    Started,
+    Done,
+    Skipped,
+}
+
+impl QueueState {
+    /// The value stored in `alert_queue.status`.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            QueueState::Enqueued => "enqueued",
+            QueueState::Started => "started",
+            QueueState::Done => "done",
+            QueueState::Skipped => "skipped",
+        }
+    }
+}
+
+/// An alert moved through the server's queue.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QueueMessage {
+    pub(crate) state: QueueState,
+    pub(crate) alert_id: String,
+    /// The full alert object, only sent with `enqueued`.
+    #[serde(default)]
+    pub(crate) alert: Option<Value>,
+}
+
+fn lenient_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum ControlMessage {
+    Ready(ReadyMessage),
+    /// Periodic liveness signal with the server's uptime in seconds.
+    Heartbeat {
+        uptime: f64,
+    },
+    /// The server hit an unrecoverable error and is about to exit.
+    Fatal {
+        message: String,
+    },
+    /// Connected Socket.io clients per namespace, sent whenever a count
+    /// changes.
+    Clients {
+        clients: BTreeMap<String, u32>,
+    },
+    /// A stream event was logged by the server.
+    Event {
+        event: StreamEvent,
+    },
+    /// An alert was queued, started playing, finished or was skipped.
+    Queue(QueueMessage),
+    /// Acknowledges a shutdown request: the database and sockets are
+    /// closed and the process is exiting.
+    Stopped,
+}
+
+/// Messages the shell writes to the sidecar's stdin.
+#[derive(Clone, Debug)]
+pub(crate) enum ShellMessage {
+    /// Ask the server to close its database and sockets and exit.
+    Shutdown,
+    /// Queue alerts a previous sidecar never finished, oldest first.
+    Replay { alerts: Vec<Value> },
+    /// Reorder the waiting alerts, dropping or collapsing expired ones.
+    Schedule(SchedulePlan),
+    /// Queue an alert from the shell's event pipeline and log the events
+    /// behind it to `event_log`, one row each.
+    Enqueue { alert: Value, events: Vec<Value> },
+    /// Alert channels changed in the database; reload them.
+    ReloadChannels,
+}
+
+impl ShellMessage {
+    /// Encodes the message as a single newline-terminated JSON line.
+    pub(crate) fn to_line(&self) -> String {
+        let message = match self {
+            ShellMessage::Shutdown => {
+                serde_json::json!({ PROTOCOL_KEY: PROTOCOL_VERSION, "type": "shutdown" })
+            }
+            ShellMessage::Replay { alerts } => {
+                serde_json::json!({ PROTOCOL_KEY: PROTOCOL_VERSION, "type": "replay", "alerts": alerts })
+            }
+            ShellMessage::Schedule(plan) => serde_json::json!({
+                PROTOCOL_KEY: PROTOCOL_VERSION,
+                "type": "schedule",
+                "order": plan.order,
+                "drop": plan.drop,
+                "collapse": plan.collapse,
+            }),
+            ShellMessage::Enqueue { alert, events } => serde_json::json!({
+                PROTOCOL_KEY: PROTOCOL_VERSION,
+                "type": "enqueue",
+                "alert": alert,
+                "events": events,
+            }),
+            ShellMessage::ReloadChannels => {
+                serde_json::json!({ PROTOCOL_KEY: PROTOCOL_VERSION, "type": "channels" })
+            }
+        };
+        format!("{}\n", message)
+    }
+}
+
+/// A parsed control line together with the protocol version it was sent with.
+#[derive(Clone, Debug)]
+pub(crate) struct ControlLine {
+    pub(crate) protocol: u32,
+    pub(crate) message: ControlMessage,
+}
+
+/// Parses a single stdout line.
+///
+/// Returns `None` for ordinary log output, `Some(Err)` for a line that is
+/// marked as a control message but can't be understood.
+pub(crate) fn parse_line(line: &str) -> Option<Result<ControlLine, String>> {
+    if !line.starts_with('{') {
+        return None;
+    }
+
+    let mut value: Value = serde_json::from_str(line).ok()?;
+    let protocol = value.as_object_mut()?.remove(PROTOCOL_KEY)?;
+
+    let result = protocol
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| format!("invalid protocol version {}", protocol))
+        .and_then(|protocol| {
+            serde_json::from_value::<ControlMessage>(value)
+                .map(|message| ControlLine { protocol, message })
+                .map_err(|e| e.to_string())
+        });
+
+    Some(result)
+}
+
+/// Checks whether the shell can talk to a sidecar using `protocol`.
+pub(crate) fn check_compatibility(protocol: u32) -> Result<(), String> {
+    if (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&protocol) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Sidecar speaks control protocol v{}, but this app supports v{}-v{}. \
+             Rebuild the server with `npm run build:server`.",
+            protocol, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+        ))
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use super::logs::LogLevel;
+use super::protocol::ShellMessage;
+use super::{shell_log, SidecarProcess, SidecarSupervisor};
+
+/// How long to wait for the sidecar to acknowledge a shutdown request before
+/// killing it. Slightly longer than the server's own 5 s forced-exit timer so
+/// it gets the chance to bail out by itself first.
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(6000);
+
+/// How long to wait for the reader thread to observe a killed process exit.
+const KILL_REAP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Overrides `DEFAULT_SHUTDOWN_TIMEOUT`, in milliseconds.
+const SHUTDOWN_TIMEOUT_ENV: &str = "STREAMFORGE_SHUTDOWN_TIMEOUT_MS";
+
+/// Signals delivered to a pending shutdown by the reader thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShutdownSignal {
+    /// The sidecar sent its `stopped` control message.
+    Acknowledged,
+    /// The process terminated without acknowledging.
+    Terminated,
+}
+
+/// How a shutdown attempt ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ShutdownOutcome {
+    /// No sidecar was running.
+    NotRunning,
+    /// The sidecar closed cleanly and confirmed it.
+    Acknowledged,
+    /// The sidecar exited on request but never confirmed.
+    ExitedWithoutAck,
+    /// The sidecar didn't react in time and was killed.
+    Killed,
+    /// The sidecar didn't react in time and couldn't be killed either.
+    KillFailed(String),
+}
+
+/// Returns the configured acknowledgement timeout.
+pub(crate) fn shutdown_timeout() -> Duration {
+    std::env::var(SHUTDOWN_TIMEOUT_ENV)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT)
+}
+
+/// Asks the running sidecar to shut down and waits up to `timeout` for it to
+/// confirm and exit, escalating to a hard kill if it doesn't.
+///
+/// Returns once the reader thread has processed the exit, so the caller can
+/// safely start a new sidecar. The caller is responsible for telling the
+/// supervisor whether to restart the process. The outcome is written to the
+/// sidecar log. Overlapping calls (a user stop during a config restart)
+/// each wait for the same exit.
+pub(crate) fn stop_sidecar(app: &AppHandle, timeout: Duration) -> ShutdownOutcome {
+    let (tx, rx) = mpsc::channel();
+
+    let requested = {
+        let state = app.state::<SidecarProcess>();
+        let mut child_opt = match state.0.lock() {
+            Ok(guard) => guard,
+            Err(_) => return ShutdownOutcome::NotRunning,
+        };
+        let Some(child) = child_opt.as_mut() else {
+            return ShutdownOutcome::NotRunning;
+        };
+
+        // Register interest before asking, so a fast reply isn't missed
+        if let Ok(mut supervisor) = app.state::<SidecarSupervisor>().0.lock() {
+            supervisor.shutdown_waiters.push(tx);
+        }
+
+        shell_log(
+            app,
+            LogLevel::Info,
+            "Requesting graceful sidecar shutdown...",
+        );
+        match child.write(ShellMessage::Shutdown.to_line().as_bytes()) {
+            Ok(()) => true,
+            Err(e) => {
+                shell_log(
+                    app,
+                    LogLevel::Warn,
+                    &format!("Failed to send shutdown request over stdin: {}", e),
+                );
+                send_sigterm(app, child.pid())
+            }
+        }
+    };
+
+    let deadline = Instant::now() + timeout;
+    let mut acknowledged = false;
+    let outcome = loop {
+        if !requested {
+            break kill_sidecar(app, &rx);
+        }
+        // Keep waiting after the acknowledgement until the process is gone
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(ShutdownSignal::Acknowledged) => acknowledged = true,
+            Ok(ShutdownSignal::Terminated) if acknowledged => break ShutdownOutcome::Acknowledged,
+            Ok(ShutdownSignal::Terminated) => break ShutdownOutcome::ExitedWithoutAck,
+            Err(_) => break kill_sidecar(app, &rx),
+        }
+    };
+
+    // Our sender is pruned by the next notification once `rx` is dropped;
+    // clearing the list here would strand a concurrent call's waiter
+    drop(rx);
+
+    let (level, message) = match &outcome {
+        ShutdownOutcome::NotRunning => (LogLevel::Info, "Sidecar was not running".to_string()),
+        ShutdownOutcome::Acknowledged => (LogLevel::Info, "Sidecar shut down cleanly".to_string()),
+        ShutdownOutcome::ExitedWithoutAck => (
+            LogLevel::Warn,
+            "Sidecar exited without acknowledging shutdown".to_string(),
+        ),
+        ShutdownOutcome::Killed => (
+            LogLevel::Warn,
+            format!(
+                "Sidecar did not shut down within {} ms and was killed",
+                timeout.as_millis()
+            ),
+        ),
+        ShutdownOutcome::KillFailed(e) => {
+            (LogLevel::Error, format!("Failed to kill sidecar: {}", e))
+        }
+    };
+    shell_log(app, level, &message);
+
+    outcome
+}
+
+/// Hard-kills the sidecar if it is still running and waits briefly for the
+/// reader thread to notice.
+fn kill_sidecar(app: &AppHandle, rx: &mpsc::Receiver<ShutdownSignal>) -> ShutdownOutcome {
+    let child = app
+        .state::<SidecarProcess>()
+        .0
+        .lock()
+        .ok()
+        .and_then(|mut guard| guard.take());
+
+    match child {
+        // The reader thread already reaped it while we were timing out
+        None => ShutdownOutcome::ExitedWithoutAck,
+        Some(child) => match child.kill() {
+            Ok(()) => {
+                let reap_deadline = Instant::now() + KILL_REAP_TIMEOUT;
+                while let Ok(signal) =
+                    rx.recv_timeout(reap_deadline.saturating_duration_since(Instant::now()))
+                {
+                    if signal == ShutdownSignal::Terminated {
+                        break;
+                    }
+                }
+                ShutdownOutcome::Killed
+            }
+            Err(e) => ShutdownOutcome::KillFailed(e.to_string()),
+        },
+    }
+}
+
+/// Falls back to SIGTERM, which the server handles the same way as a
+/// shutdown request. Returns whether the signal was delivered.
+#[cfg(unix)]
+fn send_sigterm(app: &AppHandle, pid: u32) -> bool {
+    // SAFETY: `kill` has no memory-safety preconditions; an invalid or stale
+    // PID just makes it return an error.
+    let delivered = unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0;
+    if delivered {
+        shell_log(app, LogLevel::Info, "Sent SIGTERM to sidecar");
+    }
+    delivered
+}
+
+/// There is no SIGTERM equivalent on Windows, so go straight to the kill.
+#[cfg(not(unix))]
+fn send_sigterm(_app: &AppHandle, _pid: u32) -> bool {
+    false
+}
@@ -0,0 +1,79 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Emitted with the new `SidecarStatus` every time it changes.
+pub(crate) const EVENT_STATUS: &str = "sidecar://status";
+
+/// Lifecycle of the sidecar process as seen by the Rust shell.
+///
+/// Serialized with a `state` tag so the frontend can switch on it:
+/// `{ "state": "ready", "port": 39283 }`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub(crate) enum SidecarStatus {
+    /// The app hasn't tried to launch the sidecar yet.
+    #[default]
+    NotStarted,
+    /// The process has been spawned but hasn't reported its port.
+    Spawning,
+    /// The server is listening on `port`.
+    Ready { port: u16 },
+    /// The server is running but has failed several health checks in a row.
+    Unhealthy { port: u16, reason: String },
+    /// The process was stopped on request and won't be restarted until
+    /// started again.
+    Stopped,
+    /// The process exited. `signal` is only set on Unix.
+    Exited {
+        code: Option<i32>,
+        signal: Option<i32>,
+    },
+    /// The binary could not be launched at all (missing, not executable...).
+    FailedToSpawn { reason: String },
+    /// The process started but its control protocol version isn't supported.
+    Incompatible { reason: String },
+}
+
+/// Current sidecar status, updated by the spawner, reader thread and watchdog.
+pub(crate) struct SidecarState(pub(crate) Mutex<SidecarStatus>);
+
+/// Updates the stored status and notifies the webview if it changed.
+pub(crate) fn set_status(app: &AppHandle, status: SidecarStatus) {
+    set_status_if(app, |_| true, status);
+}
+
+/// Like `set_status`, but only applies the change while `condition` holds for
+/// the current status. Used by the watchdog so a late health verdict can't
+/// overwrite an `Exited` recorded by the reader thread in the meantime.
+pub(crate) fn set_status_if(
+    app: &AppHandle,
+    condition: impl FnOnce(&SidecarStatus) -> bool,
+    status: SidecarStatus,
+) {
+    {
+        let state = app.state::<SidecarState>();
+        let mut current = match state.0.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        if *current == status || !condition(&current) {
+            return;
+        }
+        *current = status.clone();
+    }
+
+    if let Err(e) = app.emit(EVENT_STATUS, &status) {
+        eprintln!("[Tauri] Failed to emit {}: {}", EVENT_STATUS, e);
+    }
+}
+
+/// Returns a snapshot of the current status.
+pub(crate) fn current_status(app: &AppHandle) -> SidecarStatus {
+    app.state::<SidecarState>()
+        .0
+        .lock()
+        .map(|s| s.clone())
+        .unwrap_or_default()
+}
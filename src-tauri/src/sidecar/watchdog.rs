@@ -0,0 +1,210 @@
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::launch;
+use super::logs::LogLevel;
+use super::status::{current_status, set_status_if, SidecarStatus};
+use super::SidecarInfo;
+use super::{restart_unresponsive_sidecar, shell_log};
+
+/// Time between health checks.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Timeout for a single `/api/health` probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A heartbeat older than this counts as a missed check. The server sends
+/// one every 5 s, so this tolerates two lost heartbeats.
+const HEARTBEAT_STALE_AFTER: Duration = Duration::from_secs(15);
+
+/// Consecutive failed checks before the sidecar is marked unhealthy.
+const MAX_MISSES: u32 = 3;
+
+/// A hung event loop won't answer a shutdown request, so don't wait long
+/// before killing it.
+const UNHEALTHY_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+/// Health-check bookkeeping, shared between the watchdog thread and commands.
+pub(crate) struct SidecarWatchdog(pub(crate) Mutex<WatchdogState>);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WatchdogState {
+    /// Restart an unhealthy sidecar. These restarts are counted in
+    /// `restarts_triggered`, not against the supervisor's crash budget.
+    pub(crate) auto_restart: bool,
+    consecutive_misses: u32,
+    last_error: Option<String>,
+    /// Restarts triggered by the watchdog since the app started.
+    restarts_triggered: u32,
+}
+
+impl Default for WatchdogState {
+    fn default() -> Self {
+        Self {
+            auto_restart: true,
+            consecutive_misses: 0,
+            last_error: None,
+            restarts_triggered: 0,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Watchdog Thread
+// ---------------------------------------------------------------------------
+
+/// Starts the background thread that health-checks the sidecar for the
+/// lifetime of the app.
+pub(crate) fn spawn_watchdog(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+
+        // Only a listening server can be checked; anything else is the
+        // supervisor's business.
+        let port = match current_status(&app) {
+            SidecarStatus::Ready { port } | SidecarStatus::Unhealthy { port, .. } => port,
+            _ => {
+                reset(&app);
+                continue;
+            }
+        };
+
+        match check_once(&app, port) {
+            Ok(()) => on_success(&app, port),
+            Err(reason) => on_miss(&app, port, reason),
+        }
+    });
+}
+
+/// Runs a single health check against the sidecar on `port`.
+fn check_once(app: &AppHandle, port: u16) -> Result<(), String> {
+    let stale_heartbeat = app
+        .state::<SidecarInfo>()
+        .0
+        .lock()
+        .ok()
+        .and_then(|info| info.last_heartbeat.map(|(at, _)| at.elapsed()))
+        .filter(|age| *age > HEARTBEAT_STALE_AFTER);
+    if let Some(age) = stale_heartbeat {
+        return Err(format!("no heartbeat for {} s", age.as_secs()));
+    }
+
+    probe_health(launch::current(app).probe_address(), port)
+}
+
+/// Issues `GET /api/health` and expects a 200 with `"status":"ok"` in the
+/// body.
+fn probe_health(ip: IpAddr, port: u16) -> Result<(), String> {
+    let url = launch::server_url(ip, port, "/api/health");
+    let mut response = launch::server_agent(PROBE_TIMEOUT)
+        .get(&url)
+        .call()
+        .map_err(|e| format!("health probe to {} failed: {}", url, e))?;
+
+    let status = response.status().as_u16();
+    if status != 200 {
+        return Err(format!("health probe returned HTTP {}", status));
+    }
+    let body = response
+        .body_mut()
+        .read_to_string()
+        .map_err(|e| format!("reading the health probe response failed: {}", e))?;
+    if !body.contains("\"status\":\"ok\"") {
+        return Err("health probe returned an unexpected body".to_string());
+    }
+
+    Ok(())
+}
+
+fn reset(app: &AppHandle) {
+    if let Ok(mut state) = app.state::<SidecarWatchdog>().0.lock() {
+        state.consecutive_misses = 0;
+        state.last_error = None;
+    }
+}
+
+fn on_success(app: &AppHandle, port: u16) {
+    let recovered = app
+        .state::<SidecarWatchdog>()
+        .0
+        .lock()
+        .map(|mut state| {
+            let recovered = state.consecutive_misses >= MAX_MISSES;
+            state.consecutive_misses = 0;
+            state.last_error = None;
+            recovered
+        })
+        .unwrap_or(false);
+
+    if recovered {
+        shell_log(app, LogLevel::Info, "Sidecar is healthy again");
+    }
+    set_status_if(
+        app,
+        |current| matches!(current, SidecarStatus::Unhealthy { port: p, .. } if *p == port),
+        SidecarStatus::Ready { port },
+    );
+}
+
+fn on_miss(app: &AppHandle, port: u16, reason: String) {
+    let (misses, auto_restart) = {
+        let watchdog = app.state::<SidecarWatchdog>();
+        let Ok(mut state) = watchdog.0.lock() else {
+            return;
+        };
+        state.consecutive_misses += 1;
+        state.last_error = Some(reason.clone());
+        (state.consecutive_misses, state.auto_restart)
+    };
+
+    // Stay quiet once the verdict is in, so a hung server doesn't flood the log
+    if misses <= MAX_MISSES {
+        shell_log(
+            app,
+            LogLevel::Warn,
+            &format!(
+                "Sidecar health check failed ({}/{}): {}",
+                misses, MAX_MISSES, reason
+            ),
+        );
+    }
+    if misses < MAX_MISSES {
+        return;
+    }
+
+    set_status_if(
+        app,
+        |current| matches!(current, SidecarStatus::Ready { port: p } if *p == port),
+        SidecarStatus::Unhealthy {
+            port,
+            reason: reason.clone(),
+        },
+    );
+
+    if auto_restart {
+        shell_log(
+            app,
+            LogLevel::Error,
+            "Sidecar is unresponsive, restarting it",
+        );
+        if let Ok(mut state) = app.state::<SidecarWatchdog>().0.lock() {
+            state.restarts_triggered += 1;
+        }
+        if let Err(e) = restart_unresponsive_sidecar(app, UNHEALTHY_SHUTDOWN_TIMEOUT) {
+            shell_log(
+                app,
+                LogLevel::Error,
+                &format!("Sidecar restart failed: {}", e),
+            );
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Graceful exit on SIGINT/SIGTERM (Ctrl+C on Windows), so a headless
+//! instance can be stopped like any other service without orphaning the
+//! sidecar.
+
+use tauri::AppHandle;
+
+/// Exits the app -- running the normal sidecar shutdown -- when the process
+/// is asked to terminate.
+pub(crate) fn exit_on_signal(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match wait_for_signal().await {
+            Ok(name) => {
+                println!("[Tauri] Received {}, shutting down...", name);
+                app.exit(0);
+            }
+            Err(e) => eprintln!("[Tauri] Signal handling disabled: {}", e),
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> std::io::Result<&'static str> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    Ok(tokio::select! {
+        _ = terminate.recv() => "SIGTERM",
+        _ = interrupt.recv() => "SIGINT",
+        _ = hangup.recv() => "SIGHUP",
+    })
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> std::io::Result<&'static str> {
+    tokio::signal::ctrl_c().await.map(|_| "Ctrl+C")
+}
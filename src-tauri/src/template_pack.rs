@@ -0,0 +1,832 @@
+//! Alert template packs: several templates plus the sounds and images they
+//! use, shipped as one file.
+//!
+//! A pack is a zip archive (or, while authoring, a folder) laid out as:
+//!
+//! ```text
+//! pack.json              TemplatePack
+//! media/sounds/<file>    sounds referenced as "media/sounds/<file>"
+//! media/images/<file>    images referenced as "media/images/<file>"
+//! ```
+//!
+//! Field names follow the `alerts` columns and the template files the
+//! dashboard exports, so a pack's `templates` entries are interchangeable
+//! with those. Inside a pack, `sound_path`/`image_path` are null, an
+//! `http(s)` URL, or a `media/...` path within the pack; importing rewrites
+//! the latter to the uploaded copies.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::db;
+use crate::media::{self, MediaKind, MAX_MEDIA_BYTES};
+
+const PACK_FILE: &str = "pack.json";
+const MEDIA_PREFIX: &str = "media";
+
+/// Identifies template packs in `TemplatePack::format`.
+const FORMAT: &str = "streamforge-template-pack";
+
+/// Bumped whenever the pack layout changes incompatibly.
+const FORMAT_VERSION: u32 = 1;
+
+/// Upper bound for `pack.json`.
+const MAX_PACK_BYTES: u64 = 16 * 1024 * 1024;
+
+// ---------------------------------------------------------------------------
+// Format
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AlertType {
+    Follow,
+    Subscribe,
+    Cheer,
+    Raid,
+    Donation,
+    Custom,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum AnimationIn {
+    #[serde(rename = "slideIn")]
+    Slide,
+    #[serde(rename = "fadeIn")]
+    Fade,
+    #[serde(rename = "bounceIn")]
+    Bounce,
+    #[serde(rename = "popIn")]
+    Pop,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum AnimationOut {
+    #[serde(rename = "slideOut")]
+    Slide,
+    #[serde(rename = "fadeOut")]
+    Fade,
+    #[serde(rename = "bounceOut")]
+    Bounce,
+    #[serde(rename = "popOut")]
+    Pop,
+}
+
+/// An alert configuration as stored in `alert_templates.template_data`;
+/// mirrors the `alerts` columns from migrations 002 and 004. Defaults match
+/// `ALERT_DEFAULTS` in `server/alerts/database.js`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TemplateData {
+    #[serde(rename = "type")]
+    pub(crate) alert_type: AlertType,
+    pub(crate) name: String,
+    pub(crate) message_template: Option<String>,
+    pub(crate) duration_ms: u32,
+    pub(crate) animation_in: Option<AnimationIn>,
+    pub(crate) animation_out: Option<AnimationOut>,
+    pub(crate) sound_path: Option<String>,
+    pub(crate) sound_volume: f64,
+    pub(crate) image_path: Option<String>,
+    pub(crate) font_family: String,
+    pub(crate) font_size: u32,
+    pub(crate) text_color: String,
+    pub(crate) bg_color: Option<String>,
+    pub(crate) custom_css: Option<String>,
+    pub(crate) min_amount: Option<f64>,
+    #[serde(with = "int_bool")]
+    pub(crate) tts_enabled: bool,
+    pub(crate) tts_voice: Option<String>,
+    pub(crate) tts_rate: f64,
+    pub(crate) tts_pitch: f64,
+    pub(crate) tts_volume: f64,
+}
+
+impl Default for TemplateData {
+    fn default() -> Self {
+        Self {
+            alert_type: AlertType::Follow,
+            name: String::new(),
+            message_template: None,
+            duration_ms: 5000,
+            animation_in: Some(AnimationIn::Fade),
+            animation_out: Some(AnimationOut::Fade),
+            sound_path: None,
+            sound_volume: 0.8,
+            image_path: None,
+            font_family: "Arial".to_string(),
+            font_size: 24,
+            text_color: "#FFFFFF".to_string(),
+            bg_color: None,
+            custom_css: None,
+            min_amount: None,
+            tts_enabled: false,
+            tts_voice: None,
+            tts_rate: 1.0,
+            tts_pitch: 1.0,
+            tts_volume: 1.0,
+        }
+    }
+}
+
+impl TemplateData {
+    fn media_path(&self, kind: MediaKind) -> Option<&str> {
+        match kind {
+            MediaKind::Sound => self.sound_path.as_deref(),
+            MediaKind::Image => self.image_path.as_deref(),
+        }
+    }
+
+    fn media_path_mut(&mut self, kind: MediaKind) -> &mut Option<String> {
+        match kind {
+            MediaKind::Sound => &mut self.sound_path,
+            MediaKind::Image => &mut self.image_path,
+        }
+    }
+}
+
+/// SQLite-style 0/1 flags; `true`/`false` are accepted too.
+mod int_bool {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(u8::from(*value))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<bool, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Flag {
+            Bool(bool),
+            Int(i64),
+        }
+        match Flag::deserialize(deserializer)? {
+            Flag::Bool(value) => Ok(value),
+            Flag::Int(value) => Ok(value != 0),
+        }
+    }
+}
+
+/// One template in a pack, shaped like the dashboard's template export.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct PackTemplate {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) author: Option<String>,
+    pub(crate) template_data: TemplateData,
+}
+
+/// Contents of `pack.json`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct TemplatePack {
+    pub(crate) format: String,
+    pub(crate) format_version: u32,
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    /// Default author for templates that don't name one.
+    #[serde(default)]
+    pub(crate) author: Option<String>,
+    pub(crate) templates: Vec<PackTemplate>,
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PackIssue {
+    /// Index into `templates`, or `None` for pack-level problems.
+    pub(crate) template: Option<usize>,
+    pub(crate) field: String,
+    pub(crate) message: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PackReport {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) author: Option<String>,
+    pub(crate) template_names: Vec<String>,
+    pub(crate) media_files: usize,
+    /// Empty if the pack is valid.
+    pub(crate) issues: Vec<PackIssue>,
+}
+
+fn check_range(
+    issues: &mut Vec<PackIssue>,
+    template: usize,
+    field: &str,
+    value: f64,
+    min: f64,
+    max: f64,
+) {
+    if !value.is_finite() || value < min || value > max {
+        issues.push(PackIssue {
+            template: Some(template),
+            field: field.to_string(),
+            message: format!("must be between {} and {}", min, max),
+        });
+    }
+}
+
+/// Checks a template's values against the limits the alert editor
+/// enforces.
+fn validate_data(issues: &mut Vec<PackIssue>, index: usize, data: &TemplateData) {
+    let mut issue = |field: &str, message: &str| {
+        issues.push(PackIssue {
+            template: Some(index),
+            field: field.to_string(),
+            message: message.to_string(),
+        })
+    };
+    if data.name.trim().is_empty() {
+        issue("template_data.name", "must not be empty");
+    }
+    if data.text_color.trim().is_empty() {
+        issue("template_data.text_color", "must not be empty");
+    }
+    if data.font_family.trim().is_empty() {
+        issue("template_data.font_family", "must not be empty");
+    }
+    if data
+        .min_amount
+        .is_some_and(|min| !min.is_finite() || min < 0.0)
+    {
+        issue("template_data.min_amount", "must be a positive number");
+    }
+
+    let ranges = [
+        (
+            "template_data.duration_ms",
+            f64::from(data.duration_ms),
+            1000.0,
+            60000.0,
+        ),
+        (
+            "template_data.font_size",
+            f64::from(data.font_size),
+            12.0,
+            200.0,
+        ),
+        ("template_data.sound_volume", data.sound_volume, 0.0, 1.0),
+        ("template_data.tts_rate", data.tts_rate, 0.5, 2.0),
+        ("template_data.tts_pitch", data.tts_pitch, 0.5, 2.0),
+        ("template_data.tts_volume", data.tts_volume, 0.0, 1.0),
+    ];
+    for (field, value, min, max) in ranges {
+        check_range(issues, index, field, value, min, max);
+    }
+}
+
+/// The pack-relative file a media reference points at, if it is one.
+fn pack_media_name(kind: MediaKind, reference: &str) -> Option<&str> {
+    reference
+        .strip_prefix(MEDIA_PREFIX)?
+        .strip_prefix('/')?
+        .strip_prefix(kind.subdir())?
+        .strip_prefix('/')
+}
+
+fn is_remote(reference: &str) -> bool {
+    reference.starts_with("https://") || reference.starts_with("http://")
+}
+
+/// Validates `pack`; `has_file` tells whether a pack-relative path exists.
+pub(crate) fn validate(pack: &TemplatePack, has_file: &dyn Fn(&str) -> bool) -> Vec<PackIssue> {
+    let mut issues = Vec::new();
+    let mut pack_issue = |field: &str, message: String| {
+        issues.push(PackIssue {
+            template: None,
+            field: field.to_string(),
+            message,
+        })
+    };
+    if pack.format != FORMAT {
+        pack_issue("format", format!("must be {:?}", FORMAT));
+    }
+    if pack.format_version == 0 || pack.format_version > FORMAT_VERSION {
+        pack_issue(
+            "format_version",
+            format!(
+                "version {} is not supported (up to {})",
+                pack.format_version, FORMAT_VERSION
+            ),
+        );
+    }
+    if pack.name.trim().is_empty() {
+        pack_issue("name", "must not be empty".to_string());
+    }
+    if pack.templates.is_empty() {
+        pack_issue(
+            "templates",
+            "must contain at least one template".to_string(),
+        );
+    }
+
+    for (index, template) in pack.templates.iter().enumerate() {
+        if template.name.trim().is_empty() {
+            issues.push(PackIssue {
+                template: Some(index),
+                field: "name".to_string(),
+                message: "must not be empty".to_string(),
+            });
+        }
+        validate_data(&mut issues, index, &template.template_data);
+
+        for kind in MediaKind::ALL {
+            let Some(reference) = template.template_data.media_path(kind) else {
+                continue;
+            };
+            let message = match pack_media_name(kind, reference) {
+                _ if reference.is_empty() || is_remote(reference) => continue,
+                Some(name) if name.is_empty() || name.contains(['/', '\\']) => {
+                    "must name a file directly inside the media folder".to_string()
+                }
+                Some(_) if !kind.accepts(reference) => format!(
+                    "unsupported file type (allowed: {})",
+                    kind.extensions().join(", ")
+                ),
+                Some(_) if !has_file(reference) => {
+                    format!("{} is missing from the pack", reference)
+                }
+                Some(_) => continue,
+                None => format!(
+                    "must be null, an http(s) URL or a path under {}/{}/",
+                    MEDIA_PREFIX,
+                    kind.subdir()
+                ),
+            };
+            issues.push(PackIssue {
+                template: Some(index),
+                field: format!("template_data.{}", kind.column()),
+                message,
+            });
+        }
+    }
+    issues
+}
+
+// ---------------------------------------------------------------------------
+// Reading
+// ---------------------------------------------------------------------------
+
+/// A pack archive, or an unpacked pack folder.
+enum PackSource {
+    Archive(ZipArchive<File>),
+    Folder(PathBuf),
+}
+
+impl PackSource {
+    fn open(path: &Path) -> Result<Self, String> {
+        if path.is_dir() {
+            return Ok(Self::Folder(path.to_path_buf()));
+        }
+        let file =
+            File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        ZipArchive::new(file)
+            .map(Self::Archive)
+            .map_err(|e| format!("{} is not a template pack: {}", path.display(), e))
+    }
+
+    fn has_file(&self, name: &str) -> bool {
+        match self {
+            Self::Archive(zip) => zip.index_for_name(name).is_some(),
+            Self::Folder(dir) => dir.join(name).is_file(),
+        }
+    }
+
+    fn read(&mut self, name: &str, limit: u64) -> Result<Vec<u8>, String> {
+        let (size, reader): (u64, Box<dyn Read + '_>) = match self {
+            Self::Archive(zip) => {
+                let entry = zip
+                    .by_name(name)
+                    .map_err(|_| format!("The pack has no {}", name))?;
+                (entry.size(), Box::new(entry))
+            }
+            Self::Folder(dir) => {
+                let path = dir.join(name);
+                let file = File::open(&path)
+                    .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+                let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+                (size, Box::new(file))
+            }
+        };
+        if size > limit {
+            return Err(format!("{} is too large", name));
+        }
+        let mut bytes = Vec::new();
+        reader
+            .take(limit)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        Ok(bytes)
+    }
+}
+
+/// Media files `pack` references, as pack-relative paths.
+fn media_files(pack: &TemplatePack) -> Vec<(MediaKind, String)> {
+    let mut files: Vec<(MediaKind, String)> = Vec::new();
+    for template in &pack.templates {
+        for kind in MediaKind::ALL {
+            if let Some(reference) = template.template_data.media_path(kind) {
+                let entry = (kind, reference.to_string());
+                if pack_media_name(kind, reference).is_some() && !files.contains(&entry) {
+                    files.push(entry);
+                }
+            }
+        }
+    }
+    files
+}
+
+fn read_pack(source: &mut PackSource) -> Result<TemplatePack, String> {
+    serde_json::from_slice(&source.read(PACK_FILE, MAX_PACK_BYTES)?)
+        .map_err(|e| format!("Invalid {}: {}", PACK_FILE, e))
+}
+
+/// Opens and validates the pack at `path`.
+pub(crate) fn inspect(path: &Path) -> Result<PackReport, String> {
+    let mut source = PackSource::open(path)?;
+    let pack = read_pack(&mut source)?;
+    let issues = validate(&pack, &|name| source.has_file(name));
+    Ok(report(&pack, issues))
+}
+
+fn report(pack: &TemplatePack, issues: Vec<PackIssue>) -> PackReport {
+    PackReport {
+        name: pack.name.clone(),
+        description: pack.description.clone(),
+        author: pack.author.clone(),
+        template_names: pack.templates.iter().map(|t| t.name.clone()).collect(),
+        media_files: media_files(pack).len(),
+        issues,
+    }
+}
+
+/// Opens the pack at `path`, failing with the first few problems if it is
+/// invalid.
+fn open_valid(path: &Path) -> Result<(PackSource, TemplatePack), String> {
+    let mut source = PackSource::open(path)?;
+    let pack = read_pack(&mut source)?;
+    let issues = validate(&pack, &|name| source.has_file(name));
+    if !issues.is_empty() {
+        let summary: Vec<String> = issues
+            .iter()
+            .take(5)
+            .map(|issue| match issue.template {
+                Some(index) => format!("template {}: {} {}", index + 1, issue.field, issue.message),
+                None => format!("{} {}", issue.field, issue.message),
+            })
+            .collect();
+        return Err(format!("Invalid template pack: {}", summary.join("; ")));
+    }
+    Ok((source, pack))
+}
+
+// ---------------------------------------------------------------------------
+// Pack / Unpack
+// ---------------------------------------------------------------------------
+
+fn write_archive(
+    dest: &Path,
+    pack: &TemplatePack,
+    media: &mut dyn FnMut(&str) -> Result<Vec<u8>, String>,
+) -> Result<(), String> {
+    let file =
+        File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let zip_error = |e: zip::result::ZipError| format!("Failed to write the pack: {}", e);
+    let io_error = |e: std::io::Error| format!("Failed to write the pack: {}", e);
+
+    let json = serde_json::to_vec_pretty(pack)
+        .map_err(|e| format!("Failed to serialize {}: {}", PACK_FILE, e))?;
+    zip.start_file(
+        PACK_FILE,
+        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+    )
+    .map_err(zip_error)?;
+    zip.write_all(&json).map_err(io_error)?;
+
+    // Audio and images are already compressed
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for (_, name) in media_files(pack) {
+        let bytes = media(&name)?;
+        zip.start_file(name.as_str(), stored).map_err(zip_error)?;
+        zip.write_all(&bytes).map_err(io_error)?;
+    }
+
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+/// Zips the pack folder `dir` into `dest` after validating it. Only the
+/// files the templates reference are included.
+pub(crate) fn pack(dir: &Path, dest: &Path) -> Result<PackReport, String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a folder", dir.display()));
+    }
+    let (mut source, pack) = open_valid(dir)?;
+    write_archive(dest, &pack, &mut |name| source.read(name, MAX_MEDIA_BYTES)).inspect_err(
+        |_| {
+            let _ = fs::remove_file(dest);
+        },
+    )?;
+    println!("[Tauri] Template pack written to {}", dest.display());
+    Ok(report(&pack, Vec::new()))
+}
+
+/// Extracts the pack archive at `path` into the folder `dest_dir` for
+/// editing.
+pub(crate) fn unpack(path: &Path, dest_dir: &Path) -> Result<PackReport, String> {
+    let (mut source, pack) = open_valid(path)?;
+    let mut files = vec![PACK_FILE.to_string()];
+    files.extend(media_files(&pack).into_iter().map(|(_, name)| name));
+
+    for name in files {
+        let limit = if name == PACK_FILE {
+            MAX_PACK_BYTES
+        } else {
+            MAX_MEDIA_BYTES
+        };
+        let bytes = source.read(&name, limit)?;
+        // Validation guarantees `name` stays inside `dest_dir`
+        let target = dest_dir.join(&name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&target, bytes)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    }
+    println!("[Tauri] Template pack unpacked to {}", dest_dir.display());
+    Ok(report(&pack, Vec::new()))
+}
+
+/// Metadata for `export`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PackInfo {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) author: Option<String>,
+}
+
+/// Packs the stored templates `template_ids` with their media into `dest`.
+pub(crate) fn export(
+    app: &AppHandle,
+    template_ids: &[String],
+    info: PackInfo,
+    dest: &Path,
+) -> Result<PackReport, String> {
+    let conn = db::open(app)?;
+    let mut templates = Vec::new();
+    let mut sources: Vec<(String, PathBuf)> = Vec::new();
+
+    for id in template_ids {
+        let row = db::select_rows(&conn, "SELECT * FROM alert_templates WHERE id = ?1", [id])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Template {} not found", id))?;
+        let text = |column: &str| row.get(column).and_then(|v| v.as_str()).map(str::to_string);
+        let name = text("name").unwrap_or_default();
+        let mut data: TemplateData =
+            serde_json::from_str(&text("template_data").unwrap_or_default())
+                .map_err(|e| format!("Template {:?} has invalid data: {}", name, e))?;
+
+        // Bundle local media under the pack's media folder
+        for kind in MediaKind::ALL {
+            let slot = data.media_path_mut(kind);
+            let Some(reference) = slot.clone() else {
+                continue;
+            };
+            if is_remote(&reference) {
+                continue;
+            }
+            let Some(path) = media::resolve(app, kind, &reference) else {
+                eprintln!(
+                    "[Tauri] Dropping missing media {} from template {:?}",
+                    reference, name
+                );
+                *slot = None;
+                continue;
+            };
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let pack_path = format!("{}/{}/{}", MEDIA_PREFIX, kind.subdir(), file_name);
+            if !sources.iter().any(|(existing, _)| *existing == pack_path) {
+                sources.push((pack_path.clone(), path));
+            }
+            *slot = Some(pack_path);
+        }
+
+        templates.push(PackTemplate {
+            name,
+            description: text("description").filter(|d| !d.is_empty()),
+            author: text("author"),
+            template_data: data,
+        });
+    }
+
+    let pack = TemplatePack {
+        format: FORMAT.to_string(),
+        format_version: FORMAT_VERSION,
+        name: info.name,
+        description: info.description,
+        author: info.author,
+        templates,
+    };
+    let issues = validate(&pack, &|name| sources.iter().any(|(path, _)| path == name));
+    if !issues.is_empty() {
+        return Ok(report(&pack, issues));
+    }
+
+    write_archive(dest, &pack, &mut |name| {
+        let (_, path) = sources
+            .iter()
+            .find(|(pack_path, _)| pack_path == name)
+            .ok_or_else(|| format!("{} is missing", name))?;
+        fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    })
+    .inspect_err(|_| {
+        let _ = fs::remove_file(dest);
+    })?;
+    println!("[Tauri] Template pack exported to {}", dest.display());
+    Ok(report(&pack, Vec::new()))
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportedTemplate {
+    pub(crate) id: String,
+    pub(crate) name: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PackImportReport {
+    pub(crate) pack: String,
+    pub(crate) templates: Vec<ImportedTemplate>,
+    /// Media files newly stored in the uploads folder.
+    pub(crate) media_stored: usize,
+}
+
+/// Validates the pack at `path` and adds its templates as user templates,
+/// copying its media into the uploads folder.
+pub(crate) fn import(app: &AppHandle, path: &Path) -> Result<PackImportReport, String> {
+    let (mut source, mut pack) = open_valid(path)?;
+
+    let mut created: Vec<PathBuf> = Vec::new();
+    let mut relocated: Vec<(String, String)> = Vec::new();
+    let stored = media_files(&pack).into_iter().try_for_each(|(kind, name)| {
+        let bytes = source.read(&name, MAX_MEDIA_BYTES)?;
+        let file_name = name.rsplit('/').next().unwrap_or_default();
+        let stored = media::store(app, kind, file_name, &bytes)?;
+        if stored.created {
+            created.push(stored.path);
+        }
+        relocated.push((name, stored.url));
+        Ok::<(), String>(())
+    });
+
+    let result = stored.and_then(|_| {
+        for template in &mut pack.templates {
+            for kind in MediaKind::ALL {
+                let slot = template.template_data.media_path_mut(kind);
+                if let Some((_, url)) = relocated
+                    .iter()
+                    .find(|(name, _)| slot.as_deref() == Some(name.as_str()))
+                {
+                    *slot = Some(url.clone());
+                }
+            }
+        }
+        insert_templates(app, &pack)
+    });
+
+    match result {
+        Ok(templates) => {
+            println!(
+                "[Tauri] Imported {} template(s) from pack {:?}",
+                templates.len(),
+                pack.name
+            );
+            Ok(PackImportReport {
+                pack: pack.name,
+                templates,
+                media_stored: created.len(),
+            })
+        }
+        Err(e) => {
+            for path in created {
+                let _ = fs::remove_file(path);
+            }
+            Err(e)
+        }
+    }
+}
+
+fn insert_templates(app: &AppHandle, pack: &TemplatePack) -> Result<Vec<ImportedTemplate>, String> {
+    let mut conn = db::open(app)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start the import: {}", e))?;
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    let mut imported = Vec::new();
+    for template in &pack.templates {
+        let id = uuid::Uuid::new_v4().to_string();
+        let data = serde_json::to_string(&template.template_data)
+            .map_err(|e| format!("Failed to serialize template {:?}: {}", template.name, e))?;
+        tx.execute(
+            "INSERT INTO alert_templates (id, name, description, author, template_data, is_builtin, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?6)",
+            (
+                &id,
+                &template.name,
+                template.description.as_deref().unwrap_or(""),
+                template
+                    .author
+                    .as_deref()
+                    .or(pack.author.as_deref())
+                    .unwrap_or("User"),
+                &data,
+                &now,
+            ),
+        )
+        .map_err(|e| format!("Failed to save template {:?}: {}", template.name, e))?;
+        imported.push(ImportedTemplate {
+            id,
+            name: template.name.clone(),
+        });
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit the import: {}", e))?;
+    Ok(imported)
+}
+
+// ---------------------------------------------------------------------------
+// File Dialogs
+// ---------------------------------------------------------------------------
+
+/// Asks for a pack archive to open. `None` if cancelled.
+pub(crate) fn pick_pack(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    app.dialog()
+        .file()
+        .set_title("Open template pack")
+        .add_filter("StreamForge template pack", &["zip"])
+        .blocking_pick_file()
+        .map(|file| {
+            file.into_path()
+                .map_err(|e| format!("Unsupported file selection: {}", e))
+        })
+        .transpose()
+}
+
+/// Asks where to save a pack archive. `None` if cancelled.
+pub(crate) fn pick_destination(app: &AppHandle, name: &str) -> Result<Option<PathBuf>, String> {
+    let file_name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    app.dialog()
+        .file()
+        .set_title("Save template pack")
+        .add_filter("StreamForge template pack", &["zip"])
+        .set_file_name(format!("{}.zip", file_name.trim_matches('-')))
+        .blocking_save_file()
+        .map(|file| {
+            file.into_path()
+                .map_err(|e| format!("Unsupported file selection: {}", e))
+        })
+        .transpose()
+}
@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{
+    AppHandle, Listener, Manager, WebviewWindow, WebviewWindowBuilder, Window, WindowEvent, Wry,
+};
+
+use crate::sidecar::status::{current_status, SidecarStatus, EVENT_STATUS};
+use crate::sidecar::{self, SidecarInfo, EVENT_CLIENTS};
+
+const TRAY_ID: &str = "main";
+const MAIN_WINDOW: &str = "main";
+
+const MENU_TOGGLE_WINDOW: &str = "toggle-window";
+const MENU_START_SERVER: &str = "start-server";
+const MENU_STOP_SERVER: &str = "stop-server";
+const MENU_QUIT: &str = "quit";
+
+/// Namespace used by the StreamForge window itself. Everything else is an
+/// overlay loaded as an OBS browser source.
+const DASHBOARD_NAMESPACE: &str = "/dashboard";
+
+/// Colour of the badge drawn on the icon while overlays are connected.
+const LIVE_BADGE_RGB: [u8; 3] = [0x2e, 0xcc, 0x71];
+
+// ---------------------------------------------------------------------------
+// Managed State
+// ---------------------------------------------------------------------------
+
+/// Menu items whose label or enabled state follows the app state.
+struct TrayMenu {
+    status: MenuItem<Wry>,
+    overlays: MenuItem<Wry>,
+    toggle_window: MenuItem<Wry>,
+    start_server: MenuItem<Wry>,
+    stop_server: MenuItem<Wry>,
+    icons: Option<TrayIcons>,
+    /// Variant currently shown, so the icon is only swapped on change.
+    shown: Mutex<Option<Indicator>>,
+}
+
+/// What the tray icon is signalling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Indicator {
+    /// The server isn't running (or isn't usable).
+    Down,
+    /// The server is up but no overlay is connected.
+    Idle,
+    /// At least one overlay is connected.
+    Live,
+}
+
+/// Icon variants derived from the app icon.
+struct TrayIcons {
+    down: Image<'static>,
+    idle: Image<'static>,
+    live: Image<'static>,
+}
+
+impl TrayIcons {
+    fn from_app_icon(icon: &Image<'_>) -> Self {
+        let (width, height) = (icon.width(), icon.height());
+        Self {
+            down: Image::new_owned(greyed_out(icon.rgba()), width, height),
+            idle: Image::new_owned(icon.rgba().to_vec(), width, height),
+            live: Image::new_owned(with_badge(icon.rgba(), width, height), width, height),
+        }
+    }
+
+    fn get(&self, indicator: Indicator) -> &Image<'static> {
+        match indicator {
+            Indicator::Down => &self.down,
+            Indicator::Idle => &self.idle,
+            Indicator::Live => &self.live,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Setup
+// ---------------------------------------------------------------------------
+
+/// Builds the tray icon and keeps it in sync with the sidecar status.
+pub(crate) fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let status = MenuItem::with_id(app, "status", "", false, None::<&str>)?;
+    let overlays = MenuItem::with_id(app, "overlays", "", false, None::<&str>)?;
+    let toggle_label = if app.get_webview_window(MAIN_WINDOW).is_some() {
+        "Hide Window"
+    } else {
+        "Show Window"
+    };
+    let toggle_window =
+        MenuItem::with_id(app, MENU_TOGGLE_WINDOW, toggle_label, true, None::<&str>)?;
+    let start_server =
+        MenuItem::with_id(app, MENU_START_SERVER, "Start Server", false, None::<&str>)?;
+    let stop_server = MenuItem::with_id(app, MENU_STOP_SERVER, "Stop Server", false, None::<&str>)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, "Quit StreamForge", true, None::<&str>)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status,
+            &overlays,
+            &PredefinedMenuItem::separator(app)?,
+            &toggle_window,
+            &PredefinedMenuItem::separator(app)?,
+            &start_server,
+            &stop_server,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            MENU_TOGGLE_WINDOW => toggle_main_window(app),
+            MENU_START_SERVER => {
+                let app = app.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = sidecar::start_stopped_sidecar(&app) {
+                        eprintln!("[Tauri] Failed to start sidecar from tray: {}", e);
+                    }
+                });
+            }
+            MENU_STOP_SERVER => {
+                // Stopping waits for the sidecar to exit -- keep the UI responsive
+                let app = app.clone();
+                std::thread::spawn(move || sidecar::stop_sidecar_on_request(&app));
+            }
+            MENU_QUIT => app.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    let icons = app.default_window_icon().map(TrayIcons::from_app_icon);
+    if let Some(icons) = &icons {
+        builder = builder.icon(icons.get(Indicator::Down).clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayMenu {
+        status,
+        overlays,
+        toggle_window,
+        start_server,
+        stop_server,
+        icons,
+        shown: Mutex::new(Some(Indicator::Down)),
+    });
+
+    for event in [EVENT_STATUS, EVENT_CLIENTS] {
+        let handle = app.clone();
+        app.listen_any(event, move |_| refresh(&handle));
+    }
+    refresh(app);
+
+    Ok(())
+}
+
+/// Hides the main window instead of closing it, so the server keeps running
+/// in the background. Quitting goes through the tray menu.
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::CloseRequested { api, .. } = event {
+        if window.label() == MAIN_WINDOW {
+            api.prevent_close();
+            set_main_window_visible(window.app_handle(), false);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Window Visibility
+// ---------------------------------------------------------------------------
+
+fn toggle_main_window(app: &AppHandle) {
+    let visible = app
+        .get_webview_window(MAIN_WINDOW)
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false);
+    set_main_window_visible(app, !visible);
+}
+
+/// Shows, unminimizes and focuses the main window.
+pub(crate) fn show_main_window(app: &AppHandle) {
+    set_main_window_visible(app, true);
+}
+
+/// Creates the main window from `tauri.conf.json`. It isn't created
+/// automatically so headless mode can start without one.
+pub(crate) fn create_main_window(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == MAIN_WINDOW)
+        .cloned()
+        .unwrap_or_default();
+    WebviewWindowBuilder::from_config(app, &config)?.build()
+}
+
+fn set_main_window_visible(app: &AppHandle, visible: bool) {
+    let window = match app.get_webview_window(MAIN_WINDOW) {
+        Some(window) => window,
+        // Headless mode: open the dashboard on first request
+        None if visible => match create_main_window(app) {
+            Ok(window) => window,
+            Err(e) => {
+                eprintln!("[Tauri] Failed to create main window: {}", e);
+                return;
+            }
+        },
+        None => return,
+    };
+
+    let result = if visible {
+        window
+            .unminimize()
+            .and_then(|_| window.show())
+            .and_then(|_| window.set_focus())
+    } else {
+        window.hide()
+    };
+    if let Err(e) = result {
+        eprintln!("[Tauri] Failed to change window visibility: {}", e);
+        return;
+    }
+
+    let label = if visible {
+        "Hide Window"
+    } else {
+        "Show Window"
+    };
+    let _ = app.state::<TrayMenu>().toggle_window.set_text(label);
+}
+
+// ---------------------------------------------------------------------------
+// Status Display
+// ---------------------------------------------------------------------------
+
+/// Updates the icon, tooltip and menu from the current sidecar status and
+/// overlay connections.
+fn refresh(app: &AppHandle) {
+    let status = current_status(app);
+    let clients = app
+        .state::<SidecarInfo>()
+        .0
+        .lock()
+        .map(|info| info.clients.clone())
+        .unwrap_or_default();
+    let overlays = overlay_count(&clients);
+
+    let indicator = match status {
+        SidecarStatus::Ready { .. } if overlays > 0 => Indicator::Live,
+        SidecarStatus::Ready { .. } => Indicator::Idle,
+        _ => Indicator::Down,
+    };
+    let summary = describe(&status);
+    let overlay_summary = match overlays {
+        0 => "no overlays connected".to_string(),
+        1 => "1 overlay connected".to_string(),
+        n => format!("{} overlays connected", n),
+    };
+    let menu = app.state::<TrayMenu>();
+
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let tooltip = if indicator == Indicator::Down {
+            format!("StreamForge — {}", summary)
+        } else {
+            format!("StreamForge — {}, {}", summary, overlay_summary)
+        };
+        let _ = tray.set_tooltip(Some(tooltip));
+
+        if let (Some(icons), Ok(mut shown)) = (&menu.icons, menu.shown.lock()) {
+            if *shown != Some(indicator)
+                && tray.set_icon(Some(icons.get(indicator).clone())).is_ok()
+            {
+                *shown = Some(indicator);
+            }
+        }
+    }
+
+    // A process is only running in these states; everything else can be
+    // (re)started.
+    let running = matches!(
+        status,
+        SidecarStatus::Spawning
+            | SidecarStatus::Ready { .. }
+            | SidecarStatus::Unhealthy { .. }
+            | SidecarStatus::Incompatible { .. }
+    );
+    let _ = menu.status.set_text(format!("Server: {}", summary));
+    let _ = menu
+        .overlays
+        .set_text(format!("Overlays: {}", overlay_breakdown(&clients)));
+    let _ = menu.start_server.set_enabled(!running);
+    let _ = menu.stop_server.set_enabled(running);
+}
+
+fn describe(status: &SidecarStatus) -> String {
+    match status {
+        SidecarStatus::NotStarted => "not started".to_string(),
+        SidecarStatus::Spawning => "starting...".to_string(),
+        SidecarStatus::Ready { port } => format!("running on port {}", port),
+        SidecarStatus::Unhealthy { port, .. } => format!("not responding on port {}", port),
+        SidecarStatus::Stopped => "stopped".to_string(),
+        SidecarStatus::Exited { .. } => "exited unexpectedly".to_string(),
+        SidecarStatus::FailedToSpawn { .. } => "failed to start".to_string(),
+        SidecarStatus::Incompatible { .. } => "incompatible version".to_string(),
+    }
+}
+
+/// Connected overlay clients, i.e. every namespace except the dashboard.
+fn overlay_count(clients: &BTreeMap<String, u32>) -> u32 {
+    clients
+        .iter()
+        .filter(|(namespace, _)| namespace.as_str() != DASHBOARD_NAMESPACE)
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// "2 (alerts 1, chat 1)" style summary for the menu.
+fn overlay_breakdown(clients: &BTreeMap<String, u32>) -> String {
+    let parts: Vec<String> = clients
+        .iter()
+        .filter(|(namespace, count)| namespace.as_str() != DASHBOARD_NAMESPACE && **count > 0)
+        .map(|(namespace, count)| format!("{} {}", namespace.trim_start_matches('/'), count))
+        .collect();
+
+    if parts.is_empty() {
+        "none connected".to_string()
+    } else {
+        format!("{} ({})", overlay_count(clients), parts.join(", "))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Icon Variants
+// ---------------------------------------------------------------------------
+
+/// Desaturates and fades the icon for the "server down" state.
+fn greyed_out(rgba: &[u8]) -> Vec<u8> {
+    rgba.chunks_exact(4)
+        .flat_map(|px| {
+            let luma =
+                (u32::from(px[0]) * 299 + u32::from(px[1]) * 587 + u32::from(px[2]) * 114) / 1000;
+            let luma = luma as u8;
+            [luma, luma, luma, px[3] / 2]
+        })
+        .collect()
+}
+
+/// Draws a filled dot in the bottom-right corner for the "live" state.
+fn with_badge(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut out = rgba.to_vec();
+    let radius = (width.min(height) as f32) * 0.22;
+    let center_x = width as f32 - radius - 1.0;
+    let center_y = height as f32 - radius - 1.0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                out[i..i + 3].copy_from_slice(&LIVE_BADGE_RGB);
+                out[i + 3] = 0xff;
+            }
+        }
+    }
+    out
+}
@@ -52,11 +52,18 @@ export interface Alert {
   variations?: AlertVariation[];
 }
 
+/**
+ * Variations match on an expression such as `amount between 100 and 499`
+ * (see `server/alerts/conditions.js`). The older fixed condition types are
+ * still understood by the server but converted to expressions on upgrade.
+ */
+export type ConditionType = "expression" | "tier" | "amount" | "custom";
+
 export interface AlertVariation {
   id: string;
   parent_alert_id: string;
   name: string;
-  condition_type: "tier" | "amount" | "custom";
+  condition_type: ConditionType;
   condition_value: string;
   message_template: string | null;
   sound_path: string | null;
@@ -74,7 +81,7 @@ export interface AlertVariation {
 /** Fields accepted when creating or updating a variation. */
 export interface AlertVariationInput {
   name?: string;
-  condition_type?: "tier" | "amount" | "custom";
+  condition_type?: ConditionType;
   condition_value?: string;
   message_template?: string | null;
   sound_path?: string | null;
@@ -0,0 +1,52 @@
+/**
+ * Alert Backlog
+ *
+ * Wrappers around the Tauri commands for the durable copy of the alert
+ * queue the shell keeps in the database. Alerts still pending when the
+ * server stops (or crashes) are replayed to it on the next start; purging
+ * them prevents that.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { AlertType } from "./alertApi";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export type QueueState = "enqueued" | "started" | "done" | "skipped";
+
+export interface BacklogEntry {
+  id: string;
+  alertType: AlertType | string;
+  username: string;
+  /** Alert channel the alert was queued on */
+  channel: string;
+  status: QueueState;
+  /** 1 unless the alert has been replayed; given up on after 3 */
+  attempts: number;
+  enqueuedAt: string;
+  updatedAt: string;
+  /** The alert object as the server queued it */
+  alert: Record<string, unknown>;
+}
+
+export interface AlertBacklog {
+  /** Alerts that will be replayed to the next server, oldest first */
+  pending: BacklogEntry[];
+  /** Recently finished alerts, newest first; empty unless requested */
+  finished: BacklogEntry[];
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+export async function listAlertBacklog(includeFinished = false): Promise<AlertBacklog> {
+  return invoke<AlertBacklog>("list_alert_backlog", { includeFinished });
+}
+
+/** Returns the number of entries removed. */
+export async function purgeAlertBacklog(includeFinished = false): Promise<number> {
+  return invoke<number>("purge_alert_backlog", { includeFinished });
+}
@@ -0,0 +1,51 @@
+/**
+ * Alert Channels
+ *
+ * Wrappers around the Tauri commands for named alert channels. Each
+ * channel has its own queue and pause state, and plays the alert types
+ * routed to it; types no channel lists play on "default". An overlay picks
+ * its channel with `?channel=<id>` on the browser source URL.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { AlertType } from "./alertApi";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export interface AlertChannel {
+  /** Lowercase letters, digits, "-" and "_"; used in `?channel=` */
+  id: string;
+  name: string;
+  alertTypes: (AlertType | string)[];
+  /** "default" can't be disabled */
+  enabled: boolean;
+  /** Set by the shell; ignored when saving */
+  createdAt?: string;
+  updatedAt?: string;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/** Lists the channels, "default" first. */
+export async function listAlertChannels(): Promise<AlertChannel[]> {
+  return invoke<AlertChannel[]>("list_alert_channels");
+}
+
+/** Creates the channel, or updates the one with the same ID. */
+export async function saveAlertChannel(
+  channel: AlertChannel
+): Promise<AlertChannel> {
+  return invoke<AlertChannel>("save_alert_channel", { channel });
+}
+
+/**
+ * Deletes a channel; its waiting alerts move to "default". Resolves to
+ * false if there was no such channel.
+ */
+export async function deleteAlertChannel(id: string): Promise<boolean> {
+  return invoke<boolean>("delete_alert_channel", { id });
+}
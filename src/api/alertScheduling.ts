@@ -0,0 +1,93 @@
+/**
+ * Alert Scheduling
+ *
+ * Wrappers around the Tauri commands for the priority scheduler that
+ * decides the order queued alerts play in: a class per alert type, amount
+ * thresholds that override it, promotion of alerts that have waited long,
+ * and a maximum queue age after which low-priority alerts are dropped or
+ * collapsed into one summary alert.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { AlertType } from "./alertApi";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export type PriorityClass = "low" | "normal" | "high" | "urgent";
+
+export type ExpiredAction = "drop" | "collapse";
+
+export interface AmountRule {
+  /** Alert type the rule applies to; null for every type */
+  alertType: AlertType | string | null;
+  minAmount: number;
+  class: PriorityClass;
+}
+
+export interface SchedulingSettings {
+  /** Off: alerts play in arrival order */
+  enabled: boolean;
+  /** Types without a class are "normal" */
+  typeClasses: Record<string, PriorityClass>;
+  /** The matching rule with the highest threshold overrides the type's class */
+  amountRules: AmountRule[];
+  /** An alert moves up one class per this many seconds waited; null disables */
+  promoteAfterSecs: number | null;
+  /** Alerts at or below expireClass expire after this long; null disables */
+  maxAgeSecs: number | null;
+  expireClass: PriorityClass;
+  expiredAction: ExpiredAction;
+}
+
+export interface ScheduledAlert {
+  id: string;
+  alertType: string;
+  username: string;
+  /** Alert channel the alert waits on */
+  channel: string;
+  amount: number | null;
+  waitedSecs: number;
+  class: PriorityClass;
+  /** class after promotion for waiting time */
+  effectiveClass: PriorityClass;
+  expired: boolean;
+}
+
+export interface CollapsedAlert {
+  replaces: string[];
+  alert: Record<string, unknown>;
+}
+
+export interface SchedulePlan {
+  /** Alert IDs in play order */
+  order: string[];
+  drop: string[];
+  collapse: CollapsedAlert[];
+}
+
+export interface SchedulePreview {
+  enabled: boolean;
+  /** Waiting alerts, oldest first */
+  alerts: ScheduledAlert[];
+  plan: SchedulePlan;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+export async function getAlertScheduling(): Promise<SchedulingSettings> {
+  return invoke<SchedulingSettings>("get_alert_scheduling");
+}
+
+export async function setAlertScheduling(
+  settings: SchedulingSettings
+): Promise<SchedulingSettings> {
+  return invoke<SchedulingSettings>("set_alert_scheduling", { settings });
+}
+
+export async function previewAlertSchedule(): Promise<SchedulePreview> {
+  return invoke<SchedulePreview>("preview_alert_schedule");
+}
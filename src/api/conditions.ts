@@ -0,0 +1,53 @@
+/**
+ * Variation Conditions
+ *
+ * Wrapper around the Tauri command that checks variation condition
+ * expressions such as `amount between 100 and 499` or
+ * `message contains "hype" or username in ["alice", "bob"]`, and
+ * optionally evaluates them against sample event data. See
+ * `src-tauri/src/condition.rs` for the grammar.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { TemplateIssue } from "./messageTemplates";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/** Event fields a condition can test. */
+export interface ConditionEvent {
+  username?: string;
+  amount?: number | string | null;
+  message?: string | null;
+  tier?: string | null;
+  platform?: string | null;
+  type?: string;
+  custom_value?: string | null;
+  [field: string]: unknown;
+}
+
+export interface ConditionCheck {
+  valid: boolean;
+  error: TemplateIssue | null;
+  /** Variables events don't carry, which are always missing */
+  warnings: TemplateIssue[];
+  /** Variables the condition uses, in order of first use */
+  variables: string[];
+  /** Whether the sample event matches; null without one or if invalid */
+  matches: boolean | null;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+export async function checkVariationCondition(
+  condition: string,
+  event?: ConditionEvent
+): Promise<ConditionCheck> {
+  return invoke<ConditionCheck>("check_variation_condition", {
+    condition,
+    event: event ?? null,
+  });
+}
@@ -25,6 +25,13 @@ export async function getServerPort(): Promise<number> {
   return port;
 }
 
+/**
+ * Forget the cached port. Called when the sidecar restarts on a new port.
+ */
+export function resetServerPort(): void {
+  cachedPort = null;
+}
+
 /**
  * Returns the base URL for the sidecar server.
  * Must only be called after `getServerPort()` has resolved at least once.
@@ -48,6 +55,87 @@ export async function getServerUrlAsync(): Promise<string> {
   return `http://localhost:${port}`;
 }
 
+// ---------------------------------------------------------------------------
+// Sidecar Status
+// ---------------------------------------------------------------------------
+
+/** Sidecar lifecycle state, mirrored from the Rust `SidecarStatus` enum. */
+export type SidecarStatus =
+  | { state: "notStarted" }
+  | { state: "spawning" }
+  | { state: "ready"; port: number }
+  | { state: "unhealthy"; port: number; reason: string }
+  | { state: "stopped" }
+  | { state: "exited"; code: number | null; signal: number | null }
+  | { state: "failedToSpawn"; reason: string }
+  | { state: "incompatible"; reason: string };
+
+/**
+ * Fetch the current sidecar lifecycle state from Tauri.
+ * Changes are also pushed as `sidecar://status` events.
+ */
+export async function getSidecarStatus(): Promise<SidecarStatus> {
+  return invoke<SidecarStatus>("get_sidecar_status");
+}
+
+// ---------------------------------------------------------------------------
+// Sidecar Logs
+// ---------------------------------------------------------------------------
+
+export type SidecarLogLevel = "info" | "warn" | "error";
+
+/** A captured sidecar output line, mirrored from the Rust `LogEntry`. */
+export interface SidecarLogEntry {
+  /** Monotonic sequence number — pass as `since` to fetch only newer lines */
+  seq: number;
+  /** Milliseconds since the Unix epoch */
+  timestamp: number;
+  level: SidecarLogLevel;
+  stream: "stdout" | "stderr" | "shell";
+  message: string;
+}
+
+/**
+ * Fetch buffered sidecar log lines (oldest first).
+ * New lines are also pushed live as `sidecar://log` events.
+ */
+export async function getSidecarLogs(options: {
+  since?: number;
+  level?: SidecarLogLevel;
+  limit?: number;
+} = {}): Promise<SidecarLogEntry[]> {
+  return invoke<SidecarLogEntry[]>("get_sidecar_logs", options);
+}
+
+// ---------------------------------------------------------------------------
+// Launch Config
+// ---------------------------------------------------------------------------
+
+/** How the sidecar is launched, mirrored from the Rust `LaunchConfig`. */
+export interface LaunchConfig {
+  /** Port tried first; must lie within `portRange` */
+  preferredPort: number;
+  portRange: { min: number; max: number };
+  /** IP address the server binds to (`127.0.0.1` keeps it local-only) */
+  bindAddress: string;
+  /** Absolute path replacing the default data directory, or null */
+  dataDir: string | null;
+  /** Extra environment variables passed to the sidecar */
+  env: Record<string, string>;
+}
+
+export async function getLaunchConfig(): Promise<LaunchConfig> {
+  return invoke<LaunchConfig>("get_launch_config");
+}
+
+/**
+ * Validate and persist a new launch config. The sidecar restarts in the
+ * background; a `sidecar://restarted` event follows once it is back up.
+ */
+export async function setLaunchConfig(config: LaunchConfig): Promise<LaunchConfig> {
+  return invoke<LaunchConfig>("set_launch_config", { config });
+}
+
 // ---------------------------------------------------------------------------
 // Server Info
 // ---------------------------------------------------------------------------
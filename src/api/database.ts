@@ -0,0 +1,101 @@
+/**
+ * Database Maintenance
+ *
+ * Wrappers around the Tauri commands that inspect `streamforge.db`
+ * directly. They work whether or not the sidecar server is running.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+// ---------------------------------------------------------------------------
+// Migrations
+// ---------------------------------------------------------------------------
+
+/** Mirrored from the Rust `MigrationInfo`. */
+export interface MigrationInfo {
+  filename: string;
+  /** Numeric filename prefix, e.g. 5 for `005_alert_templates.sql` */
+  version: number | null;
+  /** ISO timestamp, or null while pending */
+  appliedAt: string | null;
+  /** False for migrations applied by a newer StreamForge version */
+  embedded: boolean;
+}
+
+/** Numeric prefix of the newest applied migration (0 for a fresh database). */
+export async function getSchemaVersion(): Promise<number> {
+  return invoke<number>("get_schema_version");
+}
+
+export async function listMigrations(): Promise<MigrationInfo[]> {
+  return invoke<MigrationInfo[]>("list_migrations");
+}
+
+// ---------------------------------------------------------------------------
+// Backups
+// ---------------------------------------------------------------------------
+
+export type BackupKind = "auto" | "manual" | "preRestore";
+
+/** Mirrored from the Rust `BackupInfo`. */
+export interface BackupInfo {
+  path: string;
+  fileName: string;
+  kind: BackupKind;
+  sizeBytes: number;
+  /** ISO timestamp */
+  createdAt: string;
+}
+
+/** Mirrored from the Rust `BackupSettings`. */
+export interface BackupSettings {
+  /** Back up the database every time the app starts */
+  autoBackup: boolean;
+  /** Number of automatic backups to keep (1-100) */
+  keep: number;
+}
+
+export interface IntegrityReport {
+  ok: boolean;
+  /** Problems reported by `PRAGMA integrity_check` */
+  errors: string[];
+}
+
+export interface RestoreReport {
+  restoredFrom: string;
+  /** Copy of the database as it was before the restore */
+  safetyBackup: BackupInfo;
+  /** Whether the server was running and has been started again */
+  sidecarRestarted: boolean;
+}
+
+export async function getBackupSettings(): Promise<BackupSettings> {
+  return invoke<BackupSettings>("get_backup_settings");
+}
+
+export async function setBackupSettings(settings: BackupSettings): Promise<BackupSettings> {
+  return invoke<BackupSettings>("set_backup_settings", { settings });
+}
+
+/** Take a backup of the live database (safe while the server is running). */
+export async function backupDatabase(): Promise<BackupInfo> {
+  return invoke<BackupInfo>("backup_database");
+}
+
+/** Backups in the data directory, newest first. */
+export async function listBackups(): Promise<BackupInfo[]> {
+  return invoke<BackupInfo[]>("list_backups");
+}
+
+export async function checkDatabaseIntegrity(): Promise<IntegrityReport> {
+  return invoke<IntegrityReport>("check_database_integrity");
+}
+
+/**
+ * Replace the live database with `path`, or with a file picked in a dialog
+ * when omitted. Resolves to null if the dialog was cancelled. The server
+ * is stopped during the restore and restarted afterwards.
+ */
+export async function restoreDatabase(path?: string): Promise<RestoreReport | null> {
+  return invoke<RestoreReport | null>("restore_database", { path: path ?? null });
+}
@@ -0,0 +1,68 @@
+/**
+ * Event Pipeline
+ *
+ * Wrappers around the Tauri commands for the shell's event pipeline, which
+ * turns stream events into alerts. Bursts of the same type (follow trains,
+ * mass gift subs) are coalesced into one aggregate alert such as
+ * "12 new followers: a, b, c and 9 more"; every event is still logged to
+ * the event history on its own.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { AlertType } from "./alertApi";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export interface CoalesceRule {
+  /** `{username}` is the summarized names, `{amount}` the number of events */
+  template: string;
+  /** Used for subs gifted by one viewer, with `{username}` as the gifter */
+  giftTemplate?: string | null;
+}
+
+export interface CoalescingSettings {
+  enabled: boolean;
+  /** How long a batch stays open after its first event */
+  windowMs: number;
+  /** A batch this big is flushed without waiting for the window */
+  maxBatch: number;
+  /** Names spelled out before "and N more" */
+  namesShown: number;
+  /** Keyed by event type; other types are never coalesced */
+  rules: Record<string, CoalesceRule>;
+}
+
+export interface IncomingEvent {
+  eventType: AlertType | string;
+  username: string;
+  displayName?: string | null;
+  amount?: number | null;
+  message?: string | null;
+  tier?: string | null;
+  /** Defaults to "internal" */
+  platform?: string;
+  /** Who gifted the sub, for gifted subscriptions */
+  gifter?: string | null;
+  metadata?: Record<string, unknown>;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+export async function getAlertCoalescing(): Promise<CoalescingSettings> {
+  return invoke<CoalescingSettings>("get_alert_coalescing");
+}
+
+export async function setAlertCoalescing(
+  settings: CoalescingSettings
+): Promise<CoalescingSettings> {
+  return invoke<CoalescingSettings>("set_alert_coalescing", { settings });
+}
+
+/** Resolves once the event is accepted; its alert may follow after the window. */
+export async function ingestEvent(event: IncomingEvent): Promise<void> {
+  return invoke<void>("ingest_event", { event });
+}
@@ -0,0 +1,48 @@
+/**
+ * Message Templates
+ *
+ * Wrappers around the Tauri commands that check and render alert message
+ * templates. Besides `{variable}` placeholders the grammar supports
+ * filters (`{amount|currency:USD}`, `{username|upper}`,
+ * `bit{amount|plural}`) and conditionals
+ * (`{#if amount >= 100}...{#elif ...}...{#else}...{/if}`); see
+ * `src-tauri/src/message_template.rs` for the full reference.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export interface TemplateIssue {
+  message: string;
+  /** Offsets into the template string, for highlighting */
+  start: number;
+  end: number;
+}
+
+export interface TemplateCheck {
+  valid: boolean;
+  errors: TemplateIssue[];
+  /** Variables alerts don't provide, which render empty */
+  warnings: TemplateIssue[];
+  /** Variables the template uses, in order of first use */
+  variables: string[];
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+export async function checkMessageTemplate(template: string): Promise<TemplateCheck> {
+  return invoke<TemplateCheck>("check_message_template", { template });
+}
+
+/** Render a template with sample values; rejects with the first syntax error. */
+export async function renderMessageTemplate(
+  template: string,
+  variables: Record<string, unknown>
+): Promise<string> {
+  return invoke<string>("render_message_template", { template, variables });
+}
@@ -0,0 +1,52 @@
+/**
+ * Native Notification Settings
+ *
+ * Wrappers around the Tauri commands that control which stream events
+ * raise OS notifications. Settings are stored by the Rust shell, so these
+ * work without the sidecar server running.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/** Per event type rule, mirrored from the Rust `EventRule`. */
+export interface NotificationRule {
+  muted: boolean;
+  /** Skip events whose amount (bits, viewers, money...) is below this */
+  minAmount: number | null;
+}
+
+/** Mirrored from the Rust `NotificationSettings`. */
+export interface NotificationSettings {
+  doNotDisturb: boolean;
+  /** Keyed by event type; types without a rule never notify */
+  rules: Record<string, NotificationRule>;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+export async function getNotificationSettings(): Promise<NotificationSettings> {
+  return invoke<NotificationSettings>("get_notification_settings");
+}
+
+export async function setNotificationSettings(
+  settings: NotificationSettings
+): Promise<NotificationSettings> {
+  return invoke<NotificationSettings>("set_notification_settings", { settings });
+}
+
+export async function setNotificationMuted(
+  eventType: string,
+  muted: boolean
+): Promise<NotificationSettings> {
+  return invoke<NotificationSettings>("set_notification_muted", { eventType, muted });
+}
+
+export async function setDoNotDisturb(enabled: boolean): Promise<NotificationSettings> {
+  return invoke<NotificationSettings>("set_do_not_disturb", { enabled });
+}
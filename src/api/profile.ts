@@ -0,0 +1,108 @@
+/**
+ * Profile Export / Import
+ *
+ * Wrappers around the Tauri commands that move a complete alert setup
+ * (alerts, variations, user templates, settings and their media files)
+ * between machines as a single zip archive.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export interface ProfileCounts {
+  alerts: number;
+  variations: number;
+  templates: number;
+  settings: number;
+  media: number;
+}
+
+/** Mirrored from the Rust `MediaEntry`. */
+export interface ProfileMediaEntry {
+  kind: "sound" | "image";
+  /** The `sound_path` / `image_path` value used by the exported rows */
+  reference: string;
+  archivePath: string;
+  sizeBytes: number;
+}
+
+/** Contents of the archive's `manifest.json`. */
+export interface ProfileManifest {
+  format: "streamforge-profile";
+  formatVersion: number;
+  appVersion: string;
+  schemaVersion: number;
+  /** ISO timestamp */
+  exportedAt: string;
+  counts: ProfileCounts;
+  media: ProfileMediaEntry[];
+}
+
+/**
+ * What to do with imported rows whose id (or settings key) already exists:
+ * keep the existing row, overwrite it, or import alongside under a new id.
+ */
+export type ConflictStrategy = "skip" | "replace" | "keepBoth";
+
+export interface ProfileConflict {
+  kind: "alert" | "variation" | "template" | "setting" | "media";
+  /** Row id, settings key or media reference */
+  key: string;
+  name: string | null;
+  /** Null in previews */
+  resolution: "skipped" | "replaced" | "duplicated" | "renamed" | null;
+  /** New id (duplicated rows) or media path (renamed files) */
+  newKey: string | null;
+}
+
+export interface ExportReport {
+  path: string;
+  counts: ProfileCounts;
+  warnings: string[];
+}
+
+export interface ProfilePreview {
+  path: string;
+  manifest: ProfileManifest;
+  conflicts: ProfileConflict[];
+  warnings: string[];
+}
+
+export interface ImportReport {
+  manifest: ProfileManifest;
+  imported: ProfileCounts;
+  skipped: number;
+  conflicts: ProfileConflict[];
+  warnings: string[];
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/**
+ * Export the profile to `path`, or to a location picked in a save dialog
+ * when omitted. Resolves to null if the dialog was cancelled.
+ */
+export async function exportProfile(path?: string): Promise<ExportReport | null> {
+  return invoke<ExportReport | null>("export_profile", { path: path ?? null });
+}
+
+/**
+ * Validate a profile archive and list what it would collide with, without
+ * importing anything. Opens a file picker when `path` is omitted and
+ * resolves to null if it was cancelled.
+ */
+export async function inspectProfile(path?: string): Promise<ProfilePreview | null> {
+  return invoke<ProfilePreview | null>("inspect_profile", { path: path ?? null });
+}
+
+export async function importProfile(
+  path: string,
+  strategy: ConflictStrategy = "skip"
+): Promise<ImportReport> {
+  return invoke<ImportReport>("import_profile", { path, strategy });
+}
@@ -0,0 +1,66 @@
+/**
+ * Alert Resolution Simulator
+ *
+ * Wrapper around the Tauri command that works out which alert and
+ * variation the server would pick for an event — the same steps as
+ * `findMatchingAlert` — and explains each decision, without queueing
+ * anything.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { AlertType } from "./alertApi";
+import type { ConditionEvent } from "./conditions";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export type AlertOutcome = "disabled" | "belowMinAmount" | "selected" | "notReached";
+
+export type VariationOutcome = "disabled" | "selected" | "shadowed" | "noMatch" | "invalid";
+
+export interface VariationTrace {
+  id: string;
+  name: string;
+  priority: number;
+  conditionType: string;
+  conditionValue: string;
+  outcome: VariationOutcome;
+  reason: string;
+}
+
+export interface AlertTrace {
+  id: string;
+  name: string;
+  minAmount: number | null;
+  outcome: AlertOutcome;
+  reason: string;
+  /** Highest priority first; only filled in for the selected alert */
+  variations: VariationTrace[];
+}
+
+export interface ResolutionTrace {
+  eventType: string;
+  eventData: ConditionEvent;
+  /** Enabled alerts in the order they are tried, then disabled ones */
+  alerts: AlertTrace[];
+  alertId: string | null;
+  variationId: string | null;
+  /**
+   * The alert row with the selected variation's overrides merged in (plus
+   * `_variation_id` / `_variation_name`), or null if no alert would fire
+   */
+  config: Record<string, unknown> | null;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/** e.g. `simulateAlert("cheer", { username: "viewer1", amount: 250 })` */
+export async function simulateAlert(
+  eventType: AlertType,
+  eventData: ConditionEvent = {}
+): Promise<ResolutionTrace> {
+  return invoke<ResolutionTrace>("simulate_alert", { eventType, eventData });
+}
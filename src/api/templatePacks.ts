@@ -0,0 +1,91 @@
+/**
+ * Template Packs
+ *
+ * Wrappers around the Tauri commands that bundle alert templates with
+ * their sounds and images into a single zip archive (`pack.json` plus
+ * `media/sounds/*` and `media/images/*`), validate such packs and import
+ * them as user templates.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/** A problem found while validating a pack. */
+export interface PackIssue {
+  /** Index into the pack's templates, or null for pack-level problems */
+  template: number | null;
+  /** e.g. `template_data.sound_volume` */
+  field: string;
+  message: string;
+}
+
+export interface PackReport {
+  name: string;
+  description: string | null;
+  author: string | null;
+  templateNames: string[];
+  mediaFiles: number;
+  /** Empty if the pack is valid */
+  issues: PackIssue[];
+}
+
+export interface PackInfo {
+  name: string;
+  description?: string | null;
+  /** Used for templates that don't name an author */
+  author?: string | null;
+}
+
+export interface PackImportReport {
+  pack: string;
+  /** The new user templates */
+  templates: { id: string; name: string }[];
+  /** Media files copied into the uploads folder */
+  mediaStored: number;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/**
+ * Validate a pack archive or unpacked pack folder. Opens a file picker when
+ * `path` is omitted and resolves to null if it was cancelled.
+ */
+export async function validateTemplatePack(path?: string): Promise<PackReport | null> {
+  return invoke<PackReport | null>("validate_template_pack", { path: path ?? null });
+}
+
+/** Zip the pack folder `dir` into the archive `dest`. */
+export async function packTemplatePack(dir: string, dest: string): Promise<PackReport> {
+  return invoke<PackReport>("pack_template_pack", { dir, dest });
+}
+
+/** Extract the pack archive `path` into the folder `destDir`. */
+export async function unpackTemplatePack(path: string, destDir: string): Promise<PackReport> {
+  return invoke<PackReport>("unpack_template_pack", { path, destDir });
+}
+
+/**
+ * Pack stored templates and their media into `path`, or a location picked
+ * in a save dialog when omitted. Resolves to null if the dialog was
+ * cancelled; nothing is written when the report lists issues.
+ */
+export async function exportTemplatePack(
+  templateIds: string[],
+  info: PackInfo,
+  path?: string
+): Promise<PackReport | null> {
+  return invoke<PackReport | null>("export_template_pack", {
+    templateIds,
+    info,
+    path: path ?? null,
+  });
+}
+
+export async function importTemplatePack(path: string): Promise<PackImportReport> {
+  return invoke<PackImportReport>("import_template_pack", { path });
+}
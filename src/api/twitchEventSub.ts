@@ -0,0 +1,80 @@
+/**
+ * Twitch EventSub
+ *
+ * Wrappers around the Tauri commands for the shell's EventSub WebSocket
+ * client, which subscribes to follows, subs, cheers and raids and feeds
+ * them into the event pipeline as `twitch` events. Status changes are also
+ * emitted as `eventsub://status`.
+ *
+ * To test without Twitch, run the Twitch CLI's mock server
+ * (`twitch event websocket start-server`) and point `websocketUrl` at
+ * `ws://127.0.0.1:8080/ws` and `apiUrl` at `http://127.0.0.1:8080`.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+export type SubscriptionType =
+  | "channel.follow"
+  | "channel.subscribe"
+  | "channel.cheer"
+  | "channel.raid";
+
+export interface EventSubSettings {
+  enabled: boolean;
+  websocketUrl: string;
+  /** Helix base URL, without `/eventsub/subscriptions` */
+  apiUrl: string;
+  clientId: string;
+  /** Broadcaster's user access token */
+  accessToken: string;
+  broadcasterId: string;
+  subscriptionTypes: SubscriptionType[];
+}
+
+export type ConnectionState =
+  | "disabled"
+  | "connecting"
+  | "connected"
+  | "disconnected";
+
+export interface SubscriptionStatus {
+  subscriptionType: SubscriptionType;
+  version: string;
+  id: string | null;
+  /** "enabled", "failed", or the revocation reason */
+  status: string;
+  error: string | null;
+}
+
+export interface EventSubStatus {
+  state: ConnectionState;
+  sessionId: string | null;
+  keepaliveSecs: number | null;
+  subscriptions: SubscriptionStatus[];
+  /** Notifications received since the client started */
+  notifications: number;
+  lastError: string | null;
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+export async function getEventSubSettings(): Promise<EventSubSettings> {
+  return invoke<EventSubSettings>("get_eventsub_settings");
+}
+
+/** Saves the settings and reconnects (or disconnects) the client. */
+export async function setEventSubSettings(
+  settings: EventSubSettings
+): Promise<EventSubSettings> {
+  return invoke<EventSubSettings>("set_eventsub_settings", { settings });
+}
+
+export async function getEventSubStatus(): Promise<EventSubStatus> {
+  return invoke<EventSubStatus>("get_eventsub_status");
+}
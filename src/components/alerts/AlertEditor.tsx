@@ -27,6 +27,10 @@ import {
 } from "lucide-react";
 import type { Alert, AlertType, AlertInput } from "../../api/alertApi";
 import { createTemplate } from "../../api/templateApi";
+import {
+  checkMessageTemplate,
+  renderMessageTemplate,
+} from "../../api/messageTemplates";
 import type { TemplateData } from "../../api/templateApi";
 
 import AlertPreview from "./AlertPreview";
@@ -337,6 +341,40 @@ export default function AlertEditor({
   // Watch all values for the preview component
   const watchedValues = watch();
 
+  // Check the message template as it is typed and render it with sample
+  // values, using the same engine the server renders live alerts with
+  const [templateError, setTemplateError] = useState<string | null>(null);
+  const [templateWarning, setTemplateWarning] = useState<string | null>(null);
+  const [renderedSample, setRenderedSample] = useState<string | null>(null);
+  const messageTemplate = watchedValues.message_template;
+  const alertType = watchedValues.type;
+
+  useEffect(() => {
+    const timer = setTimeout(async () => {
+      try {
+        const check = await checkMessageTemplate(messageTemplate);
+        setTemplateError(check.errors[0]?.message ?? null);
+        setTemplateWarning(check.warnings[0]?.message ?? null);
+        setRenderedSample(
+          check.valid
+            ? await renderMessageTemplate(messageTemplate, {
+                username: "PreviewUser",
+                amount:
+                  alertType === "cheer" ? 100 : alertType === "donation" ? 5 : null,
+                message: "",
+                type: alertType,
+              })
+            : null
+        );
+      } catch {
+        setTemplateError(null);
+        setTemplateWarning(null);
+        setRenderedSample(null);
+      }
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [messageTemplate, alertType]);
+
   // Sync dirty state to parent
   useEffect(() => {
     onDirtyChange?.(isDirty);
@@ -581,8 +619,11 @@ export default function AlertEditor({
 
         <Field
           label="Message Template"
-          error={errors.message_template?.message}
-          hint="Variables: {username}, {amount}, {message}"
+          error={errors.message_template?.message ?? templateError ?? undefined}
+          hint={
+            templateWarning ??
+            "Variables: {username}, {amount}, {message}, {type}. Filters like {amount|number} and blocks like {#if amount >= 100}...{/if} are supported."
+          }
         >
           <textarea
             {...register("message_template")}
@@ -590,6 +631,11 @@ export default function AlertEditor({
             placeholder="{username} just followed!"
             className="w-full resize-y rounded-lg border border-panel-border bg-panel-bg px-3 py-2 text-sm text-gray-200 outline-none transition-colors placeholder:text-gray-600 focus:border-sf-primary"
           />
+          {renderedSample !== null && (
+            <p className="mt-1 text-xs text-gray-400">
+              Preview: {renderedSample || "(empty)"}
+            </p>
+          )}
         </Field>
       </section>
 
@@ -64,22 +64,12 @@ export default function AlertPreview({
       const previewAmount =
         previewType === "cheer" ? 100 : previewType === "donation" ? 5 : null;
 
-      // Build a preview message by filling in the template variables,
-      // so TTS reads exactly what appears on screen.
-      const previewMessage = formValues.tts_enabled
-        ? (formValues.message_template || "{username} triggered an alert!")
-            .replace(/\{username\}/g, previewUsername)
-            .replace(/\{amount\}/g, previewAmount != null ? String(previewAmount) : "")
-            .replace(/\{message\}/g, "")
-            .trim()
-        : null;
-
       await triggerTestAlertQueue({
         type: previewType,
         username: previewUsername,
         displayName: previewUsername,
         amount: previewAmount,
-        message: previewMessage,
+        message: null,
         config: {
           message_template: formValues.message_template || "{username} triggered an alert!",
           duration_ms: formValues.duration_ms || 5000,
@@ -2,14 +2,14 @@
  * VariationEditor — Modal form for creating or editing an alert variation.
  *
  * Provides fields for:
- *   - Name, condition type/value, priority, enabled toggle
+ *   - Name, condition expression, priority, enabled toggle
  *   - Override fields (sound, image, animation, message, custom CSS)
  *     with clear "leave empty to use parent" messaging
  *
  * Reuses SoundPicker and ImagePicker from the parent alert editor.
  */
 
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import * as Dialog from "@radix-ui/react-dialog";
 import * as Switch from "@radix-ui/react-switch";
 import * as Slider from "@radix-ui/react-slider";
@@ -21,6 +21,7 @@ import type {
   AnimationIn,
   AnimationOut,
 } from "../../api/alertApi";
+import { checkVariationCondition } from "../../api/conditions";
 import SoundPicker from "./SoundPicker";
 import ImagePicker from "./ImagePicker";
 
@@ -31,7 +32,7 @@ import ImagePicker from "./ImagePicker";
 interface VariationEditorProps {
   /** Existing variation to edit, or partial object for creation. */
   variation: Partial<AlertVariation>;
-  /** Parent alert type — used to configure condition options. */
+  /** Parent alert type — used to suggest condition examples. */
   alertType: AlertType;
   /** Called when the user saves. Should throw on error. */
   onSave: (data: AlertVariationInput) => Promise<void>;
@@ -43,29 +44,14 @@ interface VariationEditorProps {
 // Constants
 // ---------------------------------------------------------------------------
 
-const CONDITION_OPTIONS: Record<
-  string,
-  { value: AlertVariation["condition_type"]; label: string }[]
-> = {
-  subscribe: [
-    { value: "tier", label: "Subscription Tier" },
-    { value: "amount", label: "Amount" },
-    { value: "custom", label: "Custom" },
-  ],
-  cheer: [
-    { value: "amount", label: "Bit Amount" },
-    { value: "custom", label: "Custom" },
-  ],
-  donation: [
-    { value: "amount", label: "Donation Amount" },
-    { value: "custom", label: "Custom" },
-  ],
-  raid: [
-    { value: "amount", label: "Raider Count" },
-    { value: "custom", label: "Custom" },
-  ],
-  follow: [{ value: "custom", label: "Custom" }],
-  custom: [{ value: "custom", label: "Custom" }],
+/** Example expressions shown as the condition placeholder. */
+const CONDITION_EXAMPLES: Record<string, string> = {
+  subscribe: "tier == 3",
+  cheer: "amount between 100 and 499",
+  donation: "amount >= 50",
+  raid: "amount >= 20",
+  follow: 'username in ["alice", "bob"]',
+  custom: 'message contains "hype"',
 };
 
 const ANIMATIONS_IN: { value: AnimationIn; label: string }[] = [
@@ -86,42 +72,23 @@ const ANIMATIONS_OUT: { value: AnimationOut; label: string }[] = [
 // Helpers
 // ---------------------------------------------------------------------------
 
-function getConditionValueLabel(conditionType: string): string {
-  switch (conditionType) {
-    case "tier":
-      return "Tier Number";
-    case "amount":
-      return "Minimum Amount";
-    case "custom":
-      return "Custom Value";
-    default:
-      return "Value";
-  }
-}
-
-function getConditionValuePlaceholder(conditionType: string): string {
-  switch (conditionType) {
-    case "tier":
-      return "e.g. 1, 2, or 3";
-    case "amount":
-      return "e.g. 100, 500, 1000";
-    case "custom":
-      return "e.g. fieldName:value";
-    default:
-      return "";
-  }
-}
-
-function getConditionValueHint(conditionType: string): string {
+/**
+ * The expression equivalent to a pre-expression condition, matching the
+ * conversion in migration 006.
+ */
+function toExpression(conditionType: string | undefined, value: string): string {
+  const quoted = `"${value.replace(/\\/g, "\\\\").replace(/"/g, '\\"')}"`;
   switch (conditionType) {
     case "tier":
-      return "Exact tier match (1 = Tier 1, 2 = Tier 2, 3 = Tier 3)";
+      return `tier == ${quoted}`;
     case "amount":
-      return "Triggers when the amount is >= this value";
+      return /^[0-9]+(\.[0-9]+)?$/.test(value.trim())
+        ? `amount >= ${value.trim()}`
+        : `amount >= ${quoted}`;
     case "custom":
-      return "Free-form condition for custom matching";
+      return `custom_value == ${quoted}`;
     default:
-      return "";
+      return value;
   }
 }
 
@@ -139,12 +106,10 @@ export default function VariationEditor({
 
   // Form state — start from the variation data
   const [name, setName] = useState(variation.name ?? "");
-  const [conditionType, setConditionType] = useState<
-    AlertVariation["condition_type"]
-  >(variation.condition_type ?? "custom");
-  const [conditionValue, setConditionValue] = useState(
-    variation.condition_value ?? ""
+  const [condition, setCondition] = useState(
+    toExpression(variation.condition_type, variation.condition_value ?? "")
   );
+  const [conditionError, setConditionError] = useState<string | null>(null);
   const [priority, setPriority] = useState(variation.priority ?? 1);
   const [enabled, setEnabled] = useState(variation.enabled !== 0);
 
@@ -187,7 +152,19 @@ export default function VariationEditor({
     )
   );
 
-  const conditionOptions = CONDITION_OPTIONS[alertType] ?? CONDITION_OPTIONS.custom;
+  // Check the expression as it is typed
+  useEffect(() => {
+    if (!condition.trim()) {
+      setConditionError(null);
+      return;
+    }
+    const timer = setTimeout(() => {
+      checkVariationCondition(condition)
+        .then((check) => setConditionError(check.error?.message ?? null))
+        .catch(() => setConditionError(null));
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [condition]);
 
   // -----------------------------------------------------------------------
   // Handlers
@@ -202,15 +179,19 @@ export default function VariationEditor({
       setSaveError("Name is required");
       return;
     }
-    if (!conditionValue.trim()) {
-      setSaveError("Condition value is required");
+    if (!condition.trim()) {
+      setSaveError("Condition is required");
+      return;
+    }
+    if (conditionError) {
+      setSaveError(`Invalid condition: ${conditionError}`);
       return;
     }
 
     const data: AlertVariationInput = {
       name: name.trim(),
-      condition_type: conditionType,
-      condition_value: conditionValue.trim(),
+      condition_type: "expression",
+      condition_value: condition.trim(),
       priority,
       enabled: enabled ? 1 : 0,
       message_template: messageTemplate,
@@ -277,44 +258,32 @@ export default function VariationEditor({
                   />
                 </div>
 
-                {/* Condition Type */}
-                <div>
-                  <label className="mb-1.5 block text-sm font-medium text-gray-300">
-                    Condition Type
-                  </label>
-                  <select
-                    value={conditionType}
-                    onChange={(e) =>
-                      setConditionType(
-                        e.target.value as AlertVariation["condition_type"]
-                      )
-                    }
-                    className="w-full rounded-lg border border-panel-border bg-panel-bg px-3 py-2 text-sm text-gray-200 outline-none transition-colors focus:border-sf-primary"
-                  >
-                    {conditionOptions.map((opt) => (
-                      <option key={opt.value} value={opt.value}>
-                        {opt.label}
-                      </option>
-                    ))}
-                  </select>
-                </div>
-
-                {/* Condition Value */}
-                <div>
+                {/* Condition */}
+                <div className="sm:col-span-2">
                   <label className="mb-1.5 block text-sm font-medium text-gray-300">
-                    {getConditionValueLabel(conditionType)}
+                    Condition
                   </label>
                   <input
-                    type={conditionType === "tier" || conditionType === "amount" ? "number" : "text"}
-                    value={conditionValue}
-                    onChange={(e) => setConditionValue(e.target.value)}
-                    placeholder={getConditionValuePlaceholder(conditionType)}
-                    min={conditionType === "tier" ? 1 : conditionType === "amount" ? 0 : undefined}
-                    className="w-full rounded-lg border border-panel-border bg-panel-bg px-3 py-2 text-sm text-gray-200 outline-none transition-colors placeholder:text-gray-600 focus:border-sf-primary"
+                    type="text"
+                    value={condition}
+                    onChange={(e) => setCondition(e.target.value)}
+                    placeholder={`e.g. ${CONDITION_EXAMPLES[alertType] ?? CONDITION_EXAMPLES.custom}`}
+                    spellCheck={false}
+                    className={`w-full rounded-lg border bg-panel-bg px-3 py-2 font-mono text-sm text-gray-200 outline-none transition-colors placeholder:text-gray-600 ${
+                      conditionError
+                        ? "border-red-500 focus:border-red-500"
+                        : "border-panel-border focus:border-sf-primary"
+                    }`}
                   />
-                  <p className="mt-1 text-xs text-gray-500">
-                    {getConditionValueHint(conditionType)}
-                  </p>
+                  {conditionError ? (
+                    <p className="mt-1 text-xs text-red-400">{conditionError}</p>
+                  ) : (
+                    <p className="mt-1 text-xs text-gray-500">
+                      Fields: username, amount, message, tier, platform. Combine
+                      ==, !=, &gt;, &gt;=, &lt;, &lt;=, between … and …, contains,
+                      in [...] with and, or, not.
+                    </p>
+                  )}
                 </div>
 
                 {/* Priority */}
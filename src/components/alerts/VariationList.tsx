@@ -104,17 +104,9 @@ export default function VariationList({
   // -----------------------------------------------------------------------
 
   const handleCreate = () => {
-    // Smart defaults based on alert type
-    const defaultConditionType =
-      alertType === "subscribe"
-        ? "tier"
-        : alertType === "cheer" || alertType === "donation"
-          ? "amount"
-          : "custom";
-
     setEditingVariation({
       name: "",
-      condition_type: defaultConditionType,
+      condition_type: "expression",
       condition_value: "",
       priority: variations.length + 1,
       enabled: 1,
@@ -5,10 +5,18 @@
  *    needs a moment to start and print its port).
  * 2. Polls the /api/health endpoint until the server responds.
  * 3. Returns the connection status, server URL, and a retry function.
+ * 4. Reconnects automatically when the Tauri supervisor restarts a crashed
+ *    sidecar (`sidecar://restarted`), and reports an error if it gives up
+ *    (`sidecar://restart-limit`).
  */
 
 import { useState, useEffect, useCallback, useRef } from "react";
-import { getServerPort } from "../api/config";
+import { listen } from "@tauri-apps/api/event";
+import {
+  getServerPort,
+  getSidecarStatus,
+  resetServerPort,
+} from "../api/config";
 
 export type ConnectionStatus = "connecting" | "connected" | "error";
 
@@ -43,6 +51,16 @@ async function fetchPortWithRetry(
     try {
       return await getServerPort();
     } catch {
+      // No point waiting if the binary couldn't be launched at all, speaks
+      // a control protocol this app doesn't understand, or was stopped
+      const sidecar = await getSidecarStatus();
+      if (sidecar.state === "failedToSpawn" || sidecar.state === "incompatible") {
+        throw new Error(sidecar.reason);
+      }
+      if (sidecar.state === "stopped") {
+        throw new Error("The server was stopped. Start it again from the tray menu.");
+      }
+
       // Port not available yet -- the sidecar is still starting
       if (i < PORT_FETCH_RETRIES - 1) {
         await sleep(PORT_FETCH_DELAY);
@@ -130,6 +148,28 @@ export function useServerConnection(): ServerConnectionState {
     };
   }, [connect]);
 
+  // Follow the sidecar supervisor: reconnect on restart, fail on give-up
+  useEffect(() => {
+    const unlistenRestarted = listen("sidecar://restarted", () => {
+      resetServerPort();
+      connect();
+    });
+    const unlistenLimit = listen("sidecar://restart-limit", () => {
+      abortRef.current?.abort();
+      resetServerPort();
+      setServerUrl(null);
+      setError(
+        "The server crashed repeatedly and was not restarted. " +
+          "Restart StreamForge to try again."
+      );
+      setStatus("error");
+    });
+    return () => {
+      unlistenRestarted.then((unlisten) => unlisten());
+      unlistenLimit.then((unlisten) => unlisten());
+    };
+  }, [connect]);
+
   const retry = useCallback(() => {
     connect();
   }, [connect]);